@@ -0,0 +1,59 @@
+#![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
+
+use eframe::{egui, NativeOptions};
+
+use egui_dock::{ClosureTab, ClosureTabViewer, ClosureTree, DockArea};
+
+fn main() {
+    let options = NativeOptions::default();
+    eframe::run_native(
+        "My egui App",
+        options,
+        Box::new(|_cc| Box::new(MyApp::default())),
+    );
+}
+
+struct MyApp {
+    tree: ClosureTree,
+}
+
+impl Default for MyApp {
+    fn default() -> Self {
+        let mut counter = 0;
+        let counter_tab: ClosureTab = (
+            "Counter".into(),
+            Box::new(move |ui: &mut egui::Ui| {
+                ui.label(format!("Clicked {counter} times"));
+                if ui.button("Click me").clicked() {
+                    counter += 1;
+                }
+            }),
+        );
+        let hello_tab: ClosureTab = (
+            "Hello".into(),
+            Box::new(|ui: &mut egui::Ui| {
+                ui.label("Hello from a closure");
+            }),
+        );
+
+        let mut name = String::from("World");
+        let name_tab: ClosureTab = (
+            "Name".into(),
+            Box::new(move |ui: &mut egui::Ui| {
+                ui.text_edit_singleline(&mut name);
+                ui.label(format!("Hello, {name}!"));
+            }),
+        );
+
+        let mut tree = ClosureTree::new(vec![counter_tab, hello_tab]);
+        tree.split_left(tree.root().unwrap(), 0.3, vec![name_tab]);
+
+        Self { tree }
+    }
+}
+
+impl eframe::App for MyApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        DockArea::new(&mut self.tree).show(ctx, &mut ClosureTabViewer);
+    }
+}
@@ -6,7 +6,7 @@ use egui::{
     Id, LayerId, Slider, Ui, WidgetText,
 };
 
-use egui_dock::{DockArea, NodeIndex, Style, TabViewer, Tree};
+use egui_dock::{DockArea, Style, TabViewer, Tree};
 
 fn main() {
     let options = NativeOptions::default();
@@ -148,7 +148,7 @@ impl Default for MyApp {
         };
 
         let mut tree = Tree::new(vec!["Simple Demo".to_owned(), "Style Editor".to_owned()]);
-        let [a, b] = tree.split_left(NodeIndex::root(), 0.3, vec!["Inspector".to_owned()]);
+        let [a, b] = tree.split_left(tree.root().unwrap(), 0.3, vec!["Inspector".to_owned()]);
         let [_, _] = tree.split_below(
             a,
             0.7,
@@ -2,11 +2,12 @@
 
 use eframe::{egui, NativeOptions};
 use egui::{
-    style::Margin, text::LayoutJob, Align, Color32, FontId, Frame, TextFormat, TopBottomPanel, Ui,
-    Window,
+    style::Margin, text::LayoutJob, Align, Color32, FontId, Frame, Id, TextFormat, TopBottomPanel,
+    Ui, Window,
 };
 
-use egui_dock::{DockArea, DynamicTree, NodeIndex, Tab, TabBuilder};
+use egui_dock::{DockArea, DynamicTree, Tab, TabBuilder};
+use std::{cell::Cell, rc::Rc};
 
 fn main() {
     let options = NativeOptions::default();
@@ -19,16 +20,31 @@ fn main() {
 
 struct MyApp {
     tree: DynamicTree,
+    editors: usize,
 }
 
 impl Default for MyApp {
     fn default() -> Self {
-        let tab1 = Box::new(Editor::new("Text".into()));
-
-        let tab2 = TabBuilder::default()
-            .title("Tab 2")
-            .content(|ui| {
-                ui.label("Tab 2");
+        let tab1 = Box::new(Editor::new("Text".into(), 0));
+
+        // The content and the context menu of a tab are separate closures, which share state through an `Rc`.
+        let greetings = Rc::new(Cell::new(0));
+        let tab2 = TabBuilder::new("Tab 2")
+            .closeable(false)
+            .content({
+                let greetings = greetings.clone();
+                move |ui| {
+                    ui.label("Tab 2");
+                    if greetings.get() > 0 {
+                        ui.label(format!("Said hello {} times", greetings.get()));
+                    }
+                }
+            })
+            .context_menu(move |ui| {
+                if ui.button("Say hello").clicked() {
+                    greetings.set(greetings.get() + 1);
+                    ui.close_menu();
+                }
             })
             .build();
         let tab3 = TabBuilder::default()
@@ -53,11 +69,11 @@ impl Default for MyApp {
         let mut tree = DynamicTree::new(vec![tab1, tab2]);
 
         // You can modify the tree before constructing the dock
-        let [a, b] = tree.split_left(NodeIndex::root(), 0.3, vec![tab3]);
+        let [a, b] = tree.split_left(tree.root().unwrap(), 0.3, vec![tab3]);
         let [_, _] = tree.split_below(a, 0.7, vec![tab4]);
         let [_, _] = tree.split_below(b, 0.5, vec![tab5]);
 
-        Self { tree }
+        Self { tree, editors: 1 }
     }
 }
 
@@ -67,15 +83,18 @@ impl eframe::App for MyApp {
             .frame(Frame::none().inner_margin(Margin::same(2.0)))
             .show(ctx, |ui| {
                 if ui.button("Add Editor").clicked() {
-                    self.tree
-                        .push_to_focused_leaf(Box::new(Editor::new("New Text".into())));
+                    let editor = Editor::new("New Text".into(), self.editors);
+                    self.editors += 1;
+                    self.tree.push_to_focused_leaf(Box::new(editor));
                 }
             });
-        DockArea::new(&mut self.tree).show(ctx, &mut egui_dock::DynamicTabViewer {});
+        DockArea::new(&mut self.tree).show(ctx, &mut egui_dock::DynamicTabViewer::default());
     }
 }
 
 struct Editor {
+    /// Tells the editors apart, their names can repeat and their titles change once they're modified.
+    id: usize,
     name: String,
     modified: bool,
     text: String,
@@ -84,8 +103,9 @@ struct Editor {
 }
 
 impl Editor {
-    pub fn new(name: String) -> Self {
+    pub fn new(name: String, id: usize) -> Self {
         Self {
+            id,
             name,
             modified: false,
             text: "Important text to edit".into(),
@@ -172,6 +192,10 @@ impl Tab for Editor {
         }
     }
 
+    fn id(&mut self) -> Id {
+        Id::new(("editor", self.id))
+    }
+
     fn on_close(&mut self) -> bool {
         self.show_save = true;
         self.exit || !self.modified
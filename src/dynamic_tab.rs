@@ -1,27 +1,84 @@
+use std::marker::PhantomData;
+
 use egui::style::Margin;
-use egui::{Ui, WidgetText};
+use egui::{Color32, Id, TextureId, Ui, WidgetText};
+
+use crate::TabIcon;
 
 pub type TabContent = Box<dyn FnMut(&mut Ui) + 'static>;
 pub type OnClose = Box<dyn FnMut() -> bool + 'static>;
 pub type ForceClose = Box<dyn FnMut() -> bool + 'static>;
-
+pub type OnBackground = Box<dyn FnMut() + 'static>;
+pub type ContextMenu = Box<dyn FnMut(&mut Ui) + 'static>;
+
+/// Builds a [`Tab`] out of a title, its content and options, without implementing the trait.
+///
+/// ```
+/// # use egui_dock::{DynamicTree, TabBuilder};
+/// let log = TabBuilder::new("Log")
+///     .closeable(false)
+///     .content(|ui| {
+///         ui.label("Nothing logged yet");
+///     })
+///     .context_menu(|ui| {
+///         if ui.button("Clear").clicked() {
+///             ui.close_menu();
+///         }
+///     })
+///     .build();
+/// let tree = DynamicTree::new(vec![log]);
+/// ```
 pub struct TabBuilder {
     title: Option<WidgetText>,
+    id: Option<Id>,
     inner_margin: Margin,
     add_content: Option<TabContent>,
     on_close: Option<OnClose>,
     force_close: Option<ForceClose>,
+    on_background: Option<OnBackground>,
     clear_background: Option<bool>,
+    inner_background: Option<Color32>,
+    render_when_inactive: bool,
+    closeable: bool,
+    icon: Option<TabIcon>,
+    group: Option<String>,
+    tooltip: Option<WidgetText>,
+    context_menu: Option<ContextMenu>,
+    pinned: bool,
 }
 
 /// Dockable tab that can be used in [`crate::Tree`]s.
 pub trait Tab {
     /// Actual tab content.
+    ///
+    /// Only called while the tab is the active tab of its leaf, unless [`Tab::render_when_inactive`] returns `true`.
     fn ui(&mut self, ui: &mut Ui);
 
     /// The title to be displayed.
     fn title(&mut self) -> WidgetText;
 
+    /// Whether the title changed since the last frame.
+    ///
+    /// See [`TabViewer::title_changed`](crate::TabViewer::title_changed) for more detail.
+    fn title_changed(&mut self) -> bool {
+        true
+    }
+
+    /// Shows the title of the tab in its tab bar. Return `true` if you showed something, otherwise [`Tab::title`] is
+    /// shown.
+    ///
+    /// See [`TabViewer::tab_title_ui`](crate::TabViewer::tab_title_ui) for more detail.
+    fn tab_title_ui(&mut self, _ui: &mut Ui) -> bool {
+        false
+    }
+
+    /// Unique id for this tab. By default it's derived from the title, override it if titles can repeat or change.
+    ///
+    /// See [`TabViewer::id`](crate::TabViewer::id) for more detail.
+    fn id(&mut self) -> Id {
+        Id::new(self.title().text())
+    }
+
     /// This is called when the tabs close button is pressed.
     ///
     /// Returns `true` if the tab should close immediately, `false` otherwise.
@@ -49,15 +106,130 @@ pub trait Tab {
     fn clear_background(&self) -> bool {
         true
     }
+
+    /// Color the body of the tab is cleared with instead of the style's.
+    ///
+    /// See [`TabViewer::inner_background`](crate::TabViewer::inner_background) for more detail.
+    fn inner_background(&self) -> Option<Color32> {
+        None
+    }
+
+    /// Whether `ui` should be called even while the tab isn't active.
+    ///
+    /// See [`TabViewer::render_when_inactive`](crate::TabViewer::render_when_inactive) for more detail.
+    fn render_when_inactive(&self) -> bool {
+        false
+    }
+
+    /// This is called every frame while the tab's `ui` isn't.
+    ///
+    /// See [`TabViewer::on_background`](crate::TabViewer::on_background) for more detail.
+    fn on_background(&mut self) {}
+
+    /// Height of the toolbar shown between the tab bar and the tab's body.
+    ///
+    /// See [`TabViewer::toolbar_height`](crate::TabViewer::toolbar_height) for more detail.
+    fn toolbar_height(&self) -> f32 {
+        0.0
+    }
+
+    /// Shows the toolbar of the tab.
+    fn toolbar(&mut self, _ui: &mut Ui) {}
+
+    /// This is called when the tab becomes the active tab of its leaf. Return `true` if it takes keyboard focus.
+    ///
+    /// See [`TabViewer::on_activate`](crate::TabViewer::on_activate) for more detail.
+    fn on_activate(&mut self) -> bool {
+        false
+    }
+
+    /// Returns a texture holding a picture of the content of the tab.
+    ///
+    /// See [`TabViewer::preview_texture`](crate::TabViewer::preview_texture) for more detail.
+    fn preview_texture(&mut self) -> Option<TextureId> {
+        None
+    }
+
+    /// Whether the tab can be closed by the user.
+    ///
+    /// See [`TabViewer::closeable`](crate::TabViewer::closeable) for more detail.
+    fn closeable(&self) -> bool {
+        true
+    }
+
+    /// Whether the tab has unsaved changes.
+    ///
+    /// See [`TabViewer::is_modified`](crate::TabViewer::is_modified) for more detail.
+    fn is_modified(&self) -> bool {
+        false
+    }
+
+    /// Adds entries of your own to the menu opened by right-clicking the title of the tab.
+    ///
+    /// See [`TabViewer::context_menu`](crate::TabViewer::context_menu) for more detail.
+    fn context_menu(&mut self, _ui: &mut Ui) {}
+
+    /// This is called when the title of the tab is double-clicked.
+    ///
+    /// See [`TabViewer::on_tab_double_click`](crate::TabViewer::on_tab_double_click) for more detail.
+    fn on_tab_double_click(&mut self) {}
+
+    /// Shows the tooltip of the tab.
+    ///
+    /// See [`TabViewer::tab_tooltip_ui`](crate::TabViewer::tab_tooltip_ui) for more detail.
+    fn tab_tooltip_ui(&self, _ui: &mut Ui) {}
+
+    /// Returns the icon shown before the title of the tab.
+    ///
+    /// See [`TabViewer::tab_icon`](crate::TabViewer::tab_icon) for more detail.
+    fn tab_icon(&self) -> Option<TabIcon> {
+        None
+    }
+
+    /// Returns the name of the group the tab belongs to.
+    ///
+    /// See [`TabViewer::group`](crate::TabViewer::group) for more detail.
+    fn group(&self) -> Option<&str> {
+        None
+    }
+
+    /// Returns the text of the badge shown next to the title.
+    ///
+    /// See [`TabViewer::badge`](crate::TabViewer::badge) for more detail.
+    fn badge(&self) -> Option<WidgetText> {
+        None
+    }
+
+    /// Whether the tab is pinned.
+    ///
+    /// See [`TabViewer::is_pinned`](crate::TabViewer::is_pinned) for more detail.
+    fn is_pinned(&self) -> bool {
+        false
+    }
+
+    /// This is called when the tab is pinned or unpinned from the menu of its title.
+    ///
+    /// See [`TabViewer::set_pinned`](crate::TabViewer::set_pinned) for more detail.
+    fn set_pinned(&mut self, _pinned: bool) {}
 }
 
 pub struct BuiltTab {
     pub title: WidgetText,
+    id: Option<Id>,
     pub inner_margin: Margin,
     pub add_content: TabContent,
     on_close: Option<OnClose>,
     force_close: Option<ForceClose>,
+    on_background: Option<OnBackground>,
     clear_background: bool,
+    inner_background: Option<Color32>,
+    render_when_inactive: bool,
+    closeable: bool,
+    icon: Option<TabIcon>,
+    group: Option<String>,
+    tooltip: Option<WidgetText>,
+    context_menu: Option<ContextMenu>,
+    pinned: bool,
 }
 
 impl Tab for BuiltTab {
@@ -69,6 +241,10 @@ impl Tab for BuiltTab {
         self.title.clone()
     }
 
+    fn id(&mut self) -> Id {
+        self.id.unwrap_or_else(|| Id::new(self.title.text()))
+    }
+
     fn on_close(&mut self) -> bool {
         match &mut self.on_close {
             Some(on_close) => on_close(),
@@ -90,17 +266,73 @@ impl Tab for BuiltTab {
     fn clear_background(&self) -> bool {
         self.clear_background
     }
+
+    fn inner_background(&self) -> Option<Color32> {
+        self.inner_background
+    }
+
+    fn render_when_inactive(&self) -> bool {
+        self.render_when_inactive
+    }
+
+    fn on_background(&mut self) {
+        if let Some(on_background) = &mut self.on_background {
+            on_background();
+        }
+    }
+
+    fn closeable(&self) -> bool {
+        self.closeable
+    }
+
+    fn tab_icon(&self) -> Option<TabIcon> {
+        self.icon.clone()
+    }
+
+    fn group(&self) -> Option<&str> {
+        self.group.as_deref()
+    }
+
+    fn tab_tooltip_ui(&self, ui: &mut Ui) {
+        if let Some(tooltip) = &self.tooltip {
+            ui.label(tooltip.clone());
+        }
+    }
+
+    fn context_menu(&mut self, ui: &mut Ui) {
+        if let Some(context_menu) = &mut self.context_menu {
+            context_menu(ui);
+        }
+    }
+
+    fn is_pinned(&self) -> bool {
+        self.pinned
+    }
+
+    fn set_pinned(&mut self, pinned: bool) {
+        self.pinned = pinned;
+    }
 }
 
 impl Default for TabBuilder {
     fn default() -> Self {
         Self {
             title: None,
+            id: None,
             inner_margin: Margin::same(4.0),
             add_content: None,
             on_close: None,
             force_close: None,
+            on_background: None,
             clear_background: None,
+            inner_background: None,
+            render_when_inactive: false,
+            closeable: true,
+            icon: None,
+            group: None,
+            tooltip: None,
+            context_menu: None,
+            pinned: false,
         }
     }
 }
@@ -113,20 +345,43 @@ impl TabBuilder {
     pub fn build(self) -> Box<dyn Tab> {
         Box::new(BuiltTab {
             title: self.title.expect("Missing tab title"),
+            id: self.id,
             inner_margin: self.inner_margin,
             add_content: self.add_content.expect("Missing tab content"),
             on_close: self.on_close,
             force_close: self.force_close,
+            on_background: self.on_background,
             clear_background: self.clear_background.unwrap_or(true),
+            inner_background: self.inner_background,
+            render_when_inactive: self.render_when_inactive,
+            closeable: self.closeable,
+            icon: self.icon,
+            group: self.group,
+            tooltip: self.tooltip,
+            context_menu: self.context_menu,
+            pinned: self.pinned,
         })
     }
 
+    /// Starts building a tab with the given `title`, the same as `TabBuilder::default().title(title)`.
+    pub fn new(title: impl Into<WidgetText>) -> Self {
+        Self::default().title(title)
+    }
+
     /// Sets the text displayed in the tab bar.
     pub fn title(mut self, title: impl Into<WidgetText>) -> Self {
         self.title = Some(title.into());
         self
     }
 
+    /// Sets the tab's unique id. By default it's derived from the title.
+    ///
+    /// See [`Tab::id`] for more detail
+    pub fn id(mut self, id: impl std::hash::Hash) -> Self {
+        self.id = Some(Id::new(id));
+        self
+    }
+
     /// Sets the margins around the tab's content.
     pub fn inner_margin(mut self, margin: Margin) -> Self {
         self.inner_margin = margin;
@@ -168,6 +423,78 @@ impl TabBuilder {
         self.clear_background = Some(clear_background);
         self
     }
+
+    /// Sets the color the tab's body is cleared with instead of the style's.
+    ///
+    /// See [`Tab::inner_background`] for more detail
+    pub fn inner_background(mut self, color: Color32) -> Self {
+        self.inner_background = Some(color);
+        self
+    }
+
+    /// Whether the tab can be closed by the user. By default it can.
+    ///
+    /// See [`Tab::closeable`] for more detail
+    pub fn closeable(mut self, closeable: bool) -> Self {
+        self.closeable = closeable;
+        self
+    }
+
+    /// Sets the icon shown before the title.
+    ///
+    /// See [`Tab::tab_icon`] for more detail
+    pub fn icon(mut self, icon: TabIcon) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    /// Puts the tab in the group named `group`.
+    ///
+    /// See [`Tab::group`] for more detail
+    pub fn group(mut self, group: impl Into<String>) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+
+    /// Sets the text of the tooltip shown when the pointer rests on the title.
+    ///
+    /// See [`Tab::tab_tooltip_ui`] for more detail
+    pub fn tooltip(mut self, tooltip: impl Into<WidgetText>) -> Self {
+        self.tooltip = Some(tooltip.into());
+        self
+    }
+
+    /// Sets the function that adds entries to the menu opened by right-clicking the title.
+    ///
+    /// See [`Tab::context_menu`] for more detail
+    pub fn context_menu(mut self, context_menu: impl FnMut(&mut Ui) + 'static) -> Self {
+        self.context_menu = Some(Box::new(context_menu));
+        self
+    }
+
+    /// Whether the tab starts out pinned. By default it doesn't.
+    ///
+    /// See [`Tab::is_pinned`] for more detail
+    pub fn pinned(mut self, pinned: bool) -> Self {
+        self.pinned = pinned;
+        self
+    }
+
+    /// Whether the tab's content keeps being run while the tab isn't active.
+    ///
+    /// See [`Tab::render_when_inactive`] for more detail
+    pub fn render_when_inactive(mut self, render_when_inactive: bool) -> Self {
+        self.render_when_inactive = render_when_inactive;
+        self
+    }
+
+    /// Sets the function that is called every frame while the tab's content isn't shown.
+    ///
+    /// See [`Tab::on_background`] for more detail
+    pub fn on_background(mut self, on_background: impl FnMut() + 'static) -> Self {
+        self.on_background = Some(Box::new(on_background));
+        self
+    }
 }
 
 // ----------------------------------------------------------------------------
@@ -175,12 +502,24 @@ impl TabBuilder {
 /// A type-def for when using [`Tab`] or [`TabBuilder`].
 pub type DynamicTree = crate::Tree<Box<dyn Tab>>;
 
-/// For use with [`crate::DockArea::show`] when using [`DynamicTree`].
-#[derive(Default)]
-pub struct DynamicTabViewer {}
+/// A type-def for when using your own [`Tab`] implementations which are `Send + Sync`.
+///
+/// Unlike a [`DynamicTree`], it can be moved to other threads or shared through an `Arc<Mutex<_>>`.
+pub type SendDynamicTree = crate::Tree<Box<dyn Tab + Send + Sync>>;
 
-impl crate::TabViewer for DynamicTabViewer {
-    type Tab = Box<dyn Tab>;
+/// For use with [`crate::DockArea::show`] when using [`DynamicTree`] or [`SendDynamicTree`].
+pub struct DynamicTabViewer<T: ?Sized = dyn Tab> {
+    tab: PhantomData<fn() -> Box<T>>,
+}
+
+impl<T: ?Sized> Default for DynamicTabViewer<T> {
+    fn default() -> Self {
+        Self { tab: PhantomData }
+    }
+}
+
+impl<T: Tab + ?Sized> crate::TabViewer for DynamicTabViewer<T> {
+    type Tab = Box<T>;
 
     fn ui(&mut self, ui: &mut Ui, tab: &mut Self::Tab) {
         tab.ui(ui)
@@ -190,6 +529,18 @@ impl crate::TabViewer for DynamicTabViewer {
         tab.title()
     }
 
+    fn title_changed(&mut self, tab: &mut Self::Tab) -> bool {
+        tab.title_changed()
+    }
+
+    fn tab_title_ui(&mut self, ui: &mut Ui, tab: &mut Self::Tab) -> bool {
+        tab.tab_title_ui(ui)
+    }
+
+    fn id(&mut self, tab: &mut Self::Tab) -> Id {
+        tab.id()
+    }
+
     fn on_close(&mut self, tab: &mut Self::Tab) -> bool {
         tab.on_close()
     }
@@ -201,4 +552,106 @@ impl crate::TabViewer for DynamicTabViewer {
     fn clear_background(&self, tab: &Self::Tab) -> bool {
         tab.clear_background()
     }
+
+    fn inner_background(&self, tab: &Self::Tab) -> Option<Color32> {
+        tab.inner_background()
+    }
+
+    fn render_when_inactive(&self, tab: &Self::Tab) -> bool {
+        tab.render_when_inactive()
+    }
+
+    fn on_background(&mut self, tab: &mut Self::Tab) {
+        tab.on_background();
+    }
+
+    fn toolbar_height(&self, tab: &Self::Tab) -> f32 {
+        tab.toolbar_height()
+    }
+
+    fn toolbar(&mut self, ui: &mut Ui, tab: &mut Self::Tab) {
+        tab.toolbar(ui);
+    }
+
+    fn on_activate(&mut self, tab: &mut Self::Tab) -> bool {
+        tab.on_activate()
+    }
+
+    fn preview_texture(&mut self, tab: &mut Self::Tab) -> Option<TextureId> {
+        tab.preview_texture()
+    }
+
+    fn closeable(&self, tab: &Self::Tab) -> bool {
+        tab.closeable()
+    }
+
+    fn is_modified(&self, tab: &Self::Tab) -> bool {
+        tab.is_modified()
+    }
+
+    fn context_menu(&mut self, ui: &mut Ui, tab: &mut Self::Tab) {
+        tab.context_menu(ui);
+    }
+
+    fn on_tab_double_click(&mut self, tab: &mut Self::Tab) {
+        tab.on_tab_double_click();
+    }
+
+    fn tab_tooltip_ui(&mut self, ui: &mut Ui, tab: &Self::Tab) {
+        tab.tab_tooltip_ui(ui);
+    }
+
+    fn tab_icon(&self, tab: &Self::Tab) -> Option<TabIcon> {
+        tab.tab_icon()
+    }
+
+    fn group<'a>(&self, tab: &'a Self::Tab) -> Option<&'a str> {
+        tab.group()
+    }
+
+    fn badge(&self, tab: &Self::Tab) -> Option<WidgetText> {
+        tab.badge()
+    }
+
+    fn is_pinned(&self, tab: &Self::Tab) -> bool {
+        tab.is_pinned()
+    }
+
+    fn set_pinned(&mut self, tab: &mut Self::Tab, pinned: bool) {
+        tab.set_pinned(pinned)
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// A tab made of just a title and the closure showing its content.
+///
+/// A lighter alternative to [`TabBuilder`] and [`Tab`] for quick tools and examples. The id of the tab is derived
+/// from its title and where its closure is kept, so tabs sharing a title are told apart as long as their closures
+/// capture something.
+pub type ClosureTab = (WidgetText, TabContent);
+
+/// A type-def for when using [`ClosureTab`]s.
+pub type ClosureTree = crate::Tree<ClosureTab>;
+
+/// For use with [`crate::DockArea::show`] when using [`ClosureTree`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ClosureTabViewer;
+
+impl crate::TabViewer for ClosureTabViewer {
+    type Tab = ClosureTab;
+
+    fn ui(&mut self, ui: &mut Ui, (_, add_content): &mut Self::Tab) {
+        add_content(ui)
+    }
+
+    fn title(&mut self, (title, _): &mut Self::Tab) -> WidgetText {
+        title.clone()
+    }
+
+    fn id(&mut self, (title, add_content): &mut Self::Tab) -> Id {
+        // The closure stays where it is on the heap while the tab is moved around.
+        let content = &**add_content as *const dyn FnMut(&mut Ui) as *const () as usize;
+        Id::new((title.text(), content))
+    }
 }
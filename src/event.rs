@@ -0,0 +1,92 @@
+use crate::{NodeIndex, Split, SurfaceIndex, TabIndex};
+
+/// Something a [`DockArea`](crate::DockArea) changed while it was shown, reported to the listener set with
+/// [`DockArea::on_event`](crate::DockArea::on_event) once every surface is shown.
+///
+/// Indices are the ones right after the change. Tabs closed or moved later in the same frame may have moved the nodes
+/// and tabs around since.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DockEvent {
+    /// A tab was added to the leaf `node`, like the duplicate of a tab opened with
+    /// [`DockShortcuts::split_horizontal`](crate::DockShortcuts::split_horizontal).
+    TabAdded {
+        surface: SurfaceIndex,
+        node: NodeIndex,
+        tab: TabIndex,
+    },
+
+    /// The tab at `tab` in the leaf `node` was closed.
+    TabClosed {
+        surface: SurfaceIndex,
+        node: NodeIndex,
+        tab: TabIndex,
+    },
+
+    /// The tab at `tab` became the active tab of the leaf `node`.
+    TabActivated {
+        surface: SurfaceIndex,
+        node: NodeIndex,
+        tab: TabIndex,
+    },
+
+    /// A tab was dragged or moved with a shortcut from `src` into the leaf `dst`.
+    TabMoved {
+        src: (SurfaceIndex, NodeIndex, TabIndex),
+        dst: (SurfaceIndex, NodeIndex),
+    },
+
+    /// The separator of the parent node `node` was moved, leaving `fraction` of its size to its left-most or top-most
+    /// child.
+    SplitResized {
+        surface: SurfaceIndex,
+        node: NodeIndex,
+        fraction: f32,
+    },
+
+    /// The node `node` was split in two, like by dropping a tab at the edge of a leaf, or at the edge of the dock
+    /// area for the root. `node` became their parent, with the node moved into one child and a new leaf, on the
+    /// `split` side, in the other.
+    NodeSplit {
+        surface: SurfaceIndex,
+        node: NodeIndex,
+        split: Split,
+    },
+
+    /// The leaf `node` was removed after its last tab was closed or moved out, with its sibling taking the place of
+    /// their parent.
+    NodeRemoved {
+        surface: SurfaceIndex,
+        node: NodeIndex,
+    },
+
+    /// The leaf `node` was focused, or none was if it's `None`.
+    FocusChanged {
+        surface: SurfaceIndex,
+        node: Option<NodeIndex>,
+    },
+}
+
+/// A pass of showing a surface, after which the hook set with
+/// [`DockArea::after_pass`](crate::DockArea::after_pass) is called.
+///
+/// Passes run in this order, each relying only on the ones before it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DockPass {
+    /// The rects of every node and the splits between them are worked out, [`Tree::node_rect`](crate::Tree::node_rect)
+    /// has the ones of this frame from here on.
+    Layout,
+
+    /// The separators between split nodes are shown and dragged.
+    Separators,
+
+    /// The tab bars of every leaf are shown, [`Tree::tab_title_rect`](crate::Tree::tab_title_rect) has the ones of
+    /// this frame from here on. Skipped in [`ZenMode::ActiveTab`](crate::ZenMode::ActiveTab).
+    TabBars,
+
+    /// The bodies of the active tabs are shown.
+    Contents,
+
+    /// Tabs closed during the frame are removed and the drop target of a dragged tab is painted, anything shown
+    /// from here on is on top of the whole surface.
+    DragOverlay,
+}
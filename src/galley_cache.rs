@@ -0,0 +1,148 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use egui::text::{Galley, LayoutJob};
+use egui::widget_text::WidgetTextGalley;
+use egui::*;
+
+/// Laid out tab titles, kept in `Context` memory so titles aren't laid out again every frame.
+///
+/// Entries are keyed by the whole layout job, so a title is laid out again whenever its text or style changes.
+/// Titles that weren't shown during the previous frame are evicted.
+#[derive(Clone, Default)]
+struct GalleyCache {
+    /// Frames counted by the cache, egui doesn't count them itself. A new frame starts when the input time changes.
+    frame_nr: u64,
+    time: f64,
+    galleys: HashMap<u64, (Arc<Galley>, u64)>,
+    /// Titles of tabs which reported them unchanged, keyed by tab id.
+    tabs: HashMap<Id, CachedTitle>,
+}
+
+#[derive(Clone)]
+struct CachedTitle {
+    galley: Arc<Galley>,
+    galley_has_color: bool,
+    /// Layout inputs besides the title itself, the title is laid out again if any of them changed.
+    font_id: FontId,
+    pixels_per_point: f32,
+    last_used: u64,
+}
+
+impl GalleyCache {
+    /// Counts a new frame if the input `time` changed, evicting the entries which weren't used during the previous
+    /// frame. Returns the number of the current frame.
+    fn evict(&mut self, time: f64) -> u64 {
+        if self.time != time {
+            self.time = time;
+            self.frame_nr += 1;
+            let frame_nr = self.frame_nr;
+            self.galleys
+                .retain(|_, (_, last_used)| *last_used + 1 >= frame_nr);
+            self.tabs
+                .retain(|_, cached| cached.last_used + 1 >= frame_nr);
+        }
+        self.frame_nr
+    }
+}
+
+fn cache_id() -> Id {
+    Id::new("egui_dock::GalleyCache")
+}
+
+/// Lays out `label` like `WidgetText::into_galley` with no wrapping would, reusing the galley from previous frames
+/// when possible.
+pub(crate) fn title_galley(ui: &Ui, label: WidgetText, text_style: TextStyle) -> WidgetTextGalley {
+    let text_job = label.into_text_job(ui.style(), FontSelection::Style(text_style), Align::Center);
+    let mut job: LayoutJob = text_job.job;
+    job.wrap.max_width = f32::INFINITY;
+
+    let key = {
+        let mut hasher = DefaultHasher::new();
+        job.hash(&mut hasher);
+        ui.ctx().pixels_per_point().to_bits().hash(&mut hasher);
+        hasher.finish()
+    };
+
+    let time = ui.input().time;
+    let (frame_nr, cached) = {
+        let mut data = ui.ctx().data();
+        let cache = data.get_temp_mut_or_default::<GalleyCache>(cache_id());
+        let frame_nr = cache.evict(time);
+        let cached = cache.galleys.get_mut(&key).map(|(galley, last_used)| {
+            *last_used = frame_nr;
+            galley.clone()
+        });
+        (frame_nr, cached)
+    };
+
+    // The memory guard has to be released before laying out, the fonts are behind the same lock.
+    let galley = match cached {
+        Some(galley) => galley,
+        None => {
+            let galley = ui.fonts().layout_job(job);
+            ui.ctx()
+                .data()
+                .get_temp_mut_or_default::<GalleyCache>(cache_id())
+                .galleys
+                .insert(key, (galley.clone(), frame_nr));
+            galley
+        }
+    };
+
+    WidgetTextGalley {
+        galley,
+        galley_has_color: text_job.job_has_color,
+    }
+}
+
+/// Returns the title galley of the tab `id`.
+///
+/// Unless `changed` is set, the galley shown for the tab during the previous frame is reused without calling `label`,
+/// as long as the font and the pixels per point are still the same.
+pub(crate) fn tab_title_galley(
+    ui: &Ui,
+    id: Id,
+    changed: bool,
+    label: impl FnOnce() -> WidgetText,
+    text_style: TextStyle,
+) -> WidgetTextGalley {
+    let font_id = text_style.resolve(ui.style());
+    let pixels_per_point = ui.ctx().pixels_per_point();
+    let time = ui.input().time;
+
+    let frame_nr = {
+        let mut data = ui.ctx().data();
+        let cache = data.get_temp_mut_or_default::<GalleyCache>(cache_id());
+        let frame_nr = cache.evict(time);
+        if let Some(cached) = cache.tabs.get_mut(&id).filter(|_| !changed) {
+            if cached.font_id == font_id && cached.pixels_per_point == pixels_per_point {
+                cached.last_used = frame_nr;
+                return WidgetTextGalley {
+                    galley: cached.galley.clone(),
+                    galley_has_color: cached.galley_has_color,
+                };
+            }
+        }
+        frame_nr
+    };
+
+    let galley = title_galley(ui, label(), text_style);
+    ui.ctx()
+        .data()
+        .get_temp_mut_or_default::<GalleyCache>(cache_id())
+        .tabs
+        .insert(
+            id,
+            CachedTitle {
+                galley: galley.galley.clone(),
+                galley_has_color: galley.galley_has_color,
+                font_id,
+                pixels_per_point,
+                last_used: frame_nr,
+            },
+        );
+    galley
+}
@@ -0,0 +1,231 @@
+use egui::{InputState, Key, Modifiers};
+
+/// A key combined with the modifiers that have to be held down for it to trigger.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Shortcut {
+    pub modifiers: Modifiers,
+    pub key: Key,
+}
+
+impl Shortcut {
+    /// Creates a shortcut triggered by pressing `key` while holding exactly `modifiers`.
+    pub const fn new(modifiers: Modifiers, key: Key) -> Self {
+        Self { modifiers, key }
+    }
+
+    /// Creates a shortcut triggered by pressing `key` without any modifiers.
+    pub const fn key(key: Key) -> Self {
+        Self::new(NONE, key)
+    }
+
+    /// Returns `true` if the shortcut was pressed this frame, `false` otherwise.
+    ///
+    /// `ctrl` and `mac_cmd` are compared through `command`, so `COMMAND` shortcuts work the same on every platform.
+    pub fn pressed(&self, input: &InputState) -> bool {
+        let held = input.modifiers;
+        held.alt == self.modifiers.alt
+            && held.shift == self.modifiers.shift
+            && held.command == self.modifiers.command
+            && input.key_pressed(self.key)
+    }
+}
+
+pub(crate) const NONE: Modifiers = Modifiers {
+    alt: false,
+    ctrl: false,
+    shift: false,
+    mac_cmd: false,
+    command: false,
+};
+
+pub(crate) const COMMAND: Modifiers = Modifiers {
+    command: true,
+    ..NONE
+};
+
+pub(crate) const COMMAND_ALT: Modifiers = Modifiers {
+    alt: true,
+    ..COMMAND
+};
+
+pub(crate) const COMMAND_SHIFT: Modifiers = Modifiers {
+    shift: true,
+    ..COMMAND
+};
+
+pub(crate) const ALT: Modifiers = Modifiers { alt: true, ..NONE };
+
+pub(crate) const ALT_SHIFT: Modifiers = Modifiers {
+    alt: true,
+    shift: true,
+    ..NONE
+};
+
+/// Returns `true` if exactly `modifiers` are held down, `false` otherwise. `ctrl` and `mac_cmd` are compared through
+/// `command` like with [`Shortcut::pressed`].
+pub(crate) fn held(modifiers: Modifiers, input: &InputState) -> bool {
+    let held = input.modifiers;
+    held.alt == modifiers.alt && held.shift == modifiers.shift && held.command == modifiers.command
+}
+
+/// Returns `true` if any of the `shortcuts` was pressed this frame, `false` otherwise.
+pub(crate) fn any_pressed(shortcuts: &[Shortcut], input: &InputState) -> bool {
+    shortcuts.iter().any(|shortcut| shortcut.pressed(input))
+}
+
+// ----------------------------------------------------------------------------
+
+/// Every keyboard shortcut handled by [`DockArea`](crate::DockArea).
+///
+/// Each action can be bound to any number of shortcuts. Clear the list to disable an action,
+/// e.g. when it collides with one of your application's own shortcuts.
+#[derive(Clone, Debug)]
+pub struct DockShortcuts {
+    /// Closes the tab whose title has keyboard focus. By `Default` it's `Delete` and `Ctrl+F4`.
+    pub close_tab: Vec<Shortcut>,
+
+    /// Closes the active tab of the focused leaf, unless it's pinned or can't be closed. Like
+    /// [`DockShortcuts::close_tab`] it works whether close buttons are shown or not. By `Default` it's `Ctrl+W`.
+    pub close_active_tab: Vec<Shortcut>,
+
+    /// Activates the tab after the active tab of the focused leaf, going back to the first tab after the last one.
+    /// By `Default` it's `Ctrl+Tab` and `Ctrl+PageDown`.
+    pub next_tab: Vec<Shortcut>,
+
+    /// Activates the tab before the active tab of the focused leaf, going on to the last tab before the first one.
+    /// By `Default` it's `Ctrl+Shift+Tab` and `Ctrl+PageUp`.
+    pub previous_tab: Vec<Shortcut>,
+
+    /// Splits the focused leaf, opening the new leaf to the right. By `Default` it's `Ctrl+Alt+H`.
+    ///
+    /// The new leaf gets a duplicate of the active tab if [`TabViewer::duplicate_tab`](crate::TabViewer::duplicate_tab)
+    /// provides one, otherwise the active tab is moved there.
+    pub split_horizontal: Vec<Shortcut>,
+
+    /// Splits the focused leaf, opening the new leaf below. By `Default` it's `Ctrl+Alt+V`.
+    ///
+    /// See [`DockShortcuts::split_horizontal`] for what ends up in the new leaf.
+    pub split_vertical: Vec<Shortcut>,
+
+    /// Moves the active tab of the focused leaf to the leaf on its left, splitting the focused leaf if there's none.
+    /// By `Default` it's `Alt+Shift+Left`.
+    pub move_tab_left: Vec<Shortcut>,
+
+    /// Moves the active tab of the focused leaf to the leaf on its right, splitting the focused leaf if there's none.
+    /// By `Default` it's `Alt+Shift+Right`.
+    pub move_tab_right: Vec<Shortcut>,
+
+    /// Moves the active tab of the focused leaf to the leaf above it, splitting the focused leaf if there's none.
+    /// By `Default` it's `Alt+Shift+Up`.
+    pub move_tab_up: Vec<Shortcut>,
+
+    /// Moves the active tab of the focused leaf to the leaf below it, splitting the focused leaf if there's none.
+    /// By `Default` it's `Alt+Shift+Down`.
+    pub move_tab_down: Vec<Shortcut>,
+
+    /// Opens the quick-open palette, if it's enabled with [`DockArea::quick_open`](crate::DockArea::quick_open).
+    /// By `Default` it's `Ctrl+P`.
+    pub quick_open: Vec<Shortcut>,
+
+    /// Activates the Nth tab of the focused leaf, with the last entry always activating the last tab.
+    /// By `Default` it's `Alt+1` through `Alt+9`.
+    pub activate_tab: [Vec<Shortcut>; 9],
+
+    /// Enters resize mode, in which the arrow keys grow and shrink the focused leaf (hold `Shift` for finer steps).
+    /// By `Default` it's `Ctrl+Alt+R`.
+    pub resize_mode: Vec<Shortcut>,
+
+    /// Leaves resize mode. By `Default` it's `Escape` and `Enter`.
+    pub exit_resize_mode: Vec<Shortcut>,
+
+    /// Reverts the last change to the layout, if the history is enabled with
+    /// [`Tree::set_history_capacity`](crate::Tree::set_history_capacity). By `Default` it's `Ctrl+Z`.
+    pub undo: Vec<Shortcut>,
+
+    /// Brings back the layout reverted last. By `Default` it's `Ctrl+Y` and `Ctrl+Shift+Z`.
+    pub redo: Vec<Shortcut>,
+
+    /// Modifiers held while dropping a dragged tab to open a duplicate of it there instead of moving it, if
+    /// [`TabViewer::duplicate_tab`](crate::TabViewer::duplicate_tab) provides one. By `Default` it's `Ctrl`.
+    pub clone_on_drop: Option<Modifiers>,
+}
+
+impl Default for DockShortcuts {
+    fn default() -> Self {
+        Self {
+            close_tab: vec![Shortcut::key(Key::Delete), Shortcut::new(COMMAND, Key::F4)],
+            close_active_tab: vec![Shortcut::new(COMMAND, Key::W)],
+            next_tab: vec![
+                Shortcut::new(COMMAND, Key::Tab),
+                Shortcut::new(COMMAND, Key::PageDown),
+            ],
+            previous_tab: vec![
+                Shortcut::new(COMMAND_SHIFT, Key::Tab),
+                Shortcut::new(COMMAND, Key::PageUp),
+            ],
+            split_horizontal: vec![Shortcut::new(COMMAND_ALT, Key::H)],
+            split_vertical: vec![Shortcut::new(COMMAND_ALT, Key::V)],
+            move_tab_left: vec![Shortcut::new(ALT_SHIFT, Key::ArrowLeft)],
+            move_tab_right: vec![Shortcut::new(ALT_SHIFT, Key::ArrowRight)],
+            move_tab_up: vec![Shortcut::new(ALT_SHIFT, Key::ArrowUp)],
+            move_tab_down: vec![Shortcut::new(ALT_SHIFT, Key::ArrowDown)],
+            quick_open: vec![Shortcut::new(COMMAND, Key::P)],
+            activate_tab: [
+                Key::Num1,
+                Key::Num2,
+                Key::Num3,
+                Key::Num4,
+                Key::Num5,
+                Key::Num6,
+                Key::Num7,
+                Key::Num8,
+                Key::Num9,
+            ]
+            .map(|key| vec![Shortcut::new(ALT, key)]),
+            resize_mode: vec![Shortcut::new(COMMAND_ALT, Key::R)],
+            exit_resize_mode: vec![Shortcut::key(Key::Escape), Shortcut::key(Key::Enter)],
+            undo: vec![Shortcut::new(COMMAND, Key::Z)],
+            redo: vec![
+                Shortcut::new(COMMAND, Key::Y),
+                Shortcut::new(COMMAND_SHIFT, Key::Z),
+            ],
+            clone_on_drop: Some(COMMAND),
+        }
+    }
+}
+
+impl DockShortcuts {
+    /// Returns the shortcuts which don't change the layout, for [`DockArea::read_only`](crate::DockArea::read_only).
+    pub(crate) fn navigation_only(&self) -> Self {
+        Self {
+            next_tab: self.next_tab.clone(),
+            previous_tab: self.previous_tab.clone(),
+            activate_tab: self.activate_tab.clone(),
+            quick_open: self.quick_open.clone(),
+            ..Self::none()
+        }
+    }
+
+    /// Returns a `DockShortcuts` with every action unbound.
+    pub fn none() -> Self {
+        Self {
+            close_tab: Vec::new(),
+            close_active_tab: Vec::new(),
+            next_tab: Vec::new(),
+            previous_tab: Vec::new(),
+            split_horizontal: Vec::new(),
+            split_vertical: Vec::new(),
+            move_tab_left: Vec::new(),
+            move_tab_right: Vec::new(),
+            move_tab_up: Vec::new(),
+            move_tab_down: Vec::new(),
+            quick_open: Vec::new(),
+            activate_tab: Default::default(),
+            resize_mode: Vec::new(),
+            exit_resize_mode: Vec::new(),
+            undo: Vec::new(),
+            redo: Vec::new(),
+            clone_on_drop: None,
+        }
+    }
+}
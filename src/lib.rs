@@ -14,7 +14,7 @@
 //! by implementing [`TabViewer`].
 //!
 //! ```rust
-//! use egui_dock::{NodeIndex, Tree};
+//! use egui_dock::Tree;
 //!
 //! struct MyTabs {
 //!     tree: Tree<String>
@@ -26,7 +26,7 @@
 //!         let tab2 = "tab2".to_string();
 //!
 //!         let mut tree = Tree::new(vec![tab1]);
-//!         tree.split_left(NodeIndex::root(), 0.20, vec![tab2]);
+//!         tree.split_left(tree.root().unwrap(), 0.20, vec![tab2]);
 //!
 //!         Self { tree }
 //!     }
@@ -59,21 +59,72 @@
 //! # });
 //! ```
 
+use std::collections::{HashMap, HashSet};
+
+use egui::collapsing_header::CollapsingState;
+use egui::output::OutputEvent;
 use egui::style::Margin;
 use egui::*;
 
-use tree::TabIndex;
+use galley_cache::tab_title_galley;
+use mdi::Mdi;
+use preview::Previews;
+use quick_open::{Entry, QuickOpen};
+use style::Metrics;
+use tab_list::TabList;
+use tree::EDGE_SPLIT_SHARE;
 use utils::*;
 
 pub use crate::{
-    dynamic_tab::{DynamicTabViewer, DynamicTree, Tab, TabBuilder},
-    style::{Style, StyleBuilder},
-    tree::{Node, NodeIndex, Split, Tree},
+    dynamic_tab::{
+        ClosureTab, ClosureTabViewer, ClosureTree, DynamicTabViewer, DynamicTree, SendDynamicTree,
+        Tab, TabBuilder,
+    },
+    event::{DockEvent, DockPass},
+    input::{DockShortcuts, Shortcut},
+    painter::{DockPainter, SeparatorState, StylePainter, TabIcon, TabState},
+    registry::{SavedTab, TabFactory, TabRegistry},
+    style::{Style, StyleBuilder, TabOverflow},
+    surface::{DockState, Surface, SurfaceIndex, WindowState},
+    transfer::transfer_tabs,
+    tree::{
+        DropTarget, LeafLayout, Node, NodeId, NodeIndex, NodeVisitor, ResizePolicy, Split,
+        TabBarPosition, TabDestination, TabIndex, TabInsertPolicy, Tree, ZenMode,
+    },
 };
 pub use egui;
 
+/// Profiles the enclosing function with `puffin` when the `profiling` feature is enabled.
+macro_rules! profile_function {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "profiling")]
+        puffin::profile_function!($($arg)*);
+    };
+}
+
+/// Profiles the rest of the enclosing scope with `puffin` when the `profiling` feature is enabled.
+macro_rules! profile_scope {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "profiling")]
+        puffin::profile_scope!($($arg)*);
+    };
+}
+
 mod dynamic_tab;
+mod event;
+mod galley_cache;
+mod input;
+mod mdi;
+mod painter;
+mod preview;
+mod quick_open;
+mod registry;
 mod style;
+mod surface;
+mod tab_list;
+#[cfg(any(test, feature = "test_utils"))]
+pub mod test_utils;
+mod transfer;
 mod tree;
 mod utils;
 
@@ -85,12 +136,60 @@ struct HoverData {
     tab: Option<(Rect, TabIndex)>,
     dst: NodeIndex,
     pointer: Pos2,
+    /// The tabs are stacked in a vertical tab bar, so tabs are dropped above or below them.
+    vertical: bool,
 }
 
 impl HoverData {
-    fn resolve(&self) -> (Option<Split>, Rect, Option<TabIndex>) {
-        if let Some(tab) = self.tab {
-            return (None, tab.0, Some(tab.1));
+    /// The targets shown by [`Style::show_drop_targets`], in a cross in the middle of the leaf, or `None` while the
+    /// tab is over a tab bar or targets aren't shown.
+    fn drop_targets(&self, style: &Style) -> Option<[(Option<Split>, Rect); 5]> {
+        if !style.show_drop_targets || self.tabs.is_some() || self.tab.is_some() {
+            return None;
+        }
+        let size = style.drop_target_size;
+        let step = size * 1.25;
+        let center = self.rect.center();
+        let target =
+            |x: f32, y: f32| Rect::from_center_size(center + vec2(x, y), Vec2::splat(size));
+        Some([
+            (None, target(0.0, 0.0)),
+            (Some(Split::Left), target(-step, 0.0)),
+            (Some(Split::Right), target(step, 0.0)),
+            (Some(Split::Above), target(0.0, -step)),
+            (Some(Split::Below), target(0.0, step)),
+        ])
+    }
+
+    /// Paints the targets shown by [`Style::show_drop_targets`], if any.
+    fn paint_drop_targets(&self, painter: &Painter, style: &Style, dock_painter: &dyn DockPainter) {
+        if let Some(targets) = self.drop_targets(style) {
+            for (split, rect) in targets {
+                let hovered = rect.contains(self.pointer);
+                dock_painter.drop_target(painter, style, rect, split, hovered);
+            }
+        }
+    }
+
+    fn resolve(&self, style: &Style) -> (Option<Split>, Rect, Option<TabIndex>) {
+        // Tabs dropped onto a tab are inserted before it, or after it on its right half (left half from right to
+        // left), which is shown as a line between the tabs. Stacked tabs go after on their lower half.
+        if let Some((rect, tab_index)) = self.tab {
+            if self.vertical {
+                let after = self.pointer.y > rect.center().y;
+                let y = if after { rect.max.y } else { rect.min.y };
+                let line =
+                    Rect::from_center_size(pos2(rect.center().x, y), vec2(rect.width(), 2.0));
+                return (None, line, Some(TabIndex(tab_index.0 + after as usize)));
+            }
+            let after = (self.pointer.x > rect.center().x) != style.rtl;
+            let x = if after == style.rtl {
+                rect.min.x
+            } else {
+                rect.max.x
+            };
+            let line = Rect::from_center_size(pos2(x, rect.center().y), vec2(2.0, rect.height()));
+            return (None, line, Some(TabIndex(tab_index.0 + after as usize)));
         }
         if let Some(tabs) = self.tabs {
             return (None, tabs, None);
@@ -107,12 +206,17 @@ impl HoverData {
             rect.center_bottom().distance(pointer),
         ];
 
-        let position = pts
-            .into_iter()
-            .enumerate()
-            .min_by(|(_, lhs), (_, rhs)| lhs.total_cmp(rhs))
-            .map(|(idx, _)| idx)
-            .unwrap();
+        // A drop target under the pointer wins over the closest side.
+        let hovered_target = self
+            .drop_targets(style)
+            .and_then(|targets| targets.iter().position(|(_, rect)| rect.contains(pointer)));
+        let position = hovered_target.unwrap_or_else(|| {
+            pts.into_iter()
+                .enumerate()
+                .min_by(|(_, lhs), (_, rhs)| lhs.total_cmp(rhs))
+                .map(|(idx, _)| idx)
+                .unwrap()
+        });
 
         let (target, other) = match position {
             0 => (None, Rect::EVERYTHING),
@@ -127,18 +231,182 @@ impl HoverData {
     }
 }
 
+/// A tab dragged from one surface onto another. Each surface only knows about its own half of the drag, the halves
+/// are brought together once every surface is shown.
+#[derive(Default)]
+struct SurfaceDrag {
+    /// The tab being dragged.
+    src: Option<(SurfaceIndex, NodeIndex, TabIndex)>,
+    /// Where it would be dropped.
+    dst: Option<(SurfaceIndex, HoverData)>,
+}
+
 #[derive(Clone, Debug, Default)]
 struct State {
     drag_start: Option<Pos2>,
+    /// Scratch buffer for the tabs closed during the frame.
+    to_remove: Vec<(NodeIndex, TabIndex)>,
+    /// Scratch buffer for the rects of the tab titles shown during the frame, handed to the tree once they're all
+    /// shown.
+    title_rects: Vec<(NodeIndex, TabIndex, Rect)>,
+    /// Scratch buffers for the ids of the tabs removed at the end of the frame, and the leaves whose active tab they
+    /// were.
+    closed_ids: Vec<Id>,
+    closed_active: Vec<NodeIndex>,
+    quick_open: QuickOpen,
+    tab_list: TabList,
+    /// Whether the arrow keys currently resize the focused leaf.
+    resize_mode: bool,
+    /// Tab whose drag was cancelled with escape, which stays put until it's released.
+    cancelled_drag: Option<Id>,
+    /// Leaf whose tab was double-clicked, maximized or restored at the start of the next frame.
+    toggle_maximized: Option<NodeIndex>,
+    /// Whether the last click landed outside of the dock, which keeps its shortcuts from firing until it's clicked.
+    lost_focus: bool,
+    /// Widget of the dock which took the keyboard focus last. While any other widget has it the shortcuts don't fire
+    /// either, like after the focus was moved to another panel with the tab key.
+    focus: Option<Id>,
+    /// Widths of the tab titles as of the last time they were shown.
+    tab_widths: HashMap<Id, f32>,
+    /// How far the tab bars whose tabs don't fit are scrolled, keyed by tab bar id.
+    tab_bar_scroll: HashMap<Id, f32>,
+    /// Separators moving to fractions changed from code, keyed by separator id.
+    separator_animations: HashMap<Id, SeparatorAnimation>,
+    /// Rows of tabs of the wrapping tab bars as of the last time they were shown, keyed by leaf id.
+    tab_bar_rows: HashMap<Id, usize>,
+    /// Tab groups collapsed with their label, keyed by [`tab_group_id`].
+    collapsed_tab_groups: HashSet<Id>,
+    /// Id of the active tab of every leaf as of the last time it was shown.
+    active_tabs: HashMap<NodeId, Id>,
+    mdi: Mdi,
+    /// Pictures of the content of tabs, see [`Style::capture_tab_previews`].
+    previews: Previews,
+}
+
+/// Which tabs the "Close" entries of a tab's context menu close.
+#[derive(Clone, Copy, Debug)]
+enum CloseTabs {
+    One,
+    Others,
+    ToTheRight,
+}
+
+/// A separator moving from one fraction to another, see [`Style::separator_animation_time`].
+#[derive(Clone, Copy, Debug)]
+struct SeparatorAnimation {
+    from: f32,
+    to: f32,
+    start_time: f64,
+}
+
+impl SeparatorAnimation {
+    fn still(fraction: f32, time: f64) -> Self {
+        Self {
+            from: fraction,
+            to: fraction,
+            start_time: time,
+        }
+    }
+
+    /// The fraction shown at `time`, easing out towards `to`.
+    fn fraction(&self, time: f64, animation_time: f32) -> f32 {
+        let t = ((time - self.start_time) as f32 / animation_time).clamp(0.0, 1.0);
+        let t = 1.0 - (1.0 - t).powi(3);
+        lerp(self.from..=self.to, t)
+    }
 }
 
 impl State {
+    /// Takes the state out of `Context` memory, leaving a default one in its place until [`State::store`] is called.
+    ///
+    /// Unlike cloning it out, this reuses the buffers kept in the state, so nothing is allocated per frame.
     pub fn load(ctx: &Context, id: Id) -> Self {
-        ctx.data().get_temp(id).unwrap_or(Self { drag_start: None })
+        std::mem::take(ctx.data().get_temp_mut_or_default::<Self>(id))
     }
 
     fn store(self, ctx: &Context, id: Id) {
-        ctx.data().insert_temp(id, self);
+        *ctx.data().get_temp_mut_or_default::<Self>(id) = self;
+    }
+}
+
+/// Everything the passes of [`DockArea::show_inside`] share during a single frame.
+struct FrameState {
+    /// The surface being shown.
+    surface: SurfaceIndex,
+    state: State,
+    style: Style,
+    metrics: Metrics,
+    /// Whether drop zones have to be worked out, only the case while something is being dragged.
+    is_dragging: bool,
+    /// Tabs closed during the frame, removed once every leaf is shown.
+    to_remove: Vec<(NodeIndex, TabIndex)>,
+    /// The tab whose "Close Others" or "Close to the Right" menu entry was clicked, handled with `to_remove`.
+    close_tabs: Option<(NodeIndex, TabIndex, CloseTabs)>,
+    /// The leaf to focus once every leaf is shown.
+    new_focused: Option<NodeIndex>,
+    /// Messages for screen readers, pushed once the frame is done.
+    announcements: Vec<String>,
+    /// What changed during the frame, handed to the [`DockArea`] once the frame is done.
+    events: Vec<DockEvent>,
+    /// The tab being dragged, once it has been dragged far enough.
+    drag_data: Option<(NodeIndex, TabIndex)>,
+    /// Where the dragged tab would be dropped.
+    hover_data: Option<HoverData>,
+    /// Changes made after the tree was drawn are only visible once the next frame is drawn, so that frame is
+    /// requested explicitly. Nothing else makes the dock request repaints.
+    changed: bool,
+}
+
+impl FrameState {
+    fn begin(ui: &Ui, id: Id, surface: SurfaceIndex, mut style: Style) -> Self {
+        style.rtl |= ui.layout().prefer_right_to_left();
+        let mut state = State::load(ui.ctx(), id);
+        let mut to_remove = std::mem::take(&mut state.to_remove);
+        to_remove.clear();
+
+        let is_dragging =
+            cfg!(feature = "drag_and_drop") && ui.memory().is_anything_being_dragged();
+        if !is_dragging {
+            state.drag_start = None;
+        }
+
+        Self {
+            surface,
+            state,
+            metrics: style.metrics(ui),
+            style,
+            is_dragging,
+            to_remove,
+            close_tabs: None,
+            new_focused: None,
+            announcements: Vec::new(),
+            events: Vec::new(),
+            drag_data: None,
+            hover_data: None,
+            changed: false,
+        }
+    }
+
+    /// Requests the next frame if needed, pushes the announcements and stores the state again.
+    fn end(mut self, ui: &Ui, id: Id) {
+        if self.changed {
+            ui.ctx().request_repaint();
+        }
+
+        if !self.announcements.is_empty() {
+            let mut output = ui.ctx().output();
+            for announcement in self.announcements {
+                output
+                    .events
+                    .push(OutputEvent::ValueChanged(WidgetInfo::labeled(
+                        WidgetType::Other,
+                        announcement,
+                    )));
+            }
+        }
+
+        self.state.to_remove = self.to_remove;
+        self.state.store(ui.ctx(), id);
     }
 }
 
@@ -149,14 +417,119 @@ pub trait TabViewer {
     type Tab;
 
     /// Actual tab content.
+    ///
+    /// Only called for the active tab of each leaf, unless [`TabViewer::render_when_inactive`] returns `true`
+    /// for the tab.
     fn ui(&mut self, ui: &mut Ui, tab: &mut Self::Tab);
 
     /// The title to be displayed.
     fn title(&mut self, tab: &mut Self::Tab) -> WidgetText;
 
-    /// This is called when the tabs close button is pressed.
+    /// Whether the title of the tab changed since the last frame.
+    ///
+    /// While this returns `false` the dock keeps showing the title it laid out before, without calling
+    /// [`TabViewer::title`]. By default it's `true`, so the title is asked for every frame. Override
+    /// [`TabViewer::id`] as well to avoid it entirely, since the default id is derived from the title.
+    fn title_changed(&mut self, _tab: &mut Self::Tab) -> bool {
+        true
+    }
+
+    /// Shows the title of the tab in its tab bar yourself, for icons, spinners, "modified" dots or anything else a
+    /// [`WidgetText`] can't express. `ui` is laid out left to right and centered in the tab bar.
+    ///
+    /// Return `true` if you showed something, otherwise the text from [`TabViewer::title`] is shown. The title width
+    /// is measured while showing it, so a change in width is picked up on the next frame. The text from
+    /// [`TabViewer::title`] is still used everywhere else, e.g. in the tab list and by screen readers.
+    ///
+    /// ```rust
+    /// # use egui_dock::egui::{Ui, WidgetText};
+    /// struct Document {
+    ///     name: String,
+    ///     modified: bool,
+    ///     loading: bool,
+    /// }
+    ///
+    /// struct Viewer;
+    ///
+    /// impl egui_dock::TabViewer for Viewer {
+    ///     type Tab = Document;
+    ///
+    ///     fn ui(&mut self, _ui: &mut Ui, _doc: &mut Document) {}
+    ///
+    ///     fn title(&mut self, doc: &mut Document) -> WidgetText {
+    ///         doc.name.as_str().into()
+    ///     }
+    ///
+    ///     fn tab_title_ui(&mut self, ui: &mut Ui, doc: &mut Document) -> bool {
+    ///         if doc.loading {
+    ///             ui.spinner();
+    ///         }
+    ///         ui.label(&doc.name);
+    ///         if doc.modified {
+    ///             ui.label("●");
+    ///         }
+    ///         true
+    ///     }
+    /// }
+    /// ```
+    fn tab_title_ui(&mut self, _ui: &mut Ui, _tab: &mut Self::Tab) -> bool {
+        false
+    }
+
+    /// Unique id for this tab, which must stay the same for as long as the tab exists.
+    ///
+    /// Every id the dock uses for the tab (its title, close button, drag state and content) is derived from this and
+    /// the [`DockArea`] id. By default it's derived from the title, which is enough for tabs with unique, fixed titles.
+    /// Override it if titles can repeat, like several untitled documents, since tabs sharing an id share that state
+    /// too, or if they change, like a `*` marking unsaved changes, since the state is lost whenever the id changes.
+    ///
+    /// The `Ui` given to [`TabViewer::ui`] has an id derived from it too, so the state of scroll areas, collapsing
+    /// headers and text edits stays with the tab, apart from the other tabs of its leaf, and follows the tab when
+    /// it's moved to another leaf.
+    fn id(&mut self, tab: &mut Self::Tab) -> Id {
+        Id::new(self.title(tab).text())
+    }
+
+    /// This is called when the tabs close button is pressed, or the tab is closed with a keyboard shortcut.
     ///
-    /// Returns `true` if the tab should close immediately, `false` otherwise.
+    /// Returns `true` if the tab should close immediately, `false` otherwise. Returning `false` vetoes the close, like
+    /// to ask whether to save changes first; close the tab later from [`TabViewer::force_close`] once the user
+    /// answered:
+    ///
+    /// ```rust
+    /// # use egui::{Ui, WidgetText};
+    /// # use egui_dock::TabViewer;
+    /// struct Document {
+    ///     unsaved: bool,
+    ///     asking: bool,
+    ///     discard: bool,
+    /// }
+    ///
+    /// struct Viewer;
+    ///
+    /// impl TabViewer for Viewer {
+    ///     type Tab = Document;
+    ///
+    ///     fn ui(&mut self, ui: &mut Ui, doc: &mut Document) {
+    ///         if doc.asking && ui.button("Discard changes").clicked() {
+    ///             doc.discard = true;
+    ///         }
+    ///     }
+    ///
+    ///     fn title(&mut self, doc: &mut Document) -> WidgetText {
+    ///         if doc.unsaved { "Document*" } else { "Document" }.into()
+    ///     }
+    ///
+    ///     fn on_close(&mut self, doc: &mut Document) -> bool {
+    ///         doc.asking = doc.unsaved;
+    ///         !doc.unsaved
+    ///     }
+    ///
+    ///     fn force_close(&mut self, doc: &mut Document) -> bool {
+    ///         doc.discard
+    ///     }
+    /// }
+    /// ```
     ///
     /// NOTE if returning false `ui` will still be called once more if this tab is active.
     fn on_close(&mut self, _tab: &mut Self::Tab) -> bool {
@@ -181,10 +554,214 @@ pub trait TabViewer {
     fn clear_background(&self, _tab: &Self::Tab) -> bool {
         true
     }
+
+    /// Color the body of `tab` is cleared with instead of [`Style::tab_background_color`], like black for a
+    /// terminal, so the default color never shows behind it. By default it's `None`.
+    ///
+    /// Ignored when [`TabViewer::clear_background`] returns `false`.
+    fn inner_background(&self, _tab: &Self::Tab) -> Option<Color32> {
+        None
+    }
+
+    /// Whether `ui` should be called for the tab even while it's not the active tab of its leaf.
+    ///
+    /// Inactive tabs are laid out in the leaf as usual but nothing they paint is shown and they don't receive input.
+    /// Useful for tabs which must keep updating, like video players or live charts.
+    fn render_when_inactive(&self, _tab: &Self::Tab) -> bool {
+        false
+    }
+
+    /// This is called every frame for each tab whose `ui` isn't, like inactive tabs and the tabs of collapsed
+    /// leaves, so they can keep up with what they follow without showing anything, like draining a channel or
+    /// stepping an animation.
+    ///
+    /// Cheaper than [`TabViewer::render_when_inactive`], which lays out the whole tab out of sight.
+    fn on_background(&mut self, _tab: &mut Self::Tab) {}
+
+    /// Height of the toolbar strip shown between the tab bar and the body of the leaf while `tab` is its active tab.
+    /// By default it's `0.0`, which shows no toolbar.
+    fn toolbar_height(&self, _tab: &Self::Tab) -> f32 {
+        0.0
+    }
+
+    /// Shows the toolbar of `tab`, like breadcrumbs or view mode buttons, laid out from left to right.
+    ///
+    /// Only called while [`TabViewer::toolbar_height`] is above `0.0`. The strip is painted like a tab bar.
+    fn toolbar(&mut self, _ui: &mut Ui, _tab: &mut Self::Tab) {}
+
+    /// This is called when `tab` becomes the active tab of its leaf, whether it was clicked, picked with a shortcut
+    /// or activated from code, right before its `ui` is called.
+    ///
+    /// Return `true` if the tab takes keyboard focus, like an editor putting the cursor back where it was. The dock
+    /// then gives up the focus of the tab title, so a widget of the tab can take it with
+    /// [`Response::request_focus`](egui::Response::request_focus) during `ui`.
+    fn on_activate(&mut self, _tab: &mut Self::Tab) -> bool {
+        false
+    }
+
+    /// Returns a texture holding a picture of the content of `tab`, shown while it's being dragged and when the
+    /// pointer rests on its title while it's inactive. By default there's none, and the dock shows the picture it
+    /// captured itself if [`Style::capture_tab_previews`] is set.
+    ///
+    /// Return one if the app has a better picture, like a render target it already draws the tab's content to.
+    fn preview_texture(&mut self, _tab: &mut Self::Tab) -> Option<TextureId> {
+        None
+    }
+
+    /// This is called when the "+" button after the tabs of the leaf `node` is clicked, see
+    /// [`Style::show_add_buttons`]. The tree can't be changed while it's shown, so keep `node` and push the new tab
+    /// into it after [`DockArea::show`] returned.
+    ///
+    /// Not called if [`TabViewer::has_add_popup`] returns `true`, the button opens the popup instead.
+    fn on_add(&mut self, _node: NodeIndex) {}
+
+    /// Whether the "+" buttons open a popup filled by [`TabViewer::add_popup`] rather than calling
+    /// [`TabViewer::on_add`]. By default they don't.
+    fn has_add_popup(&self) -> bool {
+        false
+    }
+
+    /// Fills the popup opened by the "+" button of the leaf `node`, like with a menu of the kinds of tabs in a
+    /// [`TabRegistry`]:
+    ///
+    /// ```rust
+    /// # use egui::{Ui, WidgetText};
+    /// # use egui_dock::{NodeIndex, TabRegistry, TabViewer};
+    /// struct MyTabViewer {
+    ///     registry: TabRegistry<String>,
+    /// }
+    ///
+    /// impl TabViewer for MyTabViewer {
+    ///     type Tab = String;
+    ///
+    ///     fn ui(&mut self, ui: &mut Ui, tab: &mut Self::Tab) {
+    ///         ui.label(tab.as_str());
+    ///     }
+    ///
+    ///     fn title(&mut self, tab: &mut Self::Tab) -> WidgetText {
+    ///         tab.as_str().into()
+    ///     }
+    ///
+    ///     fn has_add_popup(&self) -> bool {
+    ///         true
+    ///     }
+    ///
+    ///     fn add_popup(&mut self, ui: &mut Ui, _node: NodeIndex) -> Option<Self::Tab> {
+    ///         self.registry.add_menu(ui)
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// The tab returned, if any, is pushed into `node` once the tab bars are shown, following the
+    /// [`TabInsertPolicy`]. The popup closes when something in it is clicked.
+    fn add_popup(&mut self, _ui: &mut Ui, _node: NodeIndex) -> Option<Self::Tab> {
+        None
+    }
+
+    /// Shows widgets of your own at the start of the tab bar of the leaf `node`, like an icon for the panel, laid
+    /// out from left to right, or top to bottom in vertical tab bars. The tabs start after them. By default there's
+    /// nothing.
+    fn tab_bar_leading_ui(&mut self, _ui: &mut Ui, _node: NodeIndex) {}
+
+    /// Shows widgets of your own at the end of the tab bar of the leaf `node`, like a settings or split button, laid
+    /// out from right to left, or bottom to top in vertical tab bars. They stay at the end, and tabs which don't fit
+    /// before them scroll or are listed in the tab list. By default there's nothing.
+    fn tab_bar_trailing_ui(&mut self, _ui: &mut Ui, _node: NodeIndex) {}
+
+    /// Whether `tab` can be closed by the user, like a panel which should always stay open. Tabs which can't have no
+    /// close button and ignore middle clicks and the close shortcut. By default every tab can be closed.
+    ///
+    /// Tabs can still be closed from code, or with [`TabViewer::force_close`].
+    fn closeable(&self, _tab: &Self::Tab) -> bool {
+        true
+    }
+
+    /// Whether `tab` has unsaved changes. Its title then shows a dot in place of the close button, which switches to
+    /// the close button while the title is hovered, like text editors do. By default no tab is modified.
+    ///
+    /// The dot is colored with [`Style::modified_marker_color`].
+    fn is_modified(&self, _tab: &Self::Tab) -> bool {
+        false
+    }
+
+    /// Adds entries of your own to the menu opened by right-clicking the title of `tab`, above the "Close",
+    /// "Close Others" and "Close to the Right" entries shown when tabs can be closed.
+    ///
+    /// Call [`Ui::close_menu`] once an entry is clicked.
+    fn context_menu(&mut self, _ui: &mut Ui, _tab: &mut Self::Tab) {}
+
+    /// This is called when the title of `tab` is double-clicked, like to start renaming a document.
+    ///
+    /// The leaf of the tab is maximized as well unless [`Style::maximize_on_double_click`] is disabled.
+    fn on_tab_double_click(&mut self, _tab: &mut Self::Tab) {}
+
+    /// Shows the tooltip of `tab`, like the full path of a file or what it's about, once the pointer rests on its
+    /// title. By default it's empty.
+    ///
+    /// Only called when [`Style::show_tab_tooltips`] is enabled, after [`Style::tab_tooltip_delay`].
+    fn tab_tooltip_ui(&mut self, _ui: &mut Ui, _tab: &Self::Tab) {}
+
+    /// Returns the icon shown before the title of `tab`, like the icon of its file type. By default there's none.
+    ///
+    /// Icons are [`Style::tab_icon_size`] large and [`Style::tab_icon_spacing`] away from the title.
+    fn tab_icon(&self, _tab: &Self::Tab) -> Option<TabIcon> {
+        None
+    }
+
+    /// Returns the name of the group `tab` belongs to, like the tab groups of web browsers. By default tabs aren't
+    /// grouped.
+    ///
+    /// Tabs of a group are marked with a stripe of its color, see [`Style::tab_group_colors`]. With
+    /// [`Style::collapsible_tab_groups`] every run of tabs of a group starts with a label, which collapses them when
+    /// clicked. The name is borrowed from the tab, as it's asked for every frame.
+    fn group<'a>(&self, _tab: &'a Self::Tab) -> Option<&'a str> {
+        None
+    }
+
+    /// Whether `tab` is pinned. Pinned tabs are kept at the start of their tab bar, in the order they're in, and
+    /// aren't closed by "Close Others", "Close to the Right", [`Tree::close_all_tabs`] or
+    /// [`DockShortcuts::close_active_tab`]. By default no tab is pinned.
+    ///
+    /// With [`Style::compact_pinned_tabs`] pinned tabs only show their [icon](TabViewer::tab_icon).
+    fn is_pinned(&self, _tab: &Self::Tab) -> bool {
+        false
+    }
+
+    /// This is called when `tab` is pinned or unpinned from the menu of its title, see [`Style::pinnable_tabs`].
+    fn set_pinned(&mut self, _tab: &mut Self::Tab, _pinned: bool) {}
+
+    /// Returns the text of a small badge shown next to the title of `tab`, like a count of unread messages or
+    /// errors. By default tabs have no badge.
+    ///
+    /// Badges are colored with [`Style::tab_badge_color`] and [`Style::tab_badge_text_color`].
+    fn badge(&self, _tab: &Self::Tab) -> Option<WidgetText> {
+        None
+    }
+
+    /// This is called when the focused leaf is split with one of the [`DockShortcuts`] split shortcuts, and when a
+    /// `tab` is dropped while holding [`DockShortcuts::clone_on_drop`], like to view the same document in two splits.
+    ///
+    /// Returns a new tab to open in the new leaf or where `tab` was dropped, or `None` if `tab` should be moved there
+    /// instead.
+    fn duplicate_tab(&mut self, _tab: &Self::Tab) -> Option<Self::Tab> {
+        None
+    }
 }
 
 // ----------------------------------------------------------------------------
 
+/// Listener set with [`DockArea::on_event`].
+type EventHandler<'a> = Box<dyn FnMut(&DockEvent) + 'a>;
+
+/// Contents of the status bar set with [`DockArea::status_bar`].
+type StatusBar<'a> = Box<dyn FnOnce(&mut Ui) + 'a>;
+
+/// Contents of empty leaves set with [`DockArea::empty_area`].
+type EmptyArea<'a> = Box<dyn FnMut(&mut Ui) + 'a>;
+
+/// Hook set with [`DockArea::after_pass`].
+type PassHook<'a, Tab> = Box<dyn FnMut(&mut Ui, DockPass, SurfaceIndex, &Tree<Tab>) + 'a>;
+
 /// Stores the layout and position of all its tabs
 ///
 /// Keeps track of the currently focused leaf and currently active tabs
@@ -192,158 +769,1724 @@ pub struct DockArea<'tree, Tab> {
     id: Id,
     tree: &'tree mut Tree<Tab>,
     style: Option<Style>,
+    announce_changes: bool,
+    shortcuts: DockShortcuts,
+    quick_open: bool,
+    painter: &'tree dyn DockPainter,
+    /// The surfaces of the [`DockState`] besides the main one, if the dock area shows one.
+    windows: &'tree mut [Surface<Tab>],
+    on_event: Option<EventHandler<'tree>>,
+    status_bar: Option<(f32, StatusBar<'tree>)>,
+    empty_area: Option<EmptyArea<'tree>>,
+    after_pass: Option<PassHook<'tree, Tab>>,
+    read_only: bool,
+    #[cfg(feature = "persistence")]
+    persist_layout: bool,
+    /// What changed on any surface during the frame.
+    events: Vec<DockEvent>,
 }
 
-impl<'tree, Tab> DockArea<'tree, Tab> {
-    pub fn new(tree: &'tree mut Tree<Tab>) -> DockArea<'tree, Tab> {
-        Self {
-            id: Id::new("egui_dock::DockArea"),
-            tree,
-            style: None,
-        }
+/// Clears the body of `tab` inside `rect`, unless [`TabViewer::clear_background`] says otherwise, with the color of
+/// [`TabViewer::inner_background`] if it has one.
+fn clear_tab_body<Tab>(
+    ui: &Ui,
+    painter: &dyn DockPainter,
+    style: &Style,
+    rect: Rect,
+    tab_viewer: &impl TabViewer<Tab = Tab>,
+    tab: &Tab,
+) {
+    if !tab_viewer.clear_background(tab) {
+        return;
     }
+    let fill = tab_viewer
+        .inner_background(tab)
+        .unwrap_or(style.tab_background_color);
+    painter.tab_body(ui.painter(), style, rect, fill);
+}
 
-    /// Sets the [DockArea] id. Useful if you have more than one [DockArea].
-    pub fn id(mut self, id: Id) -> Self {
-        self.id = id;
-        self
-    }
+/// Shows the body of `tab` in a `Ui` of its own, whose id is derived from `tab_id` rather than the leaf.
+fn show_tab_body<Tab>(
+    ui: &mut Ui,
+    tab_id: Id,
+    tab_viewer: &mut impl TabViewer<Tab = Tab>,
+    tab: &mut Tab,
+) {
+    profile_function!();
+
+    ui.push_id(tab_id, |ui| {
+        ScrollArea::both()
+            .id_source(tab_id.with("body"))
+            .show(ui, |ui| {
+                Frame::none()
+                    .inner_margin(tab_viewer.inner_margin())
+                    .show(ui, |ui| {
+                        let available_rect = ui.available_rect_before_wrap();
+                        ui.expand_to_include_rect(available_rect);
+                        tab_viewer.ui(ui, tab);
+                    });
+            });
+    });
+}
 
-    /// Sets the dock area style.
-    pub fn style(mut self, style: Style) -> Self {
-        self.style = Some(style);
-        self
+/// Shows the tabs of the leaf at `node_index` as child windows inside its `rect`, see [`Tree::set_mdi`].
+///
+/// The active tab is shown on top and is the only one taking input. Pressing any other window activates it.
+///
+/// Returns the rects of the title bars of the windows.
+#[allow(clippy::too_many_arguments)]
+fn show_mdi_children<Tab>(
+    ui: &mut Ui,
+    dock_id: Id,
+    painter: &dyn DockPainter,
+    style: &Style,
+    metrics: &Metrics,
+    mdi: &mut Mdi,
+    node_index: NodeIndex,
+    focused: bool,
+    rect: Rect,
+    tabs: &mut [Tab],
+    active: &mut TabIndex,
+    to_remove: &mut Vec<(NodeIndex, TabIndex)>,
+    title_rects: &mut Vec<(NodeIndex, TabIndex, Rect)>,
+    tab_viewer: &mut impl TabViewer<Tab = Tab>,
+) {
+    profile_function!();
+
+    let mut children = std::mem::take(&mut mdi.children);
+    children.clear();
+    for (index, tab) in tabs.iter_mut().enumerate() {
+        let id = dock_id.with(tab_viewer.id(tab));
+        children.push((id, mdi.child_rect(id, index, rect)));
     }
 
-    /// Shows the docking area.
-    pub fn show(self, ctx: &Context, tab_viewer: &mut impl TabViewer<Tab = Tab>) {
-        let layer_id = LayerId::background();
-        let max_rect = ctx.available_rect();
-        let clip_rect = ctx.available_rect();
-
-        let mut ui = Ui::new(ctx.clone(), layer_id, self.id, max_rect, clip_rect);
-        self.show_inside(&mut ui, tab_viewer);
+    // Windows are painted back to front, with the active one last.
+    let len = tabs.len();
+    let order = |active: TabIndex| {
+        (0..len)
+            .filter(move |&index| index != active.0)
+            .chain((active.0 < len).then_some(active.0))
+    };
+
+    let pressed_at = {
+        let input = ui.input();
+        input
+            .pointer
+            .any_pressed()
+            .then(|| input.pointer.interact_pos())
+            .flatten()
+    };
+    if let Some(pos) = pressed_at {
+        if let Some(index) = order(*active)
+            .rev()
+            .find(|&index| children[index].1.contains(pos))
+        {
+            *active = TabIndex(index);
+        }
     }
 
-    /// Shows the docking hierarchy inside a `Ui`.
-    pub fn show_inside(self, ui: &mut Ui, tab_viewer: &mut impl TabViewer<Tab = Tab>) {
-        let style = self
-            .style
-            .unwrap_or_else(|| Style::from_egui(ui.style().as_ref()));
+    let title_height = metrics.tab_bar_height;
+    for index in order(*active) {
+        let (id, child) = children[index];
+        let tab = &mut tabs[index];
+        let is_active = index == active.0;
 
-        let mut state = State::load(ui.ctx(), self.id);
-        let mut rect = ui.max_rect();
+        let title_rect = child.intersect(Rect::everything_above(child.min.y + title_height));
+        title_rects.push((node_index, TabIndex(index), title_rect));
+        painter.tab_bar(ui.painter(), style, title_rect, metrics.px);
 
-        if let Some(margin) = style.padding {
-            rect.min += margin.left_top();
-            rect.max -= margin.right_bottom();
-            ui.painter().rect(
-                rect,
-                margin.top,
-                style.separator_color,
-                Stroke::new(margin.top, style.border_color),
-            );
+        let title_response = ui.interact(title_rect, id.with("mdi_title"), Sense::drag());
+        if title_response.dragged() {
+            mdi.translate(id, title_response.drag_delta());
         }
 
-        if self.tree.is_empty() {
-            ui.allocate_rect(rect, Sense::hover());
-            return;
+        let mut title_text = title_rect;
+        if style.shows_close_buttons() && tab_viewer.closeable(tab) {
+            let size = Vec2::splat(title_height * 0.5);
+            let close_x = if style.close_button_on_left() {
+                title_rect.min.x + title_height * 0.5
+            } else {
+                title_rect.max.x - title_height * 0.5
+            };
+            let close_rect = Rect::from_center_size(pos2(close_x, title_rect.center().y), size);
+            if style.close_button_on_left() {
+                title_text.min.x = close_rect.max.x;
+            } else {
+                title_text.max.x = close_rect.min.x;
+            }
+            let response = ui.interact(close_rect, id.with("mdi_close"), Sense::click());
+            if tab_viewer.is_modified(tab) && !response.hovered() {
+                painter.modified_marker(ui.painter(), style, close_rect, is_active && focused);
+            } else {
+                painter.close_button(
+                    ui.painter(),
+                    style,
+                    close_rect,
+                    response.hovered(),
+                    response.is_pointer_button_down_on() || is_active && focused,
+                );
+            }
+            if response.clicked() && tab_viewer.on_close(tab) {
+                to_remove.push((node_index, TabIndex(index)));
+            }
         }
 
-        self.tree[NodeIndex::root()].set_rect(rect);
+        let galley = tab_title_galley(
+            ui,
+            id,
+            tab_viewer.title_changed(tab),
+            || tab_viewer.title(tab),
+            TextStyle::Button,
+        );
+        let text_pos = pos2(
+            title_text.min.x + 8.0,
+            title_text.center().y - galley.size().y * 0.5,
+        );
+        painter.tab_title(
+            &ui.painter()
+                .with_clip_rect(title_text.intersect(ui.clip_rect())),
+            style,
+            text_pos,
+            galley,
+            TabState {
+                active: is_active,
+                focused: is_active && focused,
+                dragged: false,
+                hovered: title_response.hovered(),
+            },
+        );
+
+        let body_rect = child.intersect(Rect::everything_below(title_rect.max.y));
+        clear_tab_body(ui, painter, style, body_rect, tab_viewer, tab);
+        let mut child_ui = ui.child_ui(body_rect, Default::default());
+        child_ui.set_clip_rect(body_rect.intersect(rect));
+        if !is_active {
+            child_ui.set_enabled(false);
+        }
+        show_tab_body(&mut child_ui, id, tab_viewer, tab);
+
+        let grip_rect = Rect::from_min_max(child.max - Vec2::splat(title_height * 0.5), child.max);
+        let grip_response = ui
+            .interact(grip_rect, id.with("mdi_resize"), Sense::drag())
+            .on_hover_cursor(CursorIcon::ResizeNwSe);
+        if grip_response.dragged() {
+            mdi.resize(id, grip_response.drag_delta());
+        }
 
-        let mut drag_data = None;
-        let mut hover_data = None;
+        ui.painter().rect_stroke(
+            child.shrink(metrics.px * 0.5),
+            Rounding::none(),
+            Stroke::new(metrics.px, style.tab_outline_color),
+        );
+    }
 
-        let pixels_per_point = ui.ctx().pixels_per_point();
-        let px = pixels_per_point.recip();
+    mdi.children = children;
+}
 
-        let focused = self.tree.focused_leaf();
+/// Height of the handle left in place of a tab bar hidden by [`Style::hide_single_tab_bars`].
+const TAB_BAR_HANDLE_HEIGHT: f32 = 4.0;
 
-        let mut to_remove = Vec::new();
-        let mut new_focused = None;
+/// Whether the tab bar of the leaf at `node_index` is hidden because it holds a single tab.
+fn hides_single_tab_bar<Tab>(tree: &Tree<Tab>, style: &Style, node_index: NodeIndex) -> bool {
+    tree[node_index].tabs_count() == 1
+        && tree
+            .hide_single_tab_bar(node_index)
+            .unwrap_or(style.hide_single_tab_bars)
+}
 
-        // Deal with Horizontal and Vertical nodes first
-        for node_index in 0..self.tree.len() {
-            let node_index = NodeIndex(node_index);
-            let is_horizontal = self.tree[node_index].is_horizontal();
-            if let Node::Horizontal { fraction, rect } | Node::Vertical { fraction, rect } =
-                &mut self.tree[node_index]
-            {
-                let rect = expand_to_pixel(*rect, pixels_per_point);
+/// How much of the tab bar of the leaf `leaf_id` at `rect` is shown, from `0.0` when it's hidden to `1.0`.
+///
+/// Tab bars are always shown unless `auto_hide` is set, in which case they're revealed while the pointer is over the
+/// top of the leaf, or while a tab is dragged so it can be dropped into them.
+fn tab_bar_reveal(
+    ui: &Ui,
+    metrics: &Metrics,
+    leaf_id: Id,
+    rect: Rect,
+    is_dragging: bool,
+    auto_hide: bool,
+    position: TabBarPosition,
+) -> f32 {
+    if !auto_hide {
+        return 1.0;
+    }
+    let (edge, _) = position.split(rect, metrics.tab_bar_height);
+    let revealed = is_dragging
+        || ui
+            .input()
+            .pointer
+            .hover_pos()
+            .map_or(false, |pos| edge.contains(pos));
+    ui.ctx().animate_bool_with_time(
+        leaf_id.with("tab_bar_reveal"),
+        revealed,
+        metrics.animation_time,
+    )
+}
 
-                let (left, separator, right) = if is_horizontal {
-                    style.hsplit(ui, fraction, rect)
-                } else {
-                    style.vsplit(ui, fraction, rect)
-                };
+/// Width of the previews of inactive tabs shown while their title is hovered.
+const HOVER_PREVIEW_WIDTH: f32 = 240.0;
 
-                ui.painter()
-                    .rect_filled(separator, Rounding::none(), style.separator_color);
+/// Size of the previews of tabs `width` wide shown in a leaf whose body is `body` large, see
+/// [`TabViewer::preview_texture`].
+fn preview_size(body: Vec2, width: f32) -> Vec2 {
+    let aspect = if body.x > 0.0 { body.y / body.x } else { 1.0 };
+    vec2(width, (width * aspect).clamp(60.0, width))
+}
 
-                self.tree[node_index.left()].set_rect(left);
-                self.tree[node_index.right()].set_rect(right);
-            }
+/// Moves the pinned tabs of a leaf ahead of the others, keeping the order within both and the same tab active.
+fn pinned_first<Tab>(tabs: &mut [Tab], active: &mut TabIndex, is_pinned: impl Fn(&Tab) -> bool) {
+    // Nothing to do, and nothing allocated, unless a pinned tab follows one which isn't.
+    if !tabs
+        .iter()
+        .map(&is_pinned)
+        .skip_while(|&pinned| pinned)
+        .any(|pinned| pinned)
+    {
+        return;
+    }
+    let mut next = 0;
+    for index in 0..tabs.len() {
+        if !is_pinned(&tabs[index]) {
+            continue;
+        }
+        tabs[next..=index].rotate_right(1);
+        if active.0 == index {
+            active.0 = next;
+        } else if (next..index).contains(&active.0) {
+            active.0 += 1;
         }
+        next += 1;
+    }
+}
 
-        // Then process Leaf nodes
-        for node_index in 0..self.tree.len() {
-            let node_index = NodeIndex(node_index);
-            if let Node::Leaf {
-                rect,
-                tabs,
-                active,
-                viewport,
-            } = &mut self.tree[node_index]
-            {
-                let rect = *rect;
-                ui.set_clip_rect(rect);
+/// Whether `hovered` has been `true` for the last `delay` seconds, keeping track of it under `id`.
+fn hovered_for(ui: &Ui, id: Id, hovered: bool, delay: f32) -> bool {
+    let since_id = id.with("hovered_since");
+    if !hovered {
+        ui.data().remove::<f64>(since_id);
+        return false;
+    }
+    let time = ui.input().time;
+    let since = *ui.data().get_temp_mut_or(since_id, time);
+    if time - since < delay as f64 {
+        ui.ctx().request_repaint();
+        return false;
+    }
+    true
+}
 
-                let height_topbar = 24.0;
+/// Rows of tabs the tab bar of the leaf `leaf_id` had the last time it was shown, see [`TabOverflow::Wrap`].
+fn tab_bar_rows(state: &State, style: &Style, leaf_id: Id) -> usize {
+    match style.tab_overflow {
+        TabOverflow::Wrap => state.tab_bar_rows.get(&leaf_id).copied().unwrap_or(1),
+        TabOverflow::Scroll | TabOverflow::Shrink => 1,
+    }
+}
 
-                let bottom_y = rect.min.y + height_topbar;
-                let tabbar = rect.intersect(Rect::everything_above(bottom_y));
+/// Id of the group named `group` in the tab bar of the leaf `leaf_id`, see [`TabViewer::group`].
+fn tab_group_id(leaf_id: Id, group: &str) -> Id {
+    leaf_id.with(("tab_group", group))
+}
 
-                let full_response = ui.allocate_rect(rect, Sense::hover());
-                let tabs_response = ui.allocate_rect(tabbar, Sense::hover());
-                let mut tab_hover_rect = None;
+/// Where a tab dropped at `hover` onto `tree` lands, with the rect previewing it.
+fn drop_target<Tab>(tree: &Tree<Tab>, style: &Style, hover: &HoverData) -> (DropTarget, Rect) {
+    // Along the edges of the dock area tabs are docked beside the whole tree. With a single leaf that's what
+    // splitting it does already.
+    let edge = tree
+        .dock_rect()
+        .filter(|_| tree.root().map_or(false, |root| tree[root].is_parent()))
+        .and_then(|dock| edge_drop_zone(style, dock, hover.pointer));
+    if let Some((split, rect)) = edge {
+        return (DropTarget::Edge(split), rect);
+    }
+    match hover.resolve(style) {
+        (Some(split), rect, _) => (DropTarget::Split(split), rect),
+        (None, rect, tab_pos) => (DropTarget::Tabs(tab_pos), rect),
+    }
+}
 
-                // tabs
-                ui.scope(|ui| {
-                    ui.painter().rect_filled(
-                        tabbar,
-                        style.tab_rounding,
-                        style.tab_bar_background_color,
-                    );
+/// The edge of the dock area `pointer` is close enough to for a tab dropped there to be docked along it, with the
+/// rect the new leaf would take, see [`Style::show_edge_drop_zones`].
+fn edge_drop_zone(style: &Style, dock: Rect, pointer: Pos2) -> Option<(Split, Rect)> {
+    if !style.show_edge_drop_zones || !dock.contains(pointer) {
+        return None;
+    }
+    let width = style.edge_drop_zone_width;
+    let (x, y) = (dock.x_range(), dock.y_range());
+    if pointer.x < dock.min.x + width {
+        let max = pos2(lerp(x, EDGE_SPLIT_SHARE), dock.max.y);
+        Some((Split::Left, Rect::from_min_max(dock.min, max)))
+    } else if pointer.x > dock.max.x - width {
+        let min = pos2(lerp(x, 1.0 - EDGE_SPLIT_SHARE), dock.min.y);
+        Some((Split::Right, Rect::from_min_max(min, dock.max)))
+    } else if pointer.y < dock.min.y + width {
+        let max = pos2(dock.max.x, lerp(y, EDGE_SPLIT_SHARE));
+        Some((Split::Above, Rect::from_min_max(dock.min, max)))
+    } else if pointer.y > dock.max.y - width {
+        let min = pos2(dock.min.x, lerp(y, 1.0 - EDGE_SPLIT_SHARE));
+        Some((Split::Below, Rect::from_min_max(min, dock.max)))
+    } else {
+        None
+    }
+}
 
-                    let a = pos2(tabbar.min.x, tabbar.max.y - px);
-                    let b = pos2(tabbar.max.x, tabbar.max.y - px);
-                    ui.painter()
-                        .line_segment([a, b], (px, style.tab_outline_color));
+/// Title of a floating window, which is the title of the active tab of its focused (or first) leaf.
+fn window_title<Tab>(
+    tree: &mut Tree<Tab>,
+    tab_viewer: &mut impl TabViewer<Tab = Tab>,
+) -> WidgetText {
+    let leaf = tree
+        .focused_leaf()
+        .or_else(|| tree.iter().position(Node::is_leaf).map(NodeIndex));
+    if let Some(Node::Leaf { tabs, active, .. }) = leaf.map(|leaf| &mut tree[leaf]) {
+        if let Some(tab) = tabs.get_mut(active.0) {
+            return tab_viewer.title(tab);
+        }
+    }
+    WidgetText::default()
+}
 
-                    let mut ui = ui.child_ui(tabbar, Default::default());
-                    ui.spacing_mut().item_spacing = vec2(0.0, 0.0);
+/// Moves every tab of the closed window `tree` into the `home` leaf of `main`, or its focused leaf if there's none.
+fn return_tabs<Tab>(main: &mut Tree<Tab>, home: Option<NodeIndex>, tree: &mut Tree<Tab>) {
+    for node in tree.iter_mut() {
+        if let Node::Leaf { tabs, .. } = node {
+            for tab in tabs.drain(..) {
+                match home {
+                    Some(home) => main[home].append_tab(tab),
+                    None => main.push_to_focused_leaf(tab),
+                }
+            }
+        }
+    }
+    *tree = Tree::default();
+}
 
+/// Shows a floating window with [`WindowState::always_on_top`] set, above every `egui::Window`.
+///
+/// `egui::Window` is always shown on the middle layer, so this is a window of its own on the foreground layer, with
+/// a title bar holding the collapse and close buttons. The response is shaped like the one of `egui::Window::show`.
+fn show_tool_window(
+    ctx: &Context,
+    id: Id,
+    title: WidgetText,
+    window: &WindowState,
+    open: Option<&mut bool>,
+    add_contents: impl FnOnce(&mut Ui),
+) -> Option<InnerResponse<Option<()>>> {
+    let mut collapsed = window.collapsed;
+    let response = Area::new(id)
+        .order(Order::Foreground)
+        .default_pos(window.rect.min)
+        .show(ctx, |ui| {
+            Frame::window(ui.style())
+                .show(ui, |ui| {
                     ui.horizontal(|ui| {
-                        for (tab_index, tab) in tabs.iter_mut().enumerate() {
-                            let id = Id::new((node_index, tab_index, "tab"));
-                            let tab_index = TabIndex(tab_index);
-                            let is_being_dragged = ui.memory().is_being_dragged(id);
-
-                            let is_active = *active == tab_index || is_being_dragged;
-                            let label = tab_viewer.title(tab);
+                        let icon = if collapsed { "⏵" } else { "⏷" };
+                        if ui.small_button(icon).clicked() {
+                            collapsed = !collapsed;
+                        }
+                        ui.label(title);
+                        if let Some(open) = open {
+                            if ui.small_button("🗙").clicked() {
+                                *open = false;
+                            }
+                        }
+                    });
+                    if collapsed {
+                        return None;
+                    }
+                    ui.separator();
+                    Resize::default()
+                        .id(id.with("resize"))
+                        .default_size(window.rect.size())
+                        .show(ui, add_contents);
+                    Some(())
+                })
+                .inner
+        });
+    Some(response)
+}
 
-                            let response = if is_being_dragged {
-                                let layer_id = LayerId::new(Order::Tooltip, id);
+impl<'tree, Tab> DockArea<'tree, Tab> {
+    pub fn new(tree: &'tree mut Tree<Tab>) -> DockArea<'tree, Tab> {
+        Self {
+            id: Id::new("egui_dock::DockArea"),
+            tree,
+            style: None,
+            announce_changes: false,
+            shortcuts: DockShortcuts::default(),
+            quick_open: false,
+            painter: &StylePainter,
+            windows: &mut [],
+            on_event: None,
+            status_bar: None,
+            empty_area: None,
+            after_pass: None,
+            read_only: false,
+            #[cfg(feature = "persistence")]
+            persist_layout: false,
+            events: Vec::new(),
+        }
+    }
+
+    /// Creates a dock area showing the main surface of `state` and all of its floating windows.
+    pub fn from_state(state: &'tree mut DockState<Tab>) -> DockArea<'tree, Tab> {
+        let (tree, windows) = state.split_main_mut();
+        Self {
+            windows,
+            ..Self::new(tree)
+        }
+    }
+
+    /// Sets the [DockArea] id. Useful if you have more than one [DockArea].
+    pub fn id(mut self, id: Id) -> Self {
+        self.id = id;
+        self
+    }
+
+    /// Sets the [DockArea] id from `id_source`, like [`DockArea::id`].
+    ///
+    /// Every id the dock uses, from separators and tab titles to the state it keeps between frames, is derived from
+    /// it. Two dock areas showing tabs with the same titles need different ids, and so does one tree shown in two
+    /// places. Dock areas shown with the same id in one frame are reported by egui like other id clashes.
+    pub fn id_source(self, id_source: impl std::hash::Hash) -> Self {
+        self.id(Id::new(id_source))
+    }
+
+    /// Sets the dock area style.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    /// Whether layout changes (tabs being activated, closed or moved) should be announced to screen readers.
+    /// By `Default` it's `false`.
+    pub fn announce_changes(mut self, announce_changes: bool) -> Self {
+        self.announce_changes = announce_changes;
+        self
+    }
+
+    /// Sets the keyboard shortcuts handled by the dock area.
+    pub fn shortcuts(mut self, shortcuts: DockShortcuts) -> Self {
+        self.shortcuts = shortcuts;
+        self
+    }
+
+    /// Enables the quick-open palette, a searchable list of every tab opened with [`DockShortcuts::quick_open`].
+    /// By `Default` it's `false`.
+    pub fn quick_open(mut self, quick_open: bool) -> Self {
+        self.quick_open = quick_open;
+        self
+    }
+
+    /// Sets the [`DockPainter`] which paints the tab bars, tabs, separators, borders and drop previews.
+    /// By `Default` it's [`StylePainter`].
+    pub fn painter(mut self, painter: &'tree dyn DockPainter) -> Self {
+        self.painter = painter;
+        self
+    }
+
+    /// Locks the layout: tabs can't be dragged, closed or split off, separators can't be dragged and only the
+    /// shortcuts activating tabs and opening the quick-open palette are handled. Every tab bar shows a lock to
+    /// tell. By `Default` it's `false`, it can be switched from one frame to the next.
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Stores the layout in `egui::Memory`, keyed by the [`DockArea::id`], and restores it the first time the
+    /// dock area is shown, so split fractions and the order of tabs survive restarts of apps persisting their
+    /// memory, like `eframe` does. Tabs are matched by their [`TabViewer::id`], the ones missing from the stored
+    /// layout end up in the focused leaf. By `Default` it's `false`.
+    ///
+    /// Needs the `persistence` feature.
+    #[cfg(feature = "persistence")]
+    pub fn persist_layout(mut self, persist_layout: bool) -> Self {
+        self.persist_layout = persist_layout;
+        self
+    }
+
+    /// Reserves a strip `height` points tall along the bottom of the dock area, below every node, and shows
+    /// `add_contents` in it. Meant for an IDE-like status bar. The strip is painted like a tab bar.
+    pub fn status_bar(mut self, height: f32, add_contents: impl FnOnce(&mut Ui) + 'tree) -> Self {
+        self.status_bar = Some((height, Box::new(add_contents)));
+        self
+    }
+
+    /// Shows `add_contents` behind the body of every leaf without tabs, and over the whole dock area when the tree is
+    /// empty, like the logo or the shortcut cheat sheet IDEs show in their empty editor area, or a hint to drop a
+    /// file or open a panel from the "View" menu.
+    pub fn empty_area(mut self, add_contents: impl FnMut(&mut Ui) + 'tree) -> Self {
+        self.empty_area = Some(Box::new(add_contents));
+        self
+    }
+
+    /// Sets a hook called after each [`DockPass`] of every surface, with the `Ui` the surface is shown in and its
+    /// tree, for showing things of your own between the passes, like an overlay over the tab bars which the bodies
+    /// of the tabs are shown on top of, or a toolbar over the bodies.
+    ///
+    /// The rects of the nodes of the tree are the ones of the frame from [`DockPass::Layout`] on.
+    pub fn after_pass(
+        mut self,
+        hook: impl FnMut(&mut Ui, DockPass, SurfaceIndex, &Tree<Tab>) + 'tree,
+    ) -> Self {
+        self.after_pass = Some(Box::new(hook));
+        self
+    }
+
+    /// Sets a listener called with every [`DockEvent`] of the frame, once the whole dock area is shown.
+    pub fn on_event(mut self, listener: impl FnMut(&DockEvent) + 'tree) -> Self {
+        self.on_event = Some(Box::new(listener));
+        self
+    }
+
+    /// Shows the docking area.
+    ///
+    /// Returns what changed during the frame, the same events the listener set with [`DockArea::on_event`] is
+    /// called with.
+    pub fn show(self, ctx: &Context, tab_viewer: &mut impl TabViewer<Tab = Tab>) -> Vec<DockEvent> {
+        let layer_id = LayerId::background();
+        let max_rect = ctx.available_rect();
+        let clip_rect = ctx.available_rect();
+
+        let mut ui = Ui::new(ctx.clone(), layer_id, self.id, max_rect, clip_rect);
+        self.show_inside(&mut ui, tab_viewer)
+    }
+
+    /// Shows the docking hierarchy inside a `Ui`, like a side panel, a window or part of another layout.
+    ///
+    /// The dock takes up the space still available in `ui` and doesn't paint outside of its clip rect.
+    ///
+    /// Returns what changed during the frame, like [`DockArea::show`].
+    pub fn show_inside(
+        self,
+        ui: &mut Ui,
+        tab_viewer: &mut impl TabViewer<Tab = Tab>,
+    ) -> Vec<DockEvent> {
+        let rect = ui.available_rect_before_wrap();
+        // Two dock areas sharing an id would share their state and the ids of their tabs, egui shows a warning.
+        ui.ctx().check_for_id_clash(self.id, rect, "DockArea");
+        // Leaves clip the `Ui` they're shown in, which mustn't leak out into the one of the caller.
+        let mut dock_ui = ui.child_ui(rect, *ui.layout());
+        dock_ui.set_clip_rect(rect.intersect(ui.clip_rect()));
+        let events = self.show_dock(&mut dock_ui, tab_viewer);
+        ui.allocate_rect(rect, Sense::hover());
+        events
+    }
+
+    fn show_dock(
+        mut self,
+        ui: &mut Ui,
+        tab_viewer: &mut impl TabViewer<Tab = Tab>,
+    ) -> Vec<DockEvent> {
+        profile_function!();
+
+        let style = self
+            .style
+            .take()
+            .unwrap_or_else(|| Style::from_egui(ui.style().as_ref()));
+
+        let mut rect = style.inner_rect(ui.max_rect());
+
+        if let Some(margin) = style.padding {
+            self.painter.border(ui.painter(), &style, rect, margin);
+        }
+
+        if let Some((height, add_contents)) = self.status_bar.take() {
+            let height = height.min(rect.height()).max(0.0);
+            let status_rect = rect.intersect(Rect::everything_below(rect.max.y - height));
+            rect.max.y -= height;
+
+            let px = ui.ctx().pixels_per_point().recip();
+            self.painter.tab_bar(ui.painter(), &style, status_rect, px);
+            let mut ui = ui.child_ui(status_rect, Layout::left_to_right(Align::Center));
+            ui.push_id(self.id.with("status_bar"), add_contents);
+        }
+
+        if self.read_only {
+            self.shortcuts = self.shortcuts.navigation_only();
+        }
+
+        #[cfg(feature = "persistence")]
+        if self.persist_layout {
+            self.restore_layout(ui.ctx(), tab_viewer);
+        }
+
+        let focused_window = self.focused_window(ui.ctx());
+        let mut drag = SurfaceDrag::default();
+        self.show_windows(ui.ctx(), &style, focused_window, &mut drag, tab_viewer);
+
+        #[cfg_attr(not(feature = "persistence"), allow(unused_variables))]
+        let changed = self.show_surface(
+            ui,
+            rect,
+            style.clone(),
+            focused_window.is_none(),
+            SurfaceIndex::main(),
+            &mut drag,
+            tab_viewer,
+        );
+
+        // Drags between dock areas are only half seen by each of them.
+        transfer::share_drag(ui.ctx(), self.id, drag.src);
+        if let (None, Some((surface, hover))) = (drag.src, &drag.dst) {
+            if surface.is_main() {
+                self.drop_from_other_dock(ui, &style, hover);
+            }
+        }
+
+        let dropped = self.drop_between_surfaces(ui, &style, drag, tab_viewer);
+        if dropped {
+            ui.ctx().request_repaint();
+        }
+
+        #[cfg(feature = "persistence")]
+        if self.persist_layout && (changed || dropped) {
+            self.store_layout(ui.ctx(), tab_viewer);
+        }
+
+        if let Some(listener) = &mut self.on_event {
+            for event in &self.events {
+                listener(event);
+            }
+        }
+        self.events
+    }
+
+    /// Applies the layout stored by [`DockArea::store_layout`], once per run of the app.
+    #[cfg(feature = "persistence")]
+    fn restore_layout(&mut self, ctx: &Context, tab_viewer: &mut impl TabViewer<Tab = Tab>) {
+        let restored = self.id.with("layout_restored");
+        if ctx.data().get_temp::<bool>(restored).unwrap_or(false) {
+            return;
+        }
+        ctx.data().insert_temp(restored, true);
+
+        let layout = ctx.data().get_persisted::<Tree<Id>>(self.id.with("layout"));
+        if let Some(layout) = layout {
+            self.tree
+                .apply_layout_with(&layout, |tab| tab_viewer.id(tab));
+        }
+    }
+
+    /// Stores the layout of the main surface, with tabs replaced by their [`TabViewer::id`].
+    #[cfg(feature = "persistence")]
+    fn store_layout(&mut self, ctx: &Context, tab_viewer: &mut impl TabViewer<Tab = Tab>) {
+        // Zen mode only shows part of the layout, the one to come back to is stored instead.
+        if self.tree.zen_mode().is_some() {
+            return;
+        }
+        let mut layout = self.tree.map_tabs(|_| Id::null());
+        for ((_, _, tab), (_, _, id)) in self.tree.tabs_mut().zip(layout.tabs_mut()) {
+            *id = tab_viewer.id(tab);
+        }
+        ctx.data().insert_persisted(self.id.with("layout"), layout);
+    }
+
+    /// Shows the tree of a single surface inside `rect`.
+    ///
+    /// Drags which start or end on another surface are recorded in `drag`. Returns whether the frame changed the
+    /// tree, either through an event or through a change only visible in the next frame.
+    #[allow(clippy::too_many_arguments)]
+    fn show_surface(
+        &mut self,
+        ui: &mut Ui,
+        rect: Rect,
+        style: Style,
+        handle_shortcuts: bool,
+        surface: SurfaceIndex,
+        drag: &mut SurfaceDrag,
+        tab_viewer: &mut impl TabViewer<Tab = Tab>,
+    ) -> bool {
+        self.tree.begin_geometry(rect);
+        if self.tree.is_empty() {
+            ui.allocate_rect(rect, Sense::hover());
+            if let Some(add_contents) = &mut self.empty_area {
+                let mut ui = ui.child_ui(rect, Default::default());
+                ui.set_clip_rect(rect.intersect(ui.clip_rect()));
+                ui.push_id(self.id.with("empty_area"), add_contents);
+            }
+            return false;
+        }
+
+        let focused = self.tree.focused_leaf();
+
+        let mut frame = FrameState::begin(ui, self.id, surface, style);
+        if self.read_only {
+            frame.style.show_close_buttons = false;
+            frame.style.show_add_buttons = false;
+            frame.state.resize_mode = false;
+        }
+
+        let pressed_at = {
+            let pointer = &ui.input().pointer;
+            pointer
+                .any_pressed()
+                .then(|| pointer.interact_pos())
+                .flatten()
+        };
+        if let Some(pos) = pressed_at {
+            frame.state.lost_focus =
+                !rect.contains(pos) || ui.ctx().layer_id_at(pos) != Some(ui.layer_id());
+        }
+
+        if let Some(node) = frame.state.toggle_maximized.take() {
+            if self.tree.zen_mode().is_some() {
+                self.tree.exit_zen_mode();
+            } else if node.0 < self.tree.len() && self.tree[node].is_leaf() {
+                self.tree.set_focused_node(node);
+                self.tree.enter_zen_mode(ZenMode::FocusedLeaf);
+            }
+            frame.changed = true;
+        }
+
+        // The frame is shown in passes, each of which only relies on the ones before it. Things which are shown
+        // on top of the dock belong after `show_contents`, anything affecting the layout before `solve_layout`.
+        let focus = ui.memory().focus();
+        let has_focus = focus.map_or(true, |id| frame.state.focus == Some(id));
+        if handle_shortcuts && !frame.state.lost_focus && has_focus {
+            self.handle_shortcuts(ui, &mut frame, tab_viewer);
+        }
+        self.solve_layout(ui, rect, &mut frame);
+        self.call_after_pass(ui, DockPass::Layout, surface);
+        self.show_separators(ui, &frame);
+        self.call_after_pass(ui, DockPass::Separators, surface);
+        if self.tree.zen_mode() == Some(ZenMode::ActiveTab) {
+            frame.metrics.tab_bar_height = 0.0;
+        } else {
+            self.show_tab_bars(ui, &mut frame, tab_viewer);
+            self.call_after_pass(ui, DockPass::TabBars, surface);
+        }
+        self.show_contents(ui, &mut frame, tab_viewer);
+        self.call_after_pass(ui, DockPass::Contents, surface);
+        // Only the widgets of the dock can take the focus while it's shown.
+        let taken = ui.memory().focus();
+        if taken != focus {
+            frame.state.focus = taken;
+        }
+        if self.read_only {
+            // A drag started before the dock was locked goes nowhere.
+            frame.drag_data = None;
+            frame.hover_data = None;
+        }
+        self.tree.set_dragged_tab(frame.drag_data);
+        self.remove_closed_tabs(&mut frame, tab_viewer);
+        self.show_drag_overlay(ui, &mut frame, tab_viewer);
+        self.call_after_pass(ui, DockPass::DragOverlay, surface);
+
+        // Whatever is left of a drag involves another surface.
+        if let Some((node, tab)) = frame.drag_data.take() {
+            drag.src = Some((surface, node, tab));
+        }
+        if let Some(hover) = frame.hover_data.take() {
+            drag.dst = Some((surface, hover));
+        }
+
+        if self.quick_open {
+            if let Some((node, tab)) = self.show_quick_open(
+                ui,
+                &frame.metrics,
+                rect,
+                &mut frame.state.quick_open,
+                tab_viewer,
+            ) {
+                frame.changed = true;
+                frame
+                    .events
+                    .push(DockEvent::TabActivated { surface, node, tab });
+            }
+        }
+
+        if let Some(focused) = self.tree.focused_leaf() {
+            if let Node::Leaf { tabs, active, .. } = &mut self.tree[focused] {
+                if let Some(tab) = tabs.get_mut(active.0) {
+                    let id = tab_viewer.id(tab);
+                    self.tree.touch_recent(id);
+                }
+            }
+        }
+
+        if self.tree.focused_leaf() != focused {
+            frame.events.push(DockEvent::FocusChanged {
+                surface,
+                node: self.tree.focused_leaf(),
+            });
+        }
+        let changed = frame.changed || !frame.events.is_empty();
+        self.events.append(&mut frame.events);
+
+        frame.end(ui, self.id);
+        changed
+    }
+
+    /// Calls the hook set with [`DockArea::after_pass`] once `pass` of `surface` is done.
+    fn call_after_pass(&mut self, ui: &mut Ui, pass: DockPass, surface: SurfaceIndex) {
+        if let Some(hook) = &mut self.after_pass {
+            hook(ui, pass, surface, self.tree);
+        }
+    }
+
+    /// Id of the floating window at `index` in `windows`.
+    fn window_id(&self, index: usize) -> Id {
+        self.id.with(("window", index))
+    }
+
+    /// Works out which floating window the keyboard shortcuts go to, `None` meaning the main surface.
+    ///
+    /// The surface which was clicked last gets them.
+    fn focused_window(&self, ctx: &Context) -> Option<usize> {
+        let id = self.id.with("focused_window");
+        let mut focused = ctx.data().get_temp::<Option<usize>>(id).flatten();
+
+        let pressed_at = {
+            let pointer = &ctx.input().pointer;
+            if pointer.any_pressed() {
+                pointer.interact_pos()
+            } else {
+                None
+            }
+        };
+        if let Some(pos) = pressed_at {
+            let layer_id = ctx.layer_id_at(pos);
+            focused = (0..self.windows.len()).find(|&index| {
+                let id = self.window_id(index);
+                layer_id == Some(LayerId::new(Order::Middle, id))
+                    || layer_id == Some(LayerId::new(Order::Foreground, id))
+            });
+        }
+
+        ctx.data().insert_temp(id, focused);
+        focused
+    }
+
+    /// Shows every floating window, each with a dock area of its own.
+    fn show_windows(
+        &mut self,
+        ctx: &Context,
+        style: &Style,
+        focused_window: Option<usize>,
+        drag: &mut SurfaceDrag,
+        tab_viewer: &mut impl TabViewer<Tab = Tab>,
+    ) {
+        profile_function!();
+
+        let windows = std::mem::take(&mut self.windows);
+        for (index, surface) in windows.iter_mut().enumerate() {
+            let (tree, window) = match surface {
+                Surface::Window(tree, window) => (tree, window),
+                _ => continue,
+            };
+            let id = self.window_id(index);
+            let title = window_title(tree, tab_viewer);
+
+            let shortcuts = if focused_window == Some(index) {
+                self.shortcuts.clone()
+            } else {
+                DockShortcuts::none()
+            };
+            let mut dock = DockArea {
+                id,
+                tree: &mut *tree,
+                style: None,
+                announce_changes: self.announce_changes,
+                shortcuts,
+                quick_open: false,
+                painter: self.painter,
+                windows: &mut [],
+                on_event: None,
+                status_bar: None,
+                empty_area: None,
+                // Windows call the hook of the dock area they belong to.
+                after_pass: self.after_pass.as_mut().map(|hook| -> PassHook<'_, Tab> {
+                    Box::new(move |ui, pass, surface, tree| hook(ui, pass, surface, tree))
+                }),
+                read_only: self.read_only,
+                #[cfg(feature = "persistence")]
+                persist_layout: false,
+                events: Vec::new(),
+            };
+
+            // Closing a window returns its tabs, which read-only dock areas don't allow.
+            let mut open = true;
+            let closeable = !self.read_only;
+            let add_contents = |ui: &mut Ui| {
+                let rect = ui.max_rect();
+                dock.show_surface(
+                    ui,
+                    rect,
+                    style.clone(),
+                    focused_window == Some(index),
+                    SurfaceIndex(index + 1),
+                    drag,
+                    tab_viewer,
+                );
+                ui.expand_to_include_rect(rect);
+            };
+            let response = if window.always_on_top {
+                let open = closeable.then_some(&mut open);
+                show_tool_window(ctx, id, title, window, open, add_contents)
+            } else {
+                // Windows start out collapsed if they were when the layout was saved, egui keeps track from there.
+                let collapsing_id = id.with("collapsing");
+                if CollapsingState::load(ctx, collapsing_id).is_none() {
+                    CollapsingState::load_with_default_open(ctx, collapsing_id, !window.collapsed)
+                        .store(ctx);
+                }
+                let egui_window = egui::Window::new(title)
+                    .id(id)
+                    .default_pos(window.rect.min)
+                    .default_size(window.rect.size());
+                if closeable {
+                    egui_window.open(&mut open).show(ctx, add_contents)
+                } else {
+                    egui_window.show(ctx, add_contents)
+                }
+            };
+            self.events.append(&mut dock.events);
+            // The dock borrows the window's tree, release it before tabs are returned or the surface is emptied.
+            drop(dock);
+            if let Some(response) = response {
+                window.collapsed = response.inner.is_none();
+                if !window.collapsed {
+                    window.rect = response.response.rect;
+                }
+            }
+
+            if !open {
+                let home = window
+                    .home
+                    .and_then(|home| self.tree.node_index(home))
+                    .filter(|&home| self.tree[home].is_leaf());
+                return_tabs(self.tree, home, tree);
+            }
+
+            if tree.is_empty() {
+                *surface = Surface::Empty;
+            }
+        }
+        self.windows = windows;
+    }
+
+    /// Returns the tree of the surface at `surface`, or `None` if there's no surface there.
+    fn surface_tree(&mut self, surface: SurfaceIndex) -> Option<&mut Tree<Tab>> {
+        if surface.is_main() {
+            Some(&mut *self.tree)
+        } else {
+            self.windows.get_mut(surface.0 - 1)?.tree_mut()
+        }
+    }
+
+    /// Previews where a tab dragged from one surface onto another would be dropped, and moves it there once it's
+    /// released.
+    ///
+    /// Returns `true` if the tab was moved, `false` otherwise.
+    fn drop_between_surfaces(
+        &mut self,
+        ui: &Ui,
+        style: &Style,
+        drag: SurfaceDrag,
+        tab_viewer: &mut impl TabViewer<Tab = Tab>,
+    ) -> bool {
+        let ((src_surface, src, tab_index), (dst_surface, hover)) = match (drag.src, drag.dst) {
+            (Some(src), Some(dst)) if src.0 != dst.0 => (src, dst),
+            _ => return false,
+        };
+        let dst = hover.dst;
+        let (target, helper, tap_pos) = hover.resolve(style);
+
+        let layer_id = LayerId::new(Order::Foreground, self.id.with("drop_preview"));
+        let painter = ui.ctx().layer_painter(layer_id);
+        self.painter.drop_preview(&painter, style, helper);
+        hover.paint_drop_targets(&painter, style, self.painter);
+
+        if !ui.input().pointer.any_released() {
+            return false;
+        }
+
+        let target = match target {
+            Some(split) => DropTarget::Split(split),
+            None => DropTarget::Tabs(tap_pos),
+        };
+        let mut events = std::mem::take(&mut self.events);
+        let announcement = self.drop_dragged_tab(
+            (src_surface, src, tab_index),
+            (dst_surface, dst),
+            target,
+            None,
+            &mut events,
+            tab_viewer,
+        );
+        self.events = events;
+        let announcement = match announcement {
+            Some(announcement) => announcement,
+            None => return false,
+        };
+        if self.announce_changes {
+            ui.ctx()
+                .output()
+                .events
+                .push(OutputEvent::ValueChanged(WidgetInfo::labeled(
+                    WidgetType::Other,
+                    announcement,
+                )));
+        }
+
+        if let Some(window) = src_surface.0.checked_sub(1) {
+            if matches!(&self.windows[window], Surface::Window(tree, _) if tree.is_empty()) {
+                self.windows[window] = Surface::Empty;
+            }
+        }
+        true
+    }
+
+    /// Previews where a tab dragged from another dock area would be dropped onto the main surface, and records the
+    /// drop once it's released for [`transfer_tabs`] to carry out.
+    fn drop_from_other_dock(&self, ui: &Ui, style: &Style, hover: &HoverData) {
+        let drag = match transfer::dragged_tab(ui.ctx()) {
+            Some(drag) if drag.dock != self.id => drag,
+            _ => return,
+        };
+        let (target, helper) = drop_target(self.tree, style, hover);
+
+        let layer_id = LayerId::new(Order::Foreground, self.id.with("drop_preview"));
+        let painter = ui.ctx().layer_painter(layer_id);
+        self.painter.drop_preview(&painter, style, helper);
+        if !matches!(target, DropTarget::Edge(_)) {
+            hover.paint_drop_targets(&painter, style, self.painter);
+        }
+
+        if ui.input().pointer.any_released() {
+            transfer::drop_tab(ui.ctx(), drag, self.id, hover.dst, target);
+            ui.ctx().request_repaint();
+        }
+    }
+
+    /// Solves the layout, letting the separators be dragged.
+    fn solve_layout(&mut self, ui: &mut Ui, rect: Rect, frame: &mut FrameState) {
+        profile_function!();
+
+        let FrameState {
+            surface,
+            state,
+            style,
+            metrics,
+            events,
+            changed,
+            ..
+        } = frame;
+        let pixels_per_point = metrics.pixels_per_point;
+        let separator_width = metrics.separator_width;
+
+        // Separators whose fraction was changed from code are shown on their way to it. The fractions they're
+        // moving to are put back once the layout is solved.
+        let animating = self.animate_separators(ui, metrics, state);
+        *changed |= !animating.is_empty();
+        self.animate_collapsing(ui, metrics);
+
+        // Solve the layout first, without touching the `Ui`.
+        self.tree.layout_with_min_size(
+            rect,
+            separator_width,
+            pixels_per_point,
+            style.min_node_size,
+            vec2(style.vertical_tab_bar_width, metrics.tab_bar_height),
+        );
+
+        // Then let the separators be dragged, and solve the layout again if any of them moved.
+        let mut resized = false;
+        let read_only = self.read_only;
+        for node_index in self.tree.node_indices().filter(|_| !read_only) {
+            profile_scope!("separator");
+            let separator =
+                match self
+                    .tree
+                    .split_rects(node_index, separator_width, pixels_per_point)
+                {
+                    Some((_, separator, _)) => separator,
+                    None => continue,
+                };
+            let is_horizontal = self.tree[node_index].is_horizontal();
+            let id = self.id.with((self.tree.node_id(node_index), "separator"));
+
+            // Only the separator under the pointer or being dragged can move, the others aren't interacted with.
+            let hit_rect = style.separator_hit_rect(separator, is_horizontal);
+            let under_pointer = ui
+                .input()
+                .pointer
+                .interact_pos()
+                .map_or(false, |pos| hit_rect.contains(pos));
+            if !under_pointer && !ui.memory().is_being_dragged(id) {
+                continue;
+            }
+
+            let dragged = match &mut self.tree[node_index] {
+                Node::Horizontal { fraction, rect } | Node::Vertical { fraction, rect } => {
+                    let rect = expand_to_pixel(*rect, pixels_per_point);
+                    style
+                        .drag_separator(ui, id, fraction, rect, separator, is_horizontal)
+                        .then_some(*fraction)
+                }
+                _ => None,
+            };
+
+            if let Some(fraction) = dragged {
+                resized = true;
+                let linked = self.tree.align_linked_separators(node_index);
+                for (node_index, fraction) in std::iter::once((node_index, fraction)).chain(linked)
+                {
+                    if metrics.separator_animation_time > 0.0 {
+                        let id = self.id.with((self.tree.node_id(node_index), "separator"));
+                        let time = ui.input().time;
+                        state
+                            .separator_animations
+                            .insert(id, SeparatorAnimation::still(fraction, time));
+                    }
+                    events.push(DockEvent::SplitResized {
+                        surface: *surface,
+                        node: node_index,
+                        fraction,
+                    });
+                }
+            }
+        }
+        if resized {
+            self.tree.layout_with_min_size(
+                rect,
+                separator_width,
+                pixels_per_point,
+                style.min_node_size,
+                vec2(style.vertical_tab_bar_width, metrics.tab_bar_height),
+            );
+        }
+
+        for (node_index, to) in animating {
+            let id = self.id.with((self.tree.node_id(node_index), "separator"));
+            let dragged = state
+                .separator_animations
+                .get(&id)
+                .map_or(false, |animation| animation.from == animation.to);
+            if let Node::Horizontal { fraction, .. } | Node::Vertical { fraction, .. } =
+                &mut self.tree[node_index]
+            {
+                if !dragged {
+                    *fraction = to;
+                }
+            }
+        }
+    }
+
+    /// Swaps the fraction of every separator moving to a fraction changed from code for the one it's shown at.
+    ///
+    /// Returns the nodes whose fractions were swapped, with the fractions they're moving to.
+    fn animate_separators(
+        &mut self,
+        ui: &Ui,
+        metrics: &Metrics,
+        state: &mut State,
+    ) -> Vec<(NodeIndex, f32)> {
+        let animation_time = metrics.separator_animation_time;
+        if animation_time <= 0.0 {
+            state.separator_animations.clear();
+            return Vec::new();
+        }
+
+        let time = ui.input().time;
+        let mut previous = std::mem::take(&mut state.separator_animations);
+        let mut animating = Vec::new();
+        for node_index in self.tree.node_indices() {
+            let id = self.id.with((self.tree.node_id(node_index), "separator"));
+            if let Node::Horizontal { fraction, .. } | Node::Vertical { fraction, .. } =
+                &mut self.tree[node_index]
+            {
+                let mut animation = previous
+                    .remove(&id)
+                    .unwrap_or_else(|| SeparatorAnimation::still(*fraction, time));
+                if animation.to != *fraction {
+                    animation = SeparatorAnimation {
+                        from: animation.fraction(time, animation_time),
+                        to: *fraction,
+                        start_time: time,
+                    };
+                }
+
+                let shown = animation.fraction(time, animation_time);
+                if shown != animation.to {
+                    animating.push((node_index, animation.to));
+                    *fraction = shown;
+                }
+                state.separator_animations.insert(id, animation);
+            }
+        }
+        animating
+    }
+
+    /// Records how far every leaf which can be collapsed is on its way to or from being collapsed.
+    fn animate_collapsing(&mut self, ui: &Ui, metrics: &Metrics) {
+        for node_index in self.tree.node_indices() {
+            if !self.tree.can_collapse(node_index) {
+                continue;
+            }
+            let collapsed = self.tree.is_collapsed(node_index);
+            let progress = if metrics.collapse_animation_time > 0.0 {
+                let id = self.id.with((self.tree.node_id(node_index), "collapse"));
+                ui.ctx()
+                    .animate_bool_with_time(id, collapsed, metrics.collapse_animation_time)
+            } else if collapsed {
+                1.0
+            } else {
+                0.0
+            };
+            self.tree.set_collapse_progress(node_index, progress);
+        }
+    }
+
+    /// Paints the separators between the nodes.
+    fn show_separators(&self, ui: &Ui, frame: &FrameState) {
+        let FrameState { style, metrics, .. } = frame;
+
+        for node_index in self.tree.node_indices() {
+            if let Some((_, separator, _)) = self.tree.split_rects(
+                node_index,
+                metrics.separator_width,
+                metrics.pixels_per_point,
+            ) {
+                if ui.is_rect_visible(separator) {
+                    let node_style = self.tree.node_style(node_index).unwrap_or(style);
+                    let id = self.id.with((self.tree.node_id(node_index), "separator"));
+                    let horizontal = self.tree[node_index].is_horizontal();
+                    // Separators are dragged by the frame style, whose hit area counts.
+                    let hit_rect = style.separator_hit_rect(separator, horizontal);
+                    let state = SeparatorState {
+                        horizontal,
+                        hovered: ui.rect_contains_pointer(hit_rect),
+                        dragged: ui.memory().is_being_dragged(id),
+                    };
+                    self.painter
+                        .separator(ui.painter(), node_style, separator, state);
+                }
+            }
+        }
+    }
+
+    /// Shows the tab bar of every leaf, working out which tab is being dragged and where it would be dropped.
+    fn show_tab_bars(
+        &mut self,
+        ui: &mut Ui,
+        frame: &mut FrameState,
+        tab_viewer: &mut impl TabViewer<Tab = Tab>,
+    ) {
+        profile_function!();
+
+        let focused = self.tree.focused_leaf();
+        let is_dragging = frame.is_dragging;
+        let FrameState {
+            surface,
+            state,
+            style,
+            metrics,
+            to_remove,
+            close_tabs,
+            new_focused,
+            announcements,
+            events,
+            drag_data,
+            hover_data,
+            changed,
+            ..
+        } = frame;
+        let px = metrics.px;
+        let clip_rect = ui.clip_rect();
+        let mut title_rects = std::mem::take(&mut state.title_rects);
+        title_rects.clear();
+        let mut tab_bar_rects = Vec::new();
+        let mut collapse = Vec::new();
+        let mut added = Vec::new();
+        if !is_dragging {
+            state.cancelled_drag = None;
+        }
+
+        // Only the widths of tabs still in the tree are kept, those of closed tabs are dropped with `previous_widths`.
+        let mut previous_widths = std::mem::take(&mut state.tab_widths);
+        // Likewise for how far the tab bars are scrolled and how many rows they wrap to, dropped along with their
+        // leaf.
+        let mut previous_scroll = std::mem::take(&mut state.tab_bar_scroll);
+        let mut previous_rows = std::mem::take(&mut state.tab_bar_rows);
+        state.previews.forget_closed();
+
+        // Then process Leaf nodes
+        for node_index in self.tree.node_indices() {
+            let leaf_id = self.id.with((self.tree.node_id(node_index), "leaf"));
+            let is_mdi = self.tree.is_mdi(node_index);
+            let single = self.tree[node_index].is_leaf()
+                && hides_single_tab_bar(self.tree, style, node_index);
+            let node_style = self.tree.shared_node_style(node_index).cloned();
+            let style = node_style.as_deref().unwrap_or(&*style);
+            let position = self.tree.shown_tab_bar_position(node_index, style);
+            let can_collapse = !self.read_only && self.tree.can_collapse(node_index);
+            let collapsed = self.tree.is_collapsed(node_index);
+            if let Node::Leaf {
+                rect, tabs, active, ..
+            } = &mut self.tree[node_index]
+            {
+                let rect = *rect;
+                pinned_first(tabs, active, |tab| tab_viewer.is_pinned(tab));
+                let scroll_id = leaf_id.with("tab_bar_scroll");
+                if let Some(scroll) = previous_scroll.remove(&scroll_id) {
+                    state.tab_bar_scroll.insert(scroll_id, scroll);
+                }
+                if let Some(rows) = previous_rows.remove(&leaf_id) {
+                    state.tab_bar_rows.insert(leaf_id, rows);
+                }
+                for tab in tabs.iter_mut() {
+                    let id = self.id.with(tab_viewer.id(tab));
+                    if let Some(width) = previous_widths.remove(&id) {
+                        state.tab_widths.insert(id, width);
+                    }
+                    state.previews.keep(id);
+                }
+                // Tab bars of leaves out of sight aren't laid out at all.
+                if !rect.intersects(clip_rect) {
+                    continue;
+                }
+                let prev_active = *active;
+                ui.set_clip_rect(rect.intersect(clip_rect));
+
+                // Leaves showing their tabs as child windows have no tab bar, and neither do leaves whose tab bar is
+                // hidden, but tabs can still be dropped into them.
+                let auto_hide = style.auto_hide_tab_bars || single;
+                let reveal =
+                    tab_bar_reveal(ui, metrics, leaf_id, rect, is_dragging, auto_hide, position);
+                if is_mdi || reveal <= 0.0 {
+                    if single && !is_mdi {
+                        // The handle reveals the tab bar on hover, from which the tab can be dragged.
+                        let (handle, _) = position.split(rect, TAB_BAR_HANDLE_HEIGHT);
+                        self.painter.tab_bar(ui.painter(), style, handle, px);
+                    }
+                    let full_response = ui.interact(rect, leaf_id, Sense::hover());
+                    if is_dragging && full_response.hovered() {
+                        *hover_data = ui.input().pointer.hover_pos().map(|pointer| HoverData {
+                            rect,
+                            dst: node_index,
+                            tabs: None,
+                            tab: None,
+                            pointer,
+                            vertical: false,
+                        });
+                    }
+                    continue;
+                }
+
+                // Vertical tab bars stack their tabs top to bottom in a single column.
+                let vertical = position.is_vertical();
+                let wrap = style.tab_overflow == TabOverflow::Wrap && !vertical;
+                let rows = tab_bar_rows(state, style, leaf_id);
+                let height_topbar = if vertical {
+                    style.vertical_tab_bar_width
+                } else {
+                    metrics.tab_bar_height * rows as f32
+                };
+
+                // Hidden tab bars slide in from beyond their edge of the leaf.
+                let hidden = (1.0 - reveal) * height_topbar;
+                let (tabbar, _) = position.split(rect, height_topbar);
+                let tabbar = match position {
+                    TabBarPosition::Top => tabbar.translate(vec2(0.0, -hidden)),
+                    TabBarPosition::Bottom => tabbar.translate(vec2(0.0, hidden)),
+                    TabBarPosition::Left => tabbar.translate(vec2(-hidden, 0.0)),
+                    TabBarPosition::Right => tabbar.translate(vec2(hidden, 0.0)),
+                };
+                let body_size = if vertical {
+                    vec2(rect.width() - height_topbar, rect.height())
+                } else {
+                    vec2(rect.width(), rect.height() - height_topbar)
+                };
+                self.painter.tab_bar(ui.painter(), style, tabbar, px);
+
+                // The widgets of the app at either end of the tab bar are laid out first, the tabs get what's left.
+                // Vertical tab bars have them at their top and bottom.
+                let leading = {
+                    let layout = if vertical {
+                        Layout::top_down(Align::Min)
+                    } else {
+                        style.row_layout()
+                    };
+                    let mut ui = ui.child_ui(tabbar, layout);
+                    tab_viewer.tab_bar_leading_ui(&mut ui, node_index);
+                    if vertical {
+                        ui.min_rect().height()
+                    } else {
+                        ui.min_rect().width()
+                    }
+                };
+                let trailing = {
+                    let layout = if vertical {
+                        Layout::bottom_up(Align::Min)
+                    } else if style.rtl {
+                        Layout::left_to_right(Align::Center)
+                    } else {
+                        Layout::right_to_left(Align::Center)
+                    };
+                    let mut ui = ui.child_ui(tabbar, layout);
+                    tab_viewer.tab_bar_trailing_ui(&mut ui, node_index);
+                    if vertical {
+                        ui.min_rect().height()
+                    } else {
+                        ui.min_rect().width()
+                    }
+                };
+                let (left, right) = if style.rtl {
+                    (trailing, leading)
+                } else {
+                    (leading, trailing)
+                };
+                let mut bar = tabbar;
+                if vertical {
+                    bar.min.y = (bar.min.y + leading).min(bar.max.y);
+                    bar.max.y = (bar.max.y - trailing).max(bar.min.y);
+                } else {
+                    bar.min.x = (bar.min.x + left).min(bar.max.x);
+                    bar.max.x = (bar.max.x - right).max(bar.min.x);
+                }
+
+                // Shrunk tabs share out the width of the tab bar.
+                let tab_max_width =
+                    if style.tab_overflow == TabOverflow::Shrink && !vertical && !tabs.is_empty() {
+                        let add_button = if style.show_add_buttons {
+                            tabbar.height()
+                        } else {
+                            0.0
+                        };
+                        let share = ((bar.width() - add_button) / tabs.len() as f32).max(0.0);
+                        Some(style.tab_max_width.map_or(share, |max| max.min(share)))
+                    } else {
+                        style.tab_max_width
+                    };
+
+                // Tabs which don't fit are listed in a menu opened from the end of the tab bar, unless they wrap.
+                // Stacked tabs are all as high as a horizontal tab bar, and only scroll.
+                let extent = |width: f32| {
+                    if vertical {
+                        metrics.tab_bar_height
+                    } else {
+                        width
+                    }
+                };
+                let mut tabs_width = 0.0;
+                let mut unmeasured = false;
+                let mut prev_group = None;
+                for (tab_index, tab) in tabs.iter_mut().enumerate() {
+                    // The labels of tab groups take room too, and collapsed groups only show their active tab.
+                    let group = style
+                        .collapsible_tab_groups
+                        .then(|| tab_viewer.group(tab))
+                        .flatten();
+                    let group_id = group.map(|group| tab_group_id(leaf_id, group));
+                    if group_id.is_some() && group_id != prev_group {
+                        tabs_width += group_id
+                            .and_then(|group_id| state.tab_widths.get(&group_id))
+                            .map_or(0.0, |&width| extent(width));
+                    }
+                    prev_group = group_id;
+                    let collapsed_group =
+                        group_id.map_or(false, |id| state.collapsed_tab_groups.contains(&id));
+                    if collapsed_group && tab_index != active.0 {
+                        continue;
+                    }
+                    match state.tab_widths.get(&self.id.with(tab_viewer.id(tab))) {
+                        Some(&width) => tabs_width += extent(width),
+                        None if vertical => tabs_width += metrics.tab_bar_height,
+                        None => unmeasured = true,
+                    }
+                }
+                if style.show_add_buttons {
+                    tabs_width += metrics.tab_bar_height;
+                }
+                let overflows = !wrap
+                    && !vertical
+                    && (tabs_width > bar.width() || unmeasured && tabs_width >= bar.width());
+                let tab_list_rect = overflows.then(|| {
+                    let size = Vec2::splat(bar.height());
+                    let x = if style.rtl {
+                        bar.min.x
+                    } else {
+                        bar.max.x - size.x
+                    };
+                    Rect::from_min_size(pos2(x, bar.min.y), size)
+                });
+                let tabs_rect = match tab_list_rect {
+                    Some(tab_list_rect) if style.rtl => {
+                        bar.intersect(Rect::everything_right_of(tab_list_rect.max.x))
+                    }
+                    Some(tab_list_rect) => {
+                        bar.intersect(Rect::everything_left_of(tab_list_rect.min.x))
+                    }
+                    None => bar,
+                };
+
+                // Tab bars whose tabs don't fit scroll sideways, with the mouse wheel or by dragging their empty part.
+                // Scrolling goes the other way from right to left, and vertical tab bars scroll down.
+                let scroll_dir = if style.rtl { -1.0 } else { 1.0 };
+                let max_scroll = if wrap {
+                    0.0
+                } else if vertical {
+                    (tabs_width - tabs_rect.height()).max(0.0)
+                } else {
+                    (tabs_width - tabs_rect.width()).max(0.0)
+                };
+                let mut scroll = state.tab_bar_scroll.get(&scroll_id).copied().unwrap_or(0.0);
+                if ui.rect_contains_pointer(tabs_rect) {
+                    let delta = ui.input().scroll_delta;
+                    scroll -= if vertical {
+                        delta.y
+                    } else {
+                        scroll_dir * delta.x + delta.y
+                    };
+                }
+                scroll = scroll.clamp(0.0, max_scroll);
+
+                let full_response = ui.interact(rect, leaf_id, Sense::hover());
+                let tabs_response = ui.interact(tabbar, leaf_id.with("tab_bar"), Sense::hover());
+                tab_bar_rects.push((node_index, tabbar));
+                let mut tab_hover_rect = None;
+                let tabs_len = tabs.len();
+                let mut menu_close = None;
+                let mut menu_collapse = false;
+                let mut focus_step = None;
+                let mut tabs_height = metrics.tab_bar_height;
+
+                // tabs
+                ui.scope(|ui| {
+                    profile_scope!("tab bar");
+                    ui.style_mut().animation_time = metrics.animation_time;
+
+                    let scrolled = if vertical {
+                        tabs_rect.translate(vec2(0.0, -scroll))
+                    } else {
+                        tabs_rect.translate(vec2(-scroll_dir * scroll, 0.0))
+                    };
+                    let mut ui = ui.child_ui(scrolled, Default::default());
+                    ui.set_clip_rect(tabs_rect.intersect(ui.clip_rect()));
+                    ui.spacing_mut().item_spacing = vec2(0.0, 0.0);
+
+                    // Stacked tabs fill the width of the tab bar, long titles are clipped at its edge.
+                    let layout = if vertical {
+                        let align = if style.rtl { Align::Max } else { Align::Min };
+                        Layout::top_down_justified(align)
+                    } else {
+                        style.row_layout().with_main_wrap(wrap)
+                    };
+                    ui.with_layout(layout, |ui| {
+                        let clip_rect = ui.clip_rect();
+                        let mut prev_group = None;
+                        for (tab_index, tab) in tabs.iter_mut().enumerate() {
+                            let id = self.id.with(tab_viewer.id(tab));
+                            let tab_index = TabIndex(tab_index);
+
+                            // Runs of tabs of a group start with its label, which collapses them when clicked.
+                            let group = tab_viewer.group(tab);
+                            // Groups are told apart by their id, the name is only borrowed while it's shown.
+                            let group_key = group.map(|group| tab_group_id(leaf_id, group));
+                            let group_id = group_key.filter(|_| style.collapsible_tab_groups);
+                            let group_color = group.map(|group| style.tab_group_color(group));
+                            let starts_group = group_key != prev_group;
+                            prev_group = group_key;
+                            if let (Some(name), Some(group_id)) = (group, group_id) {
+                                let collapsed = state.collapsed_tab_groups.contains(&group_id);
+                                if starts_group {
+                                    let galley = galley_cache::title_galley(
+                                        ui,
+                                        name.into(),
+                                        TextStyle::Small,
+                                    );
+                                    let size = vec2(galley.size().x + 16.0, metrics.tab_bar_height);
+                                    let (rect, response) =
+                                        ui.allocate_exact_size(size, Sense::click());
+                                    response.widget_info(|| {
+                                        WidgetInfo::labeled(WidgetType::Button, name)
+                                    });
+                                    self.painter.tab_group_label(
+                                        ui.painter(),
+                                        style,
+                                        rect,
+                                        galley,
+                                        group_color.unwrap_or_default(),
+                                        collapsed,
+                                    );
+                                    if response.clicked()
+                                        && !state.collapsed_tab_groups.remove(&group_id)
+                                    {
+                                        state.collapsed_tab_groups.insert(group_id);
+                                    }
+                                    state.tab_widths.insert(group_id, rect.width());
+                                }
+                                if collapsed && tab_index != *active {
+                                    continue;
+                                }
+                            }
+                            let is_being_dragged =
+                                cfg!(feature = "drag_and_drop") && ui.memory().is_being_dragged(id);
+
+                            // Tabs which can't be seen aren't measured, laid out or painted. Their widths from
+                            // previous frames keep the visible tabs in place.
+                            if !is_being_dragged && !ui.memory().has_focus(id) {
+                                let (cursor, clip) = if vertical {
+                                    (ui.cursor().min.y, clip_rect.y_range())
+                                } else {
+                                    (ui.cursor().min.x, clip_rect.x_range())
+                                };
+                                if cursor > *clip.end() {
+                                    continue;
+                                }
+                                if let Some(&width) = state.tab_widths.get(&id) {
+                                    if cursor + extent(width) < *clip.start() {
+                                        ui.allocate_space(vec2(width, metrics.tab_bar_height));
+                                        continue;
+                                    }
+                                }
+                            }
+
+                            let is_active = *active == tab_index || is_being_dragged;
+                            let title_changed = tab_viewer.title_changed(tab);
+                            let galley = tab_title_galley(
+                                ui,
+                                id,
+                                title_changed,
+                                || tab_viewer.title(tab),
+                                TextStyle::Button,
+                            );
+                            let closeable = tab_viewer.closeable(tab);
+                            let modified = tab_viewer.is_modified(tab);
+                            let icon = tab_viewer.tab_icon(tab);
+                            // Compact pinned tabs only show their icon.
+                            let pinned = tab_viewer.is_pinned(tab);
+                            let compact = pinned && style.compact_pinned_tabs && icon.is_some();
+                            let no_title = compact.then(|| {
+                                galley_cache::title_galley(ui, "".into(), TextStyle::Button)
+                            });
+                            let badge = tab_viewer.badge(tab).map(|badge| {
+                                galley_cache::title_galley(ui, badge, TextStyle::Small)
+                            });
+
+                            let response = if is_being_dragged {
+                                let layer_id = LayerId::new(Order::Tooltip, id);
                                 let response = ui
                                     .with_layer_id(layer_id, |ui| {
-                                        style.tab_title(
+                                        style.faded(style.drag_preview_opacity).tab_title(
                                             ui,
-                                            label.clone(),
+                                            metrics,
+                                            tab_max_width,
+                                            no_title.clone().unwrap_or_else(|| galley.clone()),
+                                            &mut |ui| !compact && tab_viewer.tab_title_ui(ui, tab),
+                                            icon,
+                                            badge,
                                             is_active,
                                             is_active && Some(node_index) == focused,
                                             is_being_dragged,
+                                            closeable && !compact,
+                                            modified,
                                             id,
+                                            self.painter,
                                         )
                                     })
                                     .response;
@@ -353,65 +2496,279 @@ impl<'tree, Tab> DockArea<'tree, Tab> {
                                     .interact(response.rect, id, sense)
                                     .on_hover_cursor(CursorIcon::Grabbing);
 
-                                if let Some(pointer_pos) = ui.ctx().pointer_interact_pos() {
+                                if ui.input().key_pressed(Key::Escape) {
+                                    state.cancelled_drag = Some(id);
+                                }
+                                let pointer_pos = ui
+                                    .ctx()
+                                    .pointer_interact_pos()
+                                    .filter(|_| state.cancelled_drag != Some(id));
+                                if let Some(pointer_pos) = pointer_pos {
                                     let center = response.rect.center();
                                     let start = state.drag_start.unwrap_or(center);
 
                                     let delta = pointer_pos - start;
-                                    if delta.x.abs() > 30.0 || delta.y.abs() > 6.0 {
+                                    let threshold = style.tab_drag_threshold;
+                                    if delta.x.abs() > threshold.x || delta.y.abs() > threshold.y {
                                         ui.ctx().translate_layer(layer_id, delta);
 
-                                        drag_data = Some((node_index, tab_index));
+                                        // The picture of the content hangs under the title.
+                                        let texture = tab_viewer
+                                            .preview_texture(tab)
+                                            .or_else(|| state.previews.texture(id));
+                                        if let Some(texture) = texture {
+                                            let ghost = Rect::from_min_size(
+                                                response.rect.translate(delta).left_bottom(),
+                                                preview_size(body_size, style.drag_preview_width),
+                                            );
+                                            ui.ctx()
+                                                .layer_painter(LayerId::new(
+                                                    Order::Tooltip,
+                                                    id.with("preview"),
+                                                ))
+                                                .add(Shape::image(
+                                                    texture,
+                                                    ghost,
+                                                    Rect::from_min_max(Pos2::ZERO, pos2(1.0, 1.0)),
+                                                    Color32::WHITE.linear_multiply(
+                                                        style.drag_preview_opacity,
+                                                    ),
+                                                ));
+                                        }
+
+                                        *drag_data = Some((node_index, tab_index));
                                     }
                                 }
 
                                 if response.clicked() {
+                                    if self.announce_changes && *active != tab_index {
+                                        announcements.push(format!(
+                                            "{} tab activated",
+                                            tab_viewer.title(tab).text()
+                                        ));
+                                    }
                                     *active = tab_index;
-                                    new_focused = Some(node_index);
+                                    *new_focused = Some(node_index);
                                 }
 
-                                if response.middle_clicked() && style.show_close_buttons {
+                                if response.middle_clicked()
+                                    && style.close_on_middle_click
+                                    && style.shows_close_buttons()
+                                    && closeable
+                                {
                                     if tab_viewer.on_close(tab) {
                                         to_remove.push((node_index, tab_index));
                                     } else {
                                         *active = tab_index;
-                                        new_focused = Some(node_index);
+                                        *new_focused = Some(node_index);
                                     }
                                 }
 
                                 response
                             } else {
+                                let title = galley.galley.clone();
                                 let response = style.tab_title(
                                     ui,
-                                    label,
+                                    metrics,
+                                    tab_max_width,
+                                    no_title.unwrap_or(galley),
+                                    &mut |ui| !compact && tab_viewer.tab_title_ui(ui, tab),
+                                    icon,
+                                    badge,
                                     is_active && Some(node_index) == focused,
                                     is_active,
                                     is_being_dragged,
+                                    closeable && !compact,
+                                    modified,
                                     id,
+                                    self.painter,
                                 );
 
-                                let sense = if response.1 {
+                                let sense = if response.1
+                                    || self.read_only
+                                    || !cfg!(feature = "drag_and_drop")
+                                {
                                     Sense::click()
                                 } else {
                                     Sense::click_and_drag()
                                 };
 
-                                if response.2 {
-                                    if tab_viewer.on_close(tab) {
-                                        to_remove.push((node_index, tab_index));
+                                if response.2 {
+                                    if tab_viewer.on_close(tab) {
+                                        to_remove.push((node_index, tab_index));
+                                    } else {
+                                        *active = tab_index;
+                                        *new_focused = Some(node_index);
+                                    }
+                                }
+                                let mut response = ui.interact(response.0.rect, id, sense);
+                                response.widget_info(|| {
+                                    WidgetInfo::selected(
+                                        WidgetType::SelectableLabel,
+                                        is_active,
+                                        format!(
+                                            "Tab {} of {}, {}",
+                                            tab_index.0 + 1,
+                                            tabs_len,
+                                            title.text()
+                                        ),
+                                    )
+                                });
+                                let hovered = !is_dragging && response.hovered();
+                                let preview = (hovered && !is_active)
+                                    .then(|| {
+                                        tab_viewer
+                                            .preview_texture(tab)
+                                            .or_else(|| state.previews.texture(id))
+                                    })
+                                    .flatten();
+                                if compact {
+                                    response = response.on_hover_text(title.text());
+                                }
+                                let tooltip = style.show_tab_tooltips
+                                    && hovered_for(ui, id, hovered, style.tab_tooltip_delay);
+                                if tooltip || preview.is_some() {
+                                    response = response.on_hover_ui(|ui| {
+                                        if tooltip {
+                                            tab_viewer.tab_tooltip_ui(ui, tab);
+                                        }
+                                        if let Some(texture) = preview {
+                                            ui.image(
+                                                texture,
+                                                preview_size(body_size, HOVER_PREVIEW_WIDTH),
+                                            );
+                                        }
+                                    });
+                                }
+                                if response.drag_started() {
+                                    state.drag_start = response.hover_pos();
+                                }
+
+                                // Keyboard activation, Enter and Space are reported as clicks.
+                                if response.clicked() {
+                                    if self.announce_changes && *active != tab_index {
+                                        announcements.push(format!(
+                                            "{} tab activated",
+                                            tab_viewer.title(tab).text()
+                                        ));
+                                    }
+                                    *active = tab_index;
+                                    *new_focused = Some(node_index);
+                                }
+
+                                if response.double_clicked() {
+                                    tab_viewer.on_tab_double_click(tab);
+                                    if style.maximize_on_double_click {
+                                        state.toggle_maximized = Some(node_index);
+                                        ui.ctx().request_repaint();
+                                    }
+                                }
+
+                                if response.has_focus() {
+                                    let focus_stroke = metrics.focus_outline_stroke;
+                                    self.painter.focus_outline(
+                                        ui.painter(),
+                                        style,
+                                        response.rect.shrink(focus_stroke.width),
+                                        style.tab_rounding,
+                                        focus_stroke,
+                                    );
+
+                                    let close_requested =
+                                        input::any_pressed(&self.shortcuts.close_tab, &ui.input());
+                                    if close_requested && closeable {
+                                        if tab_viewer.on_close(tab) {
+                                            to_remove.push((node_index, tab_index));
+                                        } else {
+                                            *active = tab_index;
+                                            *new_focused = Some(node_index);
+                                        }
+                                    }
+
+                                    // Stacked tabs are stepped through with the up and down keys instead.
+                                    let (next, prev, dir) = if vertical {
+                                        (Key::ArrowDown, Key::ArrowUp, 1)
                                     } else {
-                                        *active = tab_index;
-                                        new_focused = Some(node_index);
+                                        (Key::ArrowRight, Key::ArrowLeft, scroll_dir as isize)
+                                    };
+                                    let step = {
+                                        let input = ui.input();
+                                        if !input.modifiers.is_none() {
+                                            0
+                                        } else if input.key_pressed(next) {
+                                            1
+                                        } else if input.key_pressed(prev) {
+                                            -1
+                                        } else {
+                                            0
+                                        }
+                                    };
+                                    if step != 0 {
+                                        focus_step = Some((tab_index, step * dir));
                                     }
                                 }
-                                let response = ui.interact(response.0.rect, id, sense);
-                                if response.drag_started() {
-                                    state.drag_start = response.hover_pos();
-                                }
 
-                                response
+                                response.context_menu(|ui| {
+                                    let top = ui.cursor().min.y;
+                                    tab_viewer.context_menu(ui, tab);
+                                    if can_collapse && !collapsed {
+                                        if ui.cursor().min.y > top {
+                                            ui.separator();
+                                        }
+                                        if ui.button("Collapse").clicked() {
+                                            menu_collapse = true;
+                                            ui.close_menu();
+                                        }
+                                    }
+                                    if style.pinnable_tabs && !self.read_only {
+                                        if ui.cursor().min.y > top {
+                                            ui.separator();
+                                        }
+                                        let text = if pinned { "Unpin" } else { "Pin" };
+                                        if ui.button(text).clicked() {
+                                            tab_viewer.set_pinned(tab, !pinned);
+                                            ui.close_menu();
+                                        }
+                                    }
+                                    if !style.shows_close_buttons() {
+                                        return;
+                                    }
+                                    if ui.cursor().min.y > top {
+                                        ui.separator();
+                                    }
+                                    let entries = [
+                                        ("Close", CloseTabs::One, closeable),
+                                        ("Close Others", CloseTabs::Others, tabs_len > 1),
+                                        (
+                                            if style.rtl {
+                                                "Close to the Left"
+                                            } else {
+                                                "Close to the Right"
+                                            },
+                                            CloseTabs::ToTheRight,
+                                            tab_index.0 + 1 < tabs_len,
+                                        ),
+                                    ];
+                                    for (text, close, enabled) in entries {
+                                        if ui.add_enabled(enabled, Button::new(text)).clicked() {
+                                            menu_close = Some((tab_index, close));
+                                            ui.close_menu();
+                                        }
+                                    }
+                                })
                             };
-                            if state.drag_start.is_some() {
+                            state.tab_widths.insert(id, response.rect.width());
+                            title_rects.push((node_index, tab_index, response.rect));
+                            if let Some(color) = group_color.filter(|_| !is_being_dragged) {
+                                self.painter.tab_group_stripe(
+                                    ui.painter(),
+                                    style,
+                                    response.rect,
+                                    color,
+                                );
+                            }
+
+                            if is_dragging && state.drag_start.is_some() {
                                 if let Some(pos) = ui.input().pointer.hover_pos() {
                                     if response.rect.contains(pos) {
                                         tab_hover_rect = Some((response.rect, tab_index));
@@ -419,58 +2776,432 @@ impl<'tree, Tab> DockArea<'tree, Tab> {
                                 }
                             }
                         }
+
+                        if style.show_add_buttons {
+                            let size = Vec2::splat(metrics.tab_bar_height);
+                            let (rect, response) = ui.allocate_exact_size(size, Sense::click());
+                            response
+                                .widget_info(|| WidgetInfo::labeled(WidgetType::Button, "Add tab"));
+                            self.painter.add_button(
+                                ui.painter(),
+                                style,
+                                rect,
+                                response.hovered() || response.has_focus(),
+                            );
+                            let popup_id = leaf_id.with("add_popup");
+                            if response.clicked() {
+                                if tab_viewer.has_add_popup() {
+                                    ui.memory().toggle_popup(popup_id);
+                                } else {
+                                    tab_viewer.on_add(node_index);
+                                }
+                            }
+                            let tab = popup::popup_below_widget(ui, popup_id, &response, |ui| {
+                                tab_viewer.add_popup(ui, node_index)
+                            });
+                            added.extend(tab.flatten().map(|tab| (node_index, tab)));
+                        }
+                        tabs_height = ui.min_rect().height();
                     });
                 });
 
-                // tab body
-                if let Some(tab) = tabs.get_mut(active.0) {
-                    let top_y = rect.min.y + height_topbar;
-                    let rect = rect.intersect(Rect::everything_below(top_y));
-                    let rect = expand_to_pixel(rect, pixels_per_point);
+                // Wrapping tab bars are as tall as their rows of tabs as of the last frame.
+                if wrap {
+                    let new_rows = ((tabs_height / metrics.tab_bar_height).round() as usize).max(1);
+                    state.tab_bar_rows.insert(leaf_id, new_rows);
+                    if new_rows != rows {
+                        ui.ctx().request_repaint();
+                    }
+                }
 
-                    *viewport = rect;
+                // Collapsed leaves expand back when their tab bar is clicked.
+                if menu_collapse {
+                    collapse.push((node_index, true));
+                } else if collapsed {
+                    let clicked = {
+                        let pointer = &ui.input().pointer;
+                        pointer.any_click()
+                            && pointer
+                                .hover_pos()
+                                .map_or(false, |pos| tabbar.contains(pos))
+                    };
+                    if clicked {
+                        collapse.push((node_index, false));
+                    }
+                }
 
-                    if ui.input().pointer.any_click() {
-                        if let Some(pos) = ui.input().pointer.hover_pos() {
-                            if rect.contains(pos) {
-                                new_focused = Some(node_index);
-                            }
+                match menu_close {
+                    Some((tab_index, CloseTabs::One)) => {
+                        let tab = &mut tabs[tab_index.0];
+                        if tab_viewer.closeable(tab) && tab_viewer.on_close(tab) {
+                            to_remove.push((node_index, tab_index));
                         }
                     }
+                    Some((tab_index, close)) => *close_tabs = Some((node_index, tab_index, close)),
+                    None => {}
+                }
 
-                    if tab_viewer.clear_background(tab) {
-                        ui.painter()
-                            .rect_filled(rect, 0.0, style.tab_background_color);
+                // Arrow keys move the focus along the tab bar, activating the tab it lands on.
+                if let Some((tab_index, step)) = focus_step {
+                    let index = (tab_index.0 as isize + step).clamp(0, tabs_len as isize - 1);
+                    let index = index as usize;
+                    if index != tab_index.0 {
+                        let id = self.id.with(tab_viewer.id(&mut tabs[index]));
+                        ui.memory().request_focus(id);
+                        if self.announce_changes {
+                            announcements.push(format!(
+                                "{} tab activated",
+                                tab_viewer.title(&mut tabs[index]).text()
+                            ));
+                        }
+                        *active = TabIndex(index);
+                        *new_focused = Some(node_index);
                     }
+                }
 
-                    let mut ui = ui.child_ui(rect, Default::default());
-                    ui.push_id(node_index, |ui| {
-                        ScrollArea::both()
-                            .id_source(Id::new((tab_viewer.title(tab).text(), "egui_dock::Tab")))
-                            .show(ui, |ui| {
-                                Frame::none().inner_margin(tab_viewer.inner_margin()).show(
-                                    ui,
-                                    |ui| {
-                                        let available_rect = ui.available_rect_before_wrap();
-                                        ui.expand_to_include_rect(available_rect);
-                                        tab_viewer.ui(ui, tab);
-                                    },
-                                );
-                            });
+                // Interacting after the tabs leaves dragging the tabs themselves to them.
+                if max_scroll > 0.0 {
+                    let response = ui.interact(tabs_rect, scroll_id, Sense::drag());
+                    scroll -= if vertical {
+                        response.drag_delta().y
+                    } else {
+                        scroll_dir * response.drag_delta().x
+                    };
+                }
+
+                if let Some(tab_list_rect) = tab_list_rect {
+                    let response =
+                        ui.interact(tab_list_rect, leaf_id.with("tab_list"), Sense::click());
+                    self.painter.tab_list_button(
+                        ui.painter(),
+                        style,
+                        tab_list_rect,
+                        response.hovered(),
+                    );
+                    if state.tab_list.toggle(&response) {
+                        let entries = tabs
+                            .iter_mut()
+                            .enumerate()
+                            .map(|(tab_index, tab)| Entry {
+                                node: node_index,
+                                tab: TabIndex(tab_index),
+                                title: tab_viewer.title(tab).text().to_owned(),
+                                rank: usize::MAX,
+                            })
+                            .collect();
+                        if let Some(entry) =
+                            state
+                                .tab_list
+                                .show(ui, &response, metrics.animation_time, entries)
+                        {
+                            if self.announce_changes && *active != entry.tab {
+                                announcements.push(format!("{} tab activated", entry.title));
+                            }
+                            *active = entry.tab;
+                            *new_focused = Some(node_index);
+                        }
+                    }
+                }
+
+                if self.read_only {
+                    let size = Vec2::splat(metrics.tab_bar_height);
+                    let x = if style.rtl {
+                        tabs_rect.min.x
+                    } else {
+                        tabs_rect.max.x - size.x
+                    };
+                    // Vertical tab bars show it at the end of their tabs, in the bottom corner.
+                    let y = if vertical {
+                        tabs_rect.max.y - size.y
+                    } else {
+                        tabbar.min.y
+                    };
+                    let lock = Rect::from_min_size(pos2(x, y), size);
+                    self.painter.locked_indicator(ui.painter(), style, lock);
+                }
+
+                if *active != prev_active {
+                    events.push(DockEvent::TabActivated {
+                        surface: *surface,
+                        node: node_index,
+                        tab: *active,
                     });
+
+                    // Scroll the newly active tab into view.
+                    let mut widths = tabs.iter_mut().map(|tab| {
+                        let id = self.id.with(tab_viewer.id(tab));
+                        extent(state.tab_widths.get(&id).copied().unwrap_or(0.0))
+                    });
+                    let start: f32 = widths.by_ref().take(active.0).sum();
+                    let width = widths.next().unwrap_or(0.0);
+                    let length = if vertical {
+                        tabs_rect.height()
+                    } else {
+                        tabs_rect.width()
+                    };
+                    scroll = scroll.max(start + width - length).min(start);
                 }
+                state
+                    .tab_bar_scroll
+                    .insert(scroll_id, scroll.clamp(0.0, max_scroll));
 
-                let is_being_dragged = ui.memory().is_anything_being_dragged();
-                if is_being_dragged && full_response.hovered() {
-                    hover_data = ui.input().pointer.hover_pos().map(|pointer| HoverData {
+                if is_dragging && full_response.hovered() {
+                    *hover_data = ui.input().pointer.hover_pos().map(|pointer| HoverData {
                         rect,
                         dst: node_index,
                         tabs: tabs_response.hovered().then_some(tabs_response.rect),
                         tab: tab_hover_rect,
                         pointer,
+                        vertical,
                     });
                 }
+            }
+        }
+
+        ui.set_clip_rect(clip_rect);
+        for &(node_index, tab_index, rect) in &title_rects {
+            self.tree.set_tab_title_rect(node_index, tab_index, rect);
+        }
+        for (node_index, rect) in tab_bar_rects {
+            self.tree.set_tab_bar_rect(node_index, rect);
+        }
+        for (node_index, collapsed) in collapse {
+            self.tree.record_history();
+            self.tree.set_collapsed(node_index, collapsed);
+            *changed = true;
+        }
+        for (node_index, tab) in added {
+            self.tree.record_history();
+            let (node, tab) = self.tree.push_to(TabDestination::Node(node_index), tab);
+            events.push(DockEvent::TabAdded {
+                surface: *surface,
+                node,
+                tab,
+            });
+        }
+        state.title_rects = title_rects;
+    }
+
+    /// Shows the body of the active tab of every leaf, and of the inactive tabs which asked for it.
+    fn show_contents(
+        &mut self,
+        ui: &mut Ui,
+        frame: &mut FrameState,
+        tab_viewer: &mut impl TabViewer<Tab = Tab>,
+    ) {
+        profile_function!();
+
+        let focused = self.tree.focused_leaf();
+        let is_dragging = frame.is_dragging;
+        let FrameState {
+            surface,
+            state,
+            style,
+            metrics,
+            to_remove,
+            new_focused,
+            events,
+            ..
+        } = frame;
+        let pixels_per_point = metrics.pixels_per_point;
+        let clip_rect = ui.clip_rect();
+        let mut title_rects = std::mem::take(&mut state.title_rects);
+        title_rects.clear();
+        state.mdi.forget_hidden();
+        state
+            .active_tabs
+            .retain(|&leaf_id, _| self.tree.node_index(leaf_id).is_some());
+
+        for node_index in self.tree.node_indices() {
+            let is_mdi = self.tree.is_mdi(node_index);
+            let leaf_id = self.tree.node_id(node_index);
+            let single = self.tree[node_index].is_leaf()
+                && hides_single_tab_bar(self.tree, style, node_index);
+            let node_style = self.tree.shared_node_style(node_index).cloned();
+            let style = node_style.as_deref().unwrap_or(&*style);
+            let position = self.tree.shown_tab_bar_position(node_index, style);
+            // Collapsed leaves are only their tab bar, and leaves out of sight, like scrolled away, show nothing.
+            let out_of_sight = self.tree[node_index].is_leaf()
+                && self.tree[node_index]
+                    .rect()
+                    .map_or(false, |rect| !rect.intersects(clip_rect));
+            if self.tree.is_collapsed(node_index) || out_of_sight {
+                if let Node::Leaf { tabs, .. } = &mut self.tree[node_index] {
+                    tabs.iter_mut()
+                        .for_each(|tab| tab_viewer.on_background(tab));
+                }
+                continue;
+            }
+            if let Node::Leaf {
+                rect,
+                tabs,
+                active,
+                viewport,
+            } = &mut self.tree[node_index]
+            {
+                let rect = *rect;
+                ui.set_clip_rect(rect.intersect(clip_rect));
+
+                if let (Some(leaf_id), Some(tab)) = (leaf_id, tabs.get_mut(active.0)) {
+                    let tab_id = self.id.with(tab_viewer.id(tab));
+                    let shown = state.active_tabs.insert(leaf_id, tab_id);
+                    if shown.map_or(false, |shown| shown != tab_id) && tab_viewer.on_activate(tab) {
+                        ui.memory().surrender_focus(tab_id);
+                    }
+                }
+
+                if is_mdi {
+                    *viewport = rect;
+                    let prev_active = *active;
+                    show_mdi_children(
+                        ui,
+                        self.id,
+                        self.painter,
+                        style,
+                        metrics,
+                        &mut state.mdi,
+                        node_index,
+                        Some(node_index) == focused,
+                        rect,
+                        tabs,
+                        active,
+                        to_remove,
+                        &mut title_rects,
+                        tab_viewer,
+                    );
+                    if ui.input().pointer.any_click() {
+                        if let Some(pos) = ui.input().pointer.hover_pos() {
+                            if rect.contains(pos) {
+                                *new_focused = Some(node_index);
+                            }
+                        }
+                    }
+                    if *active != prev_active {
+                        *new_focused = Some(node_index);
+                        events.push(DockEvent::TabActivated {
+                            surface: *surface,
+                            node: node_index,
+                            tab: *active,
+                        });
+                    }
+                    continue;
+                }
+
+                let reveal = tab_bar_reveal(
+                    ui,
+                    metrics,
+                    self.id.with((leaf_id, "leaf")),
+                    rect,
+                    is_dragging,
+                    style.auto_hide_tab_bars || single,
+                    position,
+                );
+                let rows = tab_bar_rows(state, style, self.id.with((leaf_id, "leaf")));
+                let mut height_topbar = if position.is_vertical() {
+                    style.vertical_tab_bar_width * reveal
+                } else {
+                    metrics.tab_bar_height * rows as f32 * reveal
+                };
+                if single {
+                    height_topbar = height_topbar.max(TAB_BAR_HANDLE_HEIGHT);
+                }
+                let toolbar_height = match tabs.get(active.0) {
+                    Some(tab) => tab_viewer.toolbar_height(tab).max(0.0),
+                    None => 0.0,
+                };
+                // Toolbars are shown above the body, wherever the tab bar is.
+                let (_, rest) = position.split(rect, height_topbar);
+                let (toolbar_rect, body_rect) = TabBarPosition::Top.split(rest, toolbar_height);
+                let body_rect = expand_to_pixel(body_rect, pixels_per_point);
+
+                if let Some(tab) = tabs.get_mut(active.0) {
+                    if toolbar_height > 0.0 {
+                        self.painter
+                            .tab_bar(ui.painter(), style, toolbar_rect, metrics.px);
+
+                        let tab_id = self.id.with(tab_viewer.id(tab));
+                        let mut ui =
+                            ui.child_ui(toolbar_rect, Layout::left_to_right(Align::Center));
+                        ui.push_id(tab_id.with("toolbar"), |ui| tab_viewer.toolbar(ui, tab));
+                    }
+
+                    let rect = body_rect;
+
+                    *viewport = rect;
+
+                    if ui.input().pointer.any_click() {
+                        if let Some(pos) = ui.input().pointer.hover_pos() {
+                            if rect.contains(pos) {
+                                *new_focused = Some(node_index);
+                            }
+                        }
+                    }
+
+                    clear_tab_body(ui, self.painter, style, rect, tab_viewer, tab);
+
+                    let mut ui = ui.child_ui(rect, Default::default());
+                    let tab_id = self.id.with(tab_viewer.id(tab));
+                    show_tab_body(&mut ui, tab_id, tab_viewer, tab);
+
+                    if style.capture_tab_previews
+                        && state.previews.needs_capture(tab_id, ui.input().time)
+                    {
+                        let fill = tab_viewer
+                            .inner_background(tab)
+                            .unwrap_or(style.tab_background_color);
+                        state
+                            .previews
+                            .capture(ui.ctx(), tab_id, rect.size(), fill, |ui| {
+                                show_tab_body(ui, tab_id, tab_viewer, tab);
+                            });
+                    }
+                }
+
+                if tabs.is_empty() {
+                    if let Some(add_contents) = &mut self.empty_area {
+                        let mut ui = ui.child_ui(body_rect, Default::default());
+                        ui.push_id(self.id.with((leaf_id, "empty_area")), add_contents);
+                    }
+                }
+
+                // Inactive tabs that asked for it keep running, but nothing they paint is shown.
+                for (tab_index, tab) in tabs.iter_mut().enumerate() {
+                    if tab_index == active.0 {
+                        continue;
+                    }
+                    if tab_viewer.render_when_inactive(tab) {
+                        let mut ui = ui.child_ui(body_rect, Default::default());
+                        ui.set_visible(false);
+                        let tab_id = self.id.with(tab_viewer.id(tab));
+                        show_tab_body(&mut ui, tab_id, tab_viewer, tab);
+                    } else {
+                        tab_viewer.on_background(tab);
+                    }
+                }
+
+                if (style.show_focus_outline || style.accessibility_mode || state.resize_mode)
+                    && Some(node_index) == focused
+                {
+                    let stroke = metrics.focus_outline_stroke;
+                    self.painter.focus_outline(
+                        ui.painter(),
+                        style,
+                        rect.shrink(stroke.width * 0.5),
+                        Rounding::none(),
+                        stroke,
+                    );
+                }
+            }
+        }
+
+        ui.set_clip_rect(clip_rect);
+        for &(node_index, tab_index, rect) in &title_rects {
+            self.tree.set_tab_title_rect(node_index, tab_index, rect);
+        }
+        state.title_rects = title_rects;
 
+        for node_index in self.tree.node_indices() {
+            if let Node::Leaf { tabs, .. } = &mut self.tree[node_index] {
                 for (tab_index, tab) in tabs.iter_mut().enumerate() {
                     if tab_viewer.force_close(tab) {
                         to_remove.push((node_index, TabIndex(tab_index)));
@@ -478,17 +3209,61 @@ impl<'tree, Tab> DockArea<'tree, Tab> {
                 }
             }
         }
+    }
+
+    /// Removes the tabs closed during the frame and focuses the leaf which was clicked, if any.
+    fn remove_closed_tabs(
+        &mut self,
+        frame: &mut FrameState,
+        tab_viewer: &mut impl TabViewer<Tab = Tab>,
+    ) {
+        let FrameState {
+            surface,
+            state,
+            to_remove,
+            close_tabs,
+            new_focused,
+            announcements,
+            events,
+            changed,
+            ..
+        } = frame;
+
+        if !to_remove.is_empty() || close_tabs.is_some() {
+            *changed = true;
+            self.tree.record_history();
+        }
+
+        // Tabs can be closed by more than one pass, they're removed back to front.
+        to_remove.sort_by_key(|(node, tab)| (node.0, tab.0));
+        to_remove.dedup();
 
-        let mut emptied = 0;
+        let mut emptied = Vec::new();
+        let mut closed_ids = std::mem::take(&mut state.closed_ids);
+        let mut closed_active = std::mem::take(&mut state.closed_active);
+        closed_ids.clear();
+        closed_active.clear();
         let mut last = (NodeIndex(usize::MAX), TabIndex(usize::MAX));
         for remove in to_remove.iter().rev() {
             if let Node::Leaf { tabs, active, .. } = &mut self.tree[remove.0] {
-                tabs.remove(remove.1 .0);
+                let mut tab = tabs.remove(remove.1 .0);
+                closed_ids.push(tab_viewer.id(&mut tab));
+                if remove.1 == *active {
+                    closed_active.push(remove.0);
+                }
+                if self.announce_changes {
+                    announcements.push(format!("{} tab closed", tab_viewer.title(&mut tab).text()));
+                }
+                events.push(DockEvent::TabClosed {
+                    surface: *surface,
+                    node: remove.0,
+                    tab: remove.1,
+                });
                 if remove.1 <= *active {
                     active.0 = active.0.saturating_sub(1);
                 }
                 if tabs.is_empty() {
-                    emptied += 1;
+                    emptied.push(remove.0);
                 }
                 if last.0 == remove.0 {
                     assert!(last.1 > remove.1)
@@ -498,60 +3273,599 @@ impl<'tree, Tab> DockArea<'tree, Tab> {
                 panic!();
             }
         }
-        for _ in 0..emptied {
-            self.tree.remove_empty_leaf()
+
+        // The tab the menu was opened for has moved if tabs of its leaf were just closed.
+        if let Some((node, tab_index, close)) = close_tabs.take() {
+            if !to_remove.iter().any(|&(closed, _)| closed == node) {
+                let active = match &self.tree[node] {
+                    Node::Leaf { active, .. } => Some(*active),
+                    _ => None,
+                };
+                let closed = match close {
+                    CloseTabs::Others => self.tree.close_other_tabs(node, tab_index, tab_viewer),
+                    _ => self.tree.close_tabs_to_right(node, tab_index, tab_viewer),
+                };
+                for (node, tab_index, mut tab) in closed {
+                    closed_ids.push(tab_viewer.id(&mut tab));
+                    if Some(tab_index) == active {
+                        closed_active.push(node);
+                    }
+                    if self.announce_changes {
+                        announcements
+                            .push(format!("{} tab closed", tab_viewer.title(&mut tab).text()));
+                    }
+                    events.push(DockEvent::TabClosed {
+                        surface: *surface,
+                        node,
+                        tab: tab_index,
+                    });
+                }
+            }
+        }
+
+        // Leaves whose active tab was closed go back to the tab used before it, rather than its neighbour.
+        closed_active.dedup();
+        for &node in &closed_active {
+            self.tree.activate_recent(node, tab_viewer);
+        }
+        for &id in &closed_ids {
+            self.tree.forget_recent(id);
+        }
+        state.closed_ids = closed_ids;
+        state.closed_active = closed_active;
+
+        for node in emptied {
+            if self.tree.remove_leaf_if_empty(node) {
+                events.push(DockEvent::NodeRemoved {
+                    surface: *surface,
+                    node,
+                });
+            }
         }
 
-        if let Some(focused) = new_focused {
+        if let Some(focused) = *new_focused {
+            *changed |= self.tree.focused_leaf() != Some(focused);
             self.tree.set_focused_node(focused);
         }
+    }
 
-        if let (Some((src, tab_index)), Some(hover)) = (drag_data, hover_data) {
+    /// Previews where the dragged tab would be dropped, and moves it there once it's released.
+    fn show_drag_overlay(
+        &mut self,
+        ui: &Ui,
+        frame: &mut FrameState,
+        tab_viewer: &mut impl TabViewer<Tab = Tab>,
+    ) {
+        profile_function!();
+
+        if let (Some((src, tab_index)), Some(hover)) = (frame.drag_data, frame.hover_data.take()) {
+            frame.drag_data = None;
             let dst = hover.dst;
 
             if self.tree[src].is_leaf() && self.tree[dst].is_leaf() {
-                let (target, helper, tap_pos) = hover.resolve();
+                let (drop_target, helper) = drop_target(self.tree, &frame.style, &hover);
 
-                let id = Id::new("helper");
-                let layer_id = LayerId::new(Order::Foreground, id);
+                let layer_id = LayerId::new(Order::Foreground, self.id.with("drop_preview"));
                 let painter = ui.ctx().layer_painter(layer_id);
 
-                if src != dst || self.tree[dst].tabs_count() > 1 {
-                    painter.rect_filled(helper, 0.0, style.selection_color);
+                let cloning = self
+                    .shortcuts
+                    .clone_on_drop
+                    .map_or(false, |modifiers| input::held(modifiers, &ui.input()));
+
+                if matches!(drop_target, DropTarget::Edge(_)) {
+                    self.painter.drop_preview(&painter, &frame.style, helper);
+                } else if cloning || src != dst || self.tree[dst].tabs_count() > 1 {
+                    self.painter.drop_preview(&painter, &frame.style, helper);
+                    hover.paint_drop_targets(&painter, &frame.style, self.painter);
                 }
 
                 if ui.input().pointer.any_released() {
-                    if let Node::Leaf { active, .. } = &mut self.tree[src] {
-                        if *active >= tab_index {
-                            active.0 = active.0.saturating_sub(1);
-                        }
+                    frame.changed = true;
+
+                    let duplicate = match &self.tree[src] {
+                        Node::Leaf { tabs, .. } if cloning => tabs
+                            .get(tab_index.0)
+                            .and_then(|tab| tab_viewer.duplicate_tab(tab)),
+                        _ => None,
+                    };
+                    let surface = frame.surface;
+                    let announcement = self.drop_dragged_tab(
+                        (surface, src, tab_index),
+                        (surface, dst),
+                        drop_target,
+                        duplicate,
+                        &mut frame.events,
+                        tab_viewer,
+                    );
+                    if let Some(announcement) = announcement.filter(|_| self.announce_changes) {
+                        frame.announcements.push(announcement);
                     }
+                }
+            }
+        }
+    }
 
-                    let tab = self.tree[src].remove_tab(tab_index).unwrap();
+    /// Drops the tab at `src` onto `target` of the leaf at `dst`, or a `duplicate` of it, whether both are on the
+    /// same surface or not, and pushes what changed to `events`.
+    ///
+    /// Drops within a surface are made in the tree of the dock area itself, which is the tree of the window for the
+    /// dock areas showing floating windows.
+    ///
+    /// Returns what to announce to screen readers, or `None` if nothing was dropped.
+    #[allow(clippy::too_many_arguments)]
+    fn drop_dragged_tab(
+        &mut self,
+        (src_surface, src, tab_index): (SurfaceIndex, NodeIndex, TabIndex),
+        (dst_surface, dst): (SurfaceIndex, NodeIndex),
+        target: DropTarget,
+        duplicate: Option<Tab>,
+        events: &mut Vec<DockEvent>,
+        tab_viewer: &mut impl TabViewer<Tab = Tab>,
+    ) -> Option<String> {
+        let within = src_surface == dst_surface;
+        let title = match &mut self.drop_tree(src_surface, within)?[src] {
+            Node::Leaf { tabs, .. } => tab_viewer
+                .title(tabs.get_mut(tab_index.0)?)
+                .text()
+                .to_owned(),
+            _ => return None,
+        };
+        let announcement = match target {
+            DropTarget::Edge(_) | DropTarget::Split(_) => format!("{} split created", title),
+            DropTarget::Tabs(_) if duplicate.is_some() => format!("{} tab duplicated", title),
+            DropTarget::Tabs(_) => format!("{} tab moved", title),
+        };
 
-                    if let Some(target) = target {
-                        self.tree.split(dst, target, 0.5, Node::leaf(tab));
-                    } else {
-                        if let Some(index) = tap_pos {
-                            self.tree[dst].insert_tab(index, tab);
-                        } else {
-                            self.tree[dst].append_tab(tab);
-                        }
-                        self.tree.set_focused_node(dst);
-                    }
+        let dst_tree = self.drop_tree(dst_surface, within)?;
+        if !dst_tree[dst].is_leaf() {
+            return None;
+        }
+        let split = match target {
+            DropTarget::Edge(split) => dst_tree.root().map(|root| (root, split)),
+            DropTarget::Split(split) => Some((dst, split)),
+            DropTarget::Tabs(_) => None,
+        };
+        let split_event = split.map(|(node, split)| DockEvent::NodeSplit {
+            surface: dst_surface,
+            node,
+            split,
+        });
+
+        if let Some(tab) = duplicate {
+            dst_tree.record_history();
+            let (node, tab) = dst_tree.drop_new_tab(tab, dst, target)?;
+            events.extend(split_event);
+            events.push(DockEvent::TabAdded {
+                surface: dst_surface,
+                node,
+                tab,
+            });
+            return Some(announcement);
+        }
 
-                    self.tree.remove_empty_leaf();
-                    for node in self.tree.iter_mut() {
-                        if let Node::Leaf { tabs, active, .. } = node {
-                            if active.0 >= tabs.len() {
-                                active.0 = 0;
-                            }
-                        }
+        let (node, removed) = if within {
+            dst_tree.record_history();
+            dst_tree.drop_tab((src, tab_index), dst, target)?
+        } else {
+            let src_tree = self.surface_tree(src_surface)?;
+            src_tree.record_history();
+            let tab = src_tree.remove_tab(src, tab_index)?;
+            let removed = src_tree.node_id(src).is_none().then_some(src);
+            let dst_tree = self.surface_tree(dst_surface)?;
+            dst_tree.record_history();
+            let (node, _) = dst_tree.drop_new_tab(tab, dst, target)?;
+            (node, removed)
+        };
+        events.extend(split_event);
+        if let Some(node) = removed {
+            events.push(DockEvent::NodeRemoved {
+                surface: src_surface,
+                node,
+            });
+        }
+        events.push(DockEvent::TabMoved {
+            src: (src_surface, src, tab_index),
+            dst: (dst_surface, node),
+        });
+        Some(announcement)
+    }
+
+    /// Returns the tree a tab is dropped in by [`DockArea::drop_dragged_tab`].
+    fn drop_tree(&mut self, surface: SurfaceIndex, within: bool) -> Option<&mut Tree<Tab>> {
+        if within {
+            Some(&mut *self.tree)
+        } else {
+            self.surface_tree(surface)
+        }
+    }
+
+    /// Shows the quick-open palette if it's open.
+    ///
+    /// Returns the tab picked from it, if any.
+    fn show_quick_open(
+        &mut self,
+        ui: &Ui,
+        metrics: &Metrics,
+        rect: Rect,
+        quick_open: &mut QuickOpen,
+        tab_viewer: &mut impl TabViewer<Tab = Tab>,
+    ) -> Option<(NodeIndex, TabIndex)> {
+        profile_function!();
+
+        if !quick_open.is_open() && input::any_pressed(&self.shortcuts.quick_open, &ui.input()) {
+            quick_open.open();
+        }
+        if !quick_open.is_open() {
+            return None;
+        }
+
+        // Tabs are listed in the order they were last activated in, as far as the tree remembers.
+        let recent = self.tree.recent_tabs(tab_viewer);
+        let entries = self
+            .tree
+            .tabs_mut()
+            .map(|(node, tab, title)| Entry {
+                node,
+                tab,
+                title: tab_viewer.title(title).text().to_owned(),
+                rank: recent
+                    .iter()
+                    .position(|&recent| recent == (node, tab))
+                    .unwrap_or(usize::MAX),
+            })
+            .collect();
+        let id = self.id.with("quick_open");
+        let entry = quick_open.show(ui, rect, id, metrics.animation_time, entries)?;
+        self.tree.set_active_tab(entry.node, entry.tab);
+        self.tree.set_focused_node(entry.node);
+        Some((entry.node, entry.tab))
+    }
+
+    fn handle_shortcuts(
+        &mut self,
+        ui: &Ui,
+        frame: &mut FrameState,
+        tab_viewer: &mut impl TabViewer<Tab = Tab>,
+    ) {
+        let FrameState {
+            surface,
+            metrics,
+            state,
+            to_remove,
+            events,
+            changed,
+            ..
+        } = frame;
+        let surface = *surface;
+
+        let (undo, redo) = {
+            let input = ui.input();
+            (
+                input::any_pressed(&self.shortcuts.undo, &input),
+                input::any_pressed(&self.shortcuts.redo, &input),
+            )
+        };
+        if (undo && self.tree.undo()) || (redo && self.tree.redo()) {
+            *changed = true;
+            return;
+        }
+
+        let focused = match self.tree.focused_leaf() {
+            Some(focused) => focused,
+            None => {
+                state.resize_mode = false;
+                return;
+            }
+        };
+
+        if state.resize_mode {
+            let (exit, delta) = {
+                let input = ui.input();
+                let exit = input::any_pressed(&self.shortcuts.exit_resize_mode, &input);
+                let step = if input.modifiers.shift { 0.005 } else { 0.02 };
+                let delta = if input.key_pressed(Key::ArrowRight) {
+                    Some((true, step))
+                } else if input.key_pressed(Key::ArrowLeft) {
+                    Some((true, -step))
+                } else if input.key_pressed(Key::ArrowDown) {
+                    Some((false, step))
+                } else if input.key_pressed(Key::ArrowUp) {
+                    Some((false, -step))
+                } else {
+                    None
+                };
+                (exit, delta)
+            };
+            if exit {
+                state.resize_mode = false;
+            } else if let Some((horizontal, delta)) = delta {
+                let separator_width = metrics.separator_width;
+                if let Some((node, fraction)) =
+                    self.resize_leaf(focused, horizontal, delta, separator_width)
+                {
+                    events.push(DockEvent::SplitResized {
+                        surface,
+                        node,
+                        fraction,
+                    });
+                }
+            }
+            return;
+        }
+
+        if input::any_pressed(&self.shortcuts.resize_mode, &ui.input()) {
+            state.resize_mode = true;
+            return;
+        }
+
+        let (split, move_to, activate, cycle, close) = {
+            let input = ui.input();
+            let shortcuts = &self.shortcuts;
+            let cycle = if input::any_pressed(&shortcuts.next_tab, &input) {
+                Some(1)
+            } else if input::any_pressed(&shortcuts.previous_tab, &input) {
+                Some(-1)
+            } else {
+                None
+            };
+            let close = input::any_pressed(&shortcuts.close_active_tab, &input);
+            let split = if input::any_pressed(&shortcuts.split_horizontal, &input) {
+                Some(Split::Right)
+            } else if input::any_pressed(&shortcuts.split_vertical, &input) {
+                Some(Split::Below)
+            } else {
+                None
+            };
+            let move_to = [
+                (&shortcuts.move_tab_left, Split::Left),
+                (&shortcuts.move_tab_right, Split::Right),
+                (&shortcuts.move_tab_up, Split::Above),
+                (&shortcuts.move_tab_down, Split::Below),
+            ]
+            .into_iter()
+            .find_map(|(bindings, direction)| {
+                input::any_pressed(bindings, &input).then_some(direction)
+            });
+            let activate = shortcuts
+                .activate_tab
+                .iter()
+                .position(|bindings| input::any_pressed(bindings, &input));
+            (split, move_to, activate, cycle, close)
+        };
+
+        if let Some(step) = cycle {
+            if let Node::Leaf { tabs, active, .. } = &mut self.tree[focused] {
+                let prev_active = *active;
+                if !tabs.is_empty() {
+                    let len = tabs.len() as isize;
+                    active.0 = (active.0 as isize + step).rem_euclid(len) as usize;
+                }
+                if *active != prev_active {
+                    events.push(DockEvent::TabActivated {
+                        surface,
+                        node: focused,
+                        tab: *active,
+                    });
+                }
+            }
+        }
+
+        if close {
+            if let Node::Leaf { tabs, active, .. } = &mut self.tree[focused] {
+                if let Some(tab) = tabs.get_mut(active.0) {
+                    if !tab_viewer.is_pinned(tab)
+                        && tab_viewer.closeable(tab)
+                        && tab_viewer.on_close(tab)
+                    {
+                        // The tab is only removed at the end of the frame, the tree mustn't change under it.
+                        to_remove.push((focused, *active));
+                        return;
                     }
                 }
             }
         }
 
-        state.store(ui.ctx(), self.id);
+        if let Some(position) = activate {
+            if let Node::Leaf { tabs, active, .. } = &mut self.tree[focused] {
+                let prev_active = *active;
+                if position == self.shortcuts.activate_tab.len() - 1 {
+                    active.0 = tabs.len().saturating_sub(1);
+                } else if position < tabs.len() {
+                    active.0 = position;
+                }
+                if *active != prev_active {
+                    events.push(DockEvent::TabActivated {
+                        surface,
+                        node: focused,
+                        tab: *active,
+                    });
+                }
+            }
+        }
+
+        if let Some(split) = split {
+            self.split_focused_leaf(surface, focused, split, events, tab_viewer);
+        } else if let Some(direction) = move_to {
+            self.move_active_tab(surface, focused, direction, events);
+        }
+    }
+
+    /// Grows (or shrinks, if `delta` is negative) the leaf by moving the separator of its closest horizontal
+    /// (or vertical) ancestor by `delta` of that ancestor's size.
+    ///
+    /// The new fraction is clamped to the minimum sizes the layout keeps the children at, like dragging the separator.
+    ///
+    /// Returns the ancestor and its new fraction, or `None` if there's no such ancestor or the separator can't move
+    /// any further.
+    fn resize_leaf(
+        &mut self,
+        leaf: NodeIndex,
+        horizontal: bool,
+        delta: f32,
+        separator_width: f32,
+    ) -> Option<(NodeIndex, f32)> {
+        let mut child = leaf;
+        while let Some(parent) = self.tree.parent(child) {
+            let [left, right] = self.tree.children(parent)?;
+            let (fraction_side, fraction) = match &mut self.tree[parent] {
+                // `fraction` is the share of the left-most (or top-most) child, which is stored on the right
+                // of horizontal nodes and on the left of vertical ones.
+                Node::Horizontal { fraction, .. } if horizontal => (right, fraction),
+                Node::Vertical { fraction, .. } if !horizontal => (left, fraction),
+                _ => {
+                    child = parent;
+                    continue;
+                }
+            };
+            let delta = if child == fraction_side {
+                delta
+            } else {
+                -delta
+            };
+            let previous = *fraction;
+            let resized = self.tree.clamp_to_min_sizes(
+                parent,
+                (previous + delta).clamp(0.0, 1.0),
+                separator_width,
+            );
+            if (resized - previous).abs() <= f32::EPSILON {
+                return None;
+            }
+            self.tree.set_fraction(parent, resized);
+            return Some((parent, resized));
+        }
+        None
+    }
+
+    /// Moves the active tab of `focused` to the adjacent leaf in `direction`, splitting `focused` at that edge
+    /// if there's no leaf there.
+    fn move_active_tab(
+        &mut self,
+        surface: SurfaceIndex,
+        focused: NodeIndex,
+        direction: Split,
+        events: &mut Vec<DockEvent>,
+    ) {
+        let tabs_count = self.tree[focused].tabs_count();
+        let target = self.tree.adjacent_leaf(focused, direction);
+        if tabs_count == 0 || (target.is_none() && tabs_count == 1) {
+            return;
+        }
+        self.tree.record_history();
+
+        let (tab, tab_index) = match &mut self.tree[focused] {
+            Node::Leaf { tabs, active, .. } => {
+                let tab_index = TabIndex(active.0.min(tabs.len() - 1));
+                let tab = tabs.remove(tab_index.0);
+                active.0 = active.0.min(tabs.len().saturating_sub(1));
+                (tab, tab_index)
+            }
+            _ => return,
+        };
+
+        let dst = match target {
+            Some(target) => {
+                self.tree[target].append_tab(tab);
+                self.tree.set_focused_node(target);
+                if self.tree.remove_leaf_if_empty(focused) {
+                    events.push(DockEvent::NodeRemoved {
+                        surface,
+                        node: focused,
+                    });
+                }
+                target
+            }
+            None => {
+                events.push(DockEvent::NodeSplit {
+                    surface,
+                    node: focused,
+                    split: direction,
+                });
+                self.tree.split(focused, direction, 0.5, Node::leaf(tab))[1]
+            }
+        };
+        events.push(DockEvent::TabMoved {
+            src: (surface, focused, tab_index),
+            dst: (surface, dst),
+        });
+    }
+
+    /// Splits `focused` and opens either a duplicate of its active tab or the active tab itself in the new leaf.
+    fn split_focused_leaf(
+        &mut self,
+        surface: SurfaceIndex,
+        focused: NodeIndex,
+        split: Split,
+        events: &mut Vec<DockEvent>,
+        tab_viewer: &mut impl TabViewer<Tab = Tab>,
+    ) {
+        let duplicate = match &self.tree[focused] {
+            Node::Leaf { tabs, active, .. } => match tabs.get(active.0) {
+                Some(tab) => tab_viewer.duplicate_tab(tab),
+                None => return,
+            },
+            _ => return,
+        };
+        // Moving the only tab out would leave an empty leaf behind.
+        if duplicate.is_none() && self.tree[focused].tabs_count() < 2 {
+            return;
+        }
+        self.tree.record_history();
+
+        let (new_tab, moved) = match (duplicate, &mut self.tree[focused]) {
+            (Some(tab), _) => (tab, None),
+            (None, Node::Leaf { tabs, active, .. }) => {
+                let moved = *active;
+                let tab = tabs.remove(active.0);
+                active.0 = active.0.min(tabs.len() - 1);
+                (tab, Some(moved))
+            }
+            (None, _) => return,
+        };
+        let [_, node] = self.tree.split(focused, split, 0.5, Node::leaf(new_tab));
+        events.push(DockEvent::NodeSplit {
+            surface,
+            node: focused,
+            split,
+        });
+        events.push(match moved {
+            Some(tab) => DockEvent::TabMoved {
+                src: (surface, focused, tab),
+                dst: (surface, node),
+            },
+            None => DockEvent::TabAdded {
+                surface,
+                node,
+                tab: TabIndex(0),
+            },
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pinned_first_keeps_order_and_active_tab() {
+        let is_pinned = |tab: &&str| tab.starts_with('*');
+
+        let mut tabs = ["a", "*b", "c", "*d", "e"];
+        let mut active = TabIndex(2);
+        pinned_first(&mut tabs, &mut active, is_pinned);
+        assert_eq!(tabs, ["*b", "*d", "a", "c", "e"]);
+        assert_eq!(tabs[active.0], "c");
+
+        let mut active = TabIndex(1);
+        pinned_first(&mut tabs, &mut active, is_pinned);
+        assert_eq!(tabs, ["*b", "*d", "a", "c", "e"]);
+        assert_eq!(active, TabIndex(1));
+
+        let mut tabs = ["a", "b", "*c", "d", "e"];
+        let mut active = TabIndex(2);
+        pinned_first(&mut tabs, &mut active, is_pinned);
+        assert_eq!(tabs, ["*c", "a", "b", "d", "e"]);
+        assert_eq!(active, TabIndex(0));
     }
 }
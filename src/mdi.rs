@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+
+use egui::*;
+
+/// How far each new child window is moved down and right from the previous one.
+const CASCADE_OFFSET: f32 = 24.0;
+
+/// The smallest size a child window can be resized to.
+const MIN_CHILD_SIZE: Vec2 = vec2(80.0, 48.0);
+
+/// Where the child windows of leaves shown with [`Tree::set_mdi`](crate::Tree::set_mdi) are.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Mdi {
+    /// Rects of the child windows relative to the top left corner of their leaf, keyed by tab id.
+    rects: HashMap<Id, Rect>,
+    /// Rects of the child windows as of the previous frame, moved back to `rects` when they're shown again.
+    previous_rects: HashMap<Id, Rect>,
+    /// Scratch buffer for the id and rect of every child window of the leaf being shown.
+    pub children: Vec<(Id, Rect)>,
+}
+
+impl Mdi {
+    /// Forgets the child windows which weren't shown since the last call, like those of closed tabs or of leaves
+    /// no longer shown as child windows.
+    pub fn forget_hidden(&mut self) {
+        std::mem::swap(&mut self.rects, &mut self.previous_rects);
+        self.rects.clear();
+    }
+
+    /// Returns the rect of the child window of the tab `id`, the `index`th tab of the leaf at `leaf`.
+    ///
+    /// Windows shown for the first time are cascaded from the top left corner of the leaf. Windows are kept inside
+    /// the leaf, shrinking them if the leaf got smaller.
+    pub fn child_rect(&mut self, id: Id, index: usize, leaf: Rect) -> Rect {
+        let size = leaf.size();
+        if let Some(rect) = self.previous_rects.remove(&id) {
+            self.rects.insert(id, rect);
+        }
+        let rect = self.rects.entry(id).or_insert_with(|| {
+            let offset = Vec2::splat(CASCADE_OFFSET * (index % 8) as f32);
+            Rect::from_min_size(Pos2::ZERO + offset, size * 0.6)
+        });
+
+        let child_size = rect.size().max(MIN_CHILD_SIZE).min(size);
+        let min = rect.min.clamp(Pos2::ZERO, (size - child_size).to_pos2());
+        *rect = Rect::from_min_size(min, child_size);
+
+        rect.translate(leaf.min.to_vec2())
+    }
+
+    /// Moves the child window of the tab `id` by `delta`.
+    pub fn translate(&mut self, id: Id, delta: Vec2) {
+        if let Some(rect) = self.rects.get_mut(&id) {
+            *rect = rect.translate(delta);
+        }
+    }
+
+    /// Moves the bottom right corner of the child window of the tab `id` by `delta`.
+    pub fn resize(&mut self, id: Id, delta: Vec2) {
+        if let Some(rect) = self.rects.get_mut(&id) {
+            rect.max = (rect.max + delta).max(rect.min + MIN_CHILD_SIZE);
+        }
+    }
+}
@@ -0,0 +1,390 @@
+use egui::style::Margin;
+use egui::widget_text::WidgetTextGalley;
+use egui::*;
+
+use crate::utils::*;
+use crate::{Split, Style};
+
+/// What a tab title looks like at the moment it's painted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TabState {
+    /// The tab is the active tab of its leaf.
+    pub active: bool,
+    /// The tab is the active tab of the focused leaf.
+    pub focused: bool,
+    /// The tab is being dragged.
+    pub dragged: bool,
+    /// The pointer is over the tab.
+    pub hovered: bool,
+}
+
+/// An icon shown before the title of a tab, see [`TabViewer::tab_icon`](crate::TabViewer::tab_icon).
+#[derive(Clone, Debug, PartialEq)]
+pub enum TabIcon {
+    /// A texture, like a file type icon, stretched to [`Style::tab_icon_size`].
+    Texture(TextureId),
+    /// A glyph of the font, like an emoji, painted in the color of the title.
+    Glyph(String),
+}
+
+/// The state of a separator being painted by [`DockPainter::separator`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SeparatorState {
+    /// The separator splits a horizontal node, so it runs from top to bottom between the children.
+    pub horizontal: bool,
+    /// The pointer is over the separator.
+    pub hovered: bool,
+    /// The separator is being dragged.
+    pub dragged: bool,
+}
+
+/// Paints the chrome of a [`DockArea`](crate::DockArea): tab bars, tabs, separators, borders and drop previews.
+///
+/// Every method has a default implementation which paints the chrome the way [`StylePainter`] does, using the colors
+/// and sizes of the [`Style`]. Override the ones you want to look different and set your painter with
+/// [`DockArea::painter`](crate::DockArea::painter). Widgets and interaction aren't affected, only what gets painted.
+pub trait DockPainter {
+    /// Paints the border around the whole dock area, shown when [`Style::padding`] is set.
+    fn border(&self, painter: &Painter, style: &Style, rect: Rect, margin: Margin) {
+        painter.rect(
+            rect,
+            margin.top,
+            style.separator_color,
+            Stroke::new(margin.top, style.border_color),
+        );
+    }
+
+    /// Paints a separator between two nodes, like grip dots or a gradient. Dragging it is handled by the dock.
+    fn separator(&self, painter: &Painter, style: &Style, rect: Rect, _state: SeparatorState) {
+        painter.rect_filled(rect, Rounding::none(), style.separator_color);
+    }
+
+    /// Paints the background of a leaf's tab bar. `px` is the size of a physical pixel in points.
+    fn tab_bar(&self, painter: &Painter, style: &Style, rect: Rect, px: f32) {
+        let rect = round_to_pixel(rect, px.recip());
+        painter.rect_filled(rect, style.tab_rounding, style.tab_bar_background_color);
+
+        // Lines a pixel wide are centered on the middle of the last row of pixels.
+        let y = rect.max.y - px * 0.5;
+        let a = pos2(rect.min.x, y);
+        let b = pos2(rect.max.x, y);
+        painter.line_segment([a, b], (px, style.tab_outline_color));
+    }
+
+    /// Paints the background of a tab title. `px` is the size of a physical pixel in points.
+    fn tab(&self, painter: &Painter, style: &Style, rect: Rect, state: TabState, px: f32) {
+        let rect = round_to_pixel(rect, px.recip());
+        match (state.active, state.dragged) {
+            (true, false) => {
+                let mut tab = rect;
+                tab.min.x -= px;
+                tab.max.x += px;
+                painter.rect_filled(tab, style.tab_rounding, style.tab_outline_color);
+
+                tab.min.x += px;
+                tab.max.x -= px;
+                tab.min.y += px;
+                painter.rect_filled(tab, style.tab_rounding, style.tab_background_color);
+            }
+            (_, true) => {
+                // Dragged titles have a background of their own, as they leave their tab bar behind.
+                painter.rect_filled(rect, style.tab_rounding, style.tab_background_color);
+                painter.rect_stroke(
+                    rect.shrink(px * 0.5),
+                    style.tab_rounding,
+                    Stroke::new(px, style.tab_outline_color),
+                );
+            }
+            _ => (),
+        }
+    }
+
+    /// Paints the text of a tab title, with its top left corner at `pos`.
+    fn tab_title(
+        &self,
+        painter: &Painter,
+        style: &Style,
+        pos: Pos2,
+        galley: WidgetTextGalley,
+        state: TabState,
+    ) {
+        let override_text_color = if galley.galley_has_color {
+            None // respect the color the user has chosen
+        } else if state.focused || style.accessibility_mode {
+            Some(style.tab_text_color_focused)
+        } else {
+            Some(style.tab_text_color_unfocused)
+        };
+        painter.add(epaint::TextShape {
+            pos,
+            galley: galley.galley,
+            underline: Stroke::none(),
+            override_text_color,
+            angle: 0.0,
+        });
+    }
+
+    /// Paints the icon of a tab inside `rect`, a square [`Style::tab_icon_size`] large left of its title.
+    fn tab_icon(
+        &self,
+        painter: &Painter,
+        style: &Style,
+        rect: Rect,
+        icon: &TabIcon,
+        state: TabState,
+    ) {
+        match icon {
+            TabIcon::Texture(texture) => {
+                let uv = Rect::from_min_max(Pos2::ZERO, pos2(1.0, 1.0));
+                painter.add(Shape::image(*texture, rect, uv, Color32::WHITE));
+            }
+            TabIcon::Glyph(glyph) => {
+                let color = if state.focused || style.accessibility_mode {
+                    style.tab_text_color_focused
+                } else {
+                    style.tab_text_color_unfocused
+                };
+                painter.text(
+                    rect.center(),
+                    Align2::CENTER_CENTER,
+                    glyph,
+                    FontId::proportional(rect.height()),
+                    color,
+                );
+            }
+        }
+    }
+
+    /// Paints the close button of a tab.
+    ///
+    /// `highlighted` is set while the button is hovered or has keyboard focus, `active` while it's being pressed or
+    /// its tab is focused.
+    fn close_button(
+        &self,
+        painter: &Painter,
+        style: &Style,
+        rect: Rect,
+        highlighted: bool,
+        active: bool,
+    ) {
+        let ppi = painter.ctx().pixels_per_point();
+        let rect = round_to_pixel(rect, ppi);
+        if highlighted {
+            painter.rect_filled(rect, Rounding::same(2.0), style.close_tab_background_color);
+        }
+        let rect = round_to_pixel(rect.shrink(1.75), ppi);
+
+        let color = if active {
+            style.close_tab_active_color
+        } else {
+            style.close_tab_color
+        };
+        let stroke = Stroke::new(stroke_width_to_pixel(1.0, ppi), color);
+        painter.line_segment([rect.left_top(), rect.right_bottom()], stroke);
+        painter.line_segment([rect.right_top(), rect.left_bottom()], stroke);
+    }
+
+    /// Paints the dot shown in place of the close button of a tab with unsaved changes, see
+    /// [`TabViewer::is_modified`](crate::TabViewer::is_modified).
+    ///
+    /// `active` is set while its tab is the active tab of the focused leaf.
+    fn modified_marker(&self, painter: &Painter, style: &Style, rect: Rect, active: bool) {
+        let color = if active {
+            style.modified_marker_active_color
+        } else {
+            style.modified_marker_color
+        };
+        painter.circle_filled(rect.center(), rect.width() * 0.25, color);
+    }
+
+    /// Paints the badge of a tab as a pill filling `rect`, see [`TabViewer::badge`](crate::TabViewer::badge). Text
+    /// wider than [`Style::tab_badge_max_width`] is cut off at its end.
+    fn tab_badge(
+        &self,
+        painter: &Painter,
+        style: &Style,
+        rect: Rect,
+        galley: WidgetTextGalley,
+        _state: TabState,
+    ) {
+        painter.rect_filled(rect, rect.height() * 0.5, style.tab_badge_color);
+        let override_text_color = if galley.galley_has_color {
+            None
+        } else {
+            Some(style.tab_badge_text_color)
+        };
+        let inner = rect.shrink2(vec2(rect.height() * 0.5, 0.0));
+        let pos = pos2(
+            (rect.center().x - galley.size().x * 0.5).max(inner.min.x),
+            rect.center().y - galley.size().y * 0.5,
+        );
+        painter
+            .with_clip_rect(inner.intersect(painter.clip_rect()))
+            .add(epaint::TextShape {
+                pos,
+                galley: galley.galley,
+                underline: Stroke::none(),
+                override_text_color,
+                angle: 0.0,
+            });
+    }
+
+    /// Paints the background of a tab's body, for tabs which ask for it with
+    /// [`TabViewer::clear_background`](crate::TabViewer::clear_background).
+    ///
+    /// `fill` is [`Style::tab_background_color`], or the color of
+    /// [`TabViewer::inner_background`](crate::TabViewer::inner_background) for tabs which have one.
+    fn tab_body(&self, painter: &Painter, _style: &Style, rect: Rect, fill: Color32) {
+        painter.rect_filled(rect, 0.0, fill);
+    }
+
+    /// Paints the outline of whatever has keyboard focus: a tab title, or the focused leaf when
+    /// [`Style::show_focus_outline`] is set.
+    fn focus_outline(
+        &self,
+        painter: &Painter,
+        _style: &Style,
+        rect: Rect,
+        rounding: Rounding,
+        stroke: Stroke,
+    ) {
+        let ppi = painter.ctx().pixels_per_point();
+        let (rect, width) = stroke_rect_to_pixel(rect, stroke.width, ppi);
+        painter.rect_stroke(rect, rounding, Stroke::new(width, stroke.color));
+    }
+
+    /// Paints the "+" button shown after the tabs of a tab bar when [`Style::show_add_buttons`] is set.
+    fn add_button(&self, painter: &Painter, style: &Style, rect: Rect, highlighted: bool) {
+        if highlighted {
+            painter.rect_filled(
+                rect.shrink(2.0),
+                Rounding::same(2.0),
+                style.close_tab_background_color,
+            );
+        }
+        let ppi = painter.ctx().pixels_per_point();
+        let center = pixel_center(rect.center(), ppi);
+        let rect = Rect::from_center_size(center, Vec2::splat(rect.height() * 0.35));
+        let stroke = Stroke::new(
+            stroke_width_to_pixel(1.0, ppi),
+            style.tab_text_color_unfocused,
+        );
+        painter.line_segment([rect.center_top(), rect.center_bottom()], stroke);
+        painter.line_segment([rect.left_center(), rect.right_center()], stroke);
+    }
+
+    /// Paints the stripe of `color` marking a tab title at `rect` as part of a group, see
+    /// [`TabViewer::group`](crate::TabViewer::group).
+    fn tab_group_stripe(&self, painter: &Painter, _style: &Style, rect: Rect, color: Color32) {
+        let ppi = painter.ctx().pixels_per_point();
+        let stripe = Rect::from_min_size(rect.min, vec2(rect.width(), 2.0));
+        painter.rect_filled(round_to_pixel(stripe, ppi), 0.0, color);
+    }
+
+    /// Paints the label starting a group of tabs, see [`Style::collapsible_tab_groups`]. `galley` is the name of the
+    /// group, to be centered in `rect`.
+    fn tab_group_label(
+        &self,
+        painter: &Painter,
+        style: &Style,
+        rect: Rect,
+        galley: WidgetTextGalley,
+        color: Color32,
+        collapsed: bool,
+    ) {
+        let rect = rect.shrink2(vec2(2.0, 4.0));
+        let text_color = if collapsed {
+            painter.rect_filled(rect, Rounding::same(4.0), color);
+            style.tab_background_color
+        } else {
+            painter.rect_stroke(rect, Rounding::same(4.0), Stroke::new(1.0, color));
+            color
+        };
+        painter.add(epaint::TextShape {
+            pos: rect.center() - galley.size() * 0.5,
+            galley: galley.galley,
+            underline: Stroke::none(),
+            override_text_color: Some(text_color),
+            angle: 0.0,
+        });
+    }
+
+    /// Paints the button opening the list of a leaf's tabs, shown at the end of its tab bar when the tabs don't fit.
+    fn tab_list_button(&self, painter: &Painter, style: &Style, rect: Rect, highlighted: bool) {
+        if highlighted {
+            painter.rect_filled(
+                rect.shrink(2.0),
+                Rounding::same(2.0),
+                style.close_tab_background_color,
+            );
+        }
+        painter.text(
+            rect.center(),
+            Align2::CENTER_CENTER,
+            "⏷",
+            FontId::proportional(rect.height() * 0.5),
+            style.tab_text_color_unfocused,
+        );
+    }
+
+    /// Paints the lock shown at the end of every tab bar while the dock is
+    /// [read-only](crate::DockArea::read_only).
+    fn locked_indicator(&self, painter: &Painter, style: &Style, rect: Rect) {
+        painter.text(
+            rect.center(),
+            Align2::CENTER_CENTER,
+            "🔒",
+            FontId::proportional(rect.height() * 0.5),
+            style.tab_text_color_unfocused.linear_multiply(0.5),
+        );
+    }
+
+    /// Paints the area a dragged tab will be dropped into.
+    fn drop_preview(&self, painter: &Painter, style: &Style, rect: Rect) {
+        painter.rect_filled(rect, 0.0, style.selection_color);
+    }
+
+    /// Paints one of the targets shown by [`Style::show_drop_targets`], which splits off the tab on the `split` side
+    /// of the leaf, or adds it to the leaf if `split` is `None`.
+    fn drop_target(
+        &self,
+        painter: &Painter,
+        style: &Style,
+        rect: Rect,
+        split: Option<Split>,
+        hovered: bool,
+    ) {
+        let color = if hovered {
+            style.drop_target_hover_color
+        } else {
+            style.drop_target_color
+        };
+        let ppi = painter.ctx().pixels_per_point();
+        let (outline, width) = stroke_rect_to_pixel(rect, 1.0, ppi);
+        painter.rect(
+            outline,
+            4.0,
+            style.tab_background_color,
+            Stroke::new(width, color),
+        );
+
+        let icon = round_to_pixel(rect.shrink(rect.width() * 0.2), ppi);
+        let center = icon.center();
+        let docked = match split {
+            None => icon,
+            Some(Split::Left) => icon.intersect(Rect::everything_left_of(center.x)),
+            Some(Split::Right) => icon.intersect(Rect::everything_right_of(center.x)),
+            Some(Split::Above) => icon.intersect(Rect::everything_above(center.y)),
+            Some(Split::Below) => icon.intersect(Rect::everything_below(center.y)),
+        };
+        painter.rect_filled(docked, 0.0, color);
+        let (icon, width) = stroke_rect_to_pixel(icon, 1.0, ppi);
+        painter.rect_stroke(icon, 0.0, Stroke::new(width, color));
+    }
+}
+
+/// The default [`DockPainter`], which paints everything from the [`Style`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StylePainter;
+
+impl DockPainter for StylePainter {}
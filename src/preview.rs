@@ -0,0 +1,408 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use egui::epaint::{ClippedPrimitive, ImageDelta, Mesh, Primitive, Vertex};
+use egui::*;
+
+/// Seconds the picture of an active tab is kept before it's captured again.
+const CAPTURE_INTERVAL: f64 = 1.0;
+
+/// Longest side, in pixels, of the pictures of tabs. Larger contents are scaled down by whole factors until they fit.
+const MAX_PREVIEW_SIDE: usize = 480;
+
+/// Pictures of the content of tabs, captured while they're active when [`Style::capture_tab_previews`] is set, and
+/// shown while they're dragged or their title is hovered while they're inactive.
+///
+/// [`Style::capture_tab_previews`]: crate::Style::capture_tab_previews
+#[derive(Clone, Default)]
+pub(crate) struct Previews {
+    /// Pictures of the tabs and the time they were captured at, keyed by tab id.
+    textures: HashMap<Id, (TextureHandle, f64)>,
+    /// Pictures as of the previous frame, moved back to `textures` for the tabs still in the tree.
+    previous: HashMap<Id, (TextureHandle, f64)>,
+    capturer: Capturer,
+}
+
+impl fmt::Debug for Previews {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Previews")
+            .field("textures", &self.textures.len())
+            .finish()
+    }
+}
+
+impl Previews {
+    /// Forgets the pictures of the tabs [`Previews::keep`] wasn't called for since the last call, like closed tabs.
+    pub fn forget_closed(&mut self) {
+        std::mem::swap(&mut self.textures, &mut self.previous);
+        self.textures.clear();
+    }
+
+    /// Keeps the picture of the tab `id` around.
+    pub fn keep(&mut self, id: Id) {
+        if let Some(texture) = self.previous.remove(&id) {
+            self.textures.insert(id, texture);
+        }
+    }
+
+    /// Returns the picture of the tab `id`, if it was captured.
+    pub fn texture(&self, id: Id) -> Option<TextureId> {
+        self.textures.get(&id).map(|(texture, _)| texture.id())
+    }
+
+    /// Whether the tab `id` has no picture yet, or one which is too old at `time`.
+    pub fn needs_capture(&self, id: Id, time: f64) -> bool {
+        self.textures
+            .get(&id)
+            .map_or(true, |&(_, captured)| time - captured >= CAPTURE_INTERVAL)
+    }
+
+    /// Captures what `add_contents` shows inside a body `size` large filled with `fill` as the picture of the tab
+    /// `id`.
+    pub fn capture(
+        &mut self,
+        ctx: &Context,
+        id: Id,
+        size: Vec2,
+        fill: Color32,
+        add_contents: impl FnOnce(&mut Ui),
+    ) {
+        let time = ctx.input().time;
+        let image = match self.capturer.capture(ctx, size, fill, add_contents) {
+            Some(image) => image,
+            None => return,
+        };
+        match self.textures.get_mut(&id) {
+            Some((texture, captured)) => {
+                texture.set(image, TextureFilter::Linear);
+                *captured = time;
+            }
+            None => {
+                let texture =
+                    ctx.load_texture("egui_dock_tab_preview", image, TextureFilter::Linear);
+                self.textures.insert(id, (texture, time));
+            }
+        }
+    }
+}
+
+/// Shows the content of tabs in a `Context` of its own and paints what it outputs in software, egui can't read back
+/// what it painted.
+#[derive(Clone, Default)]
+struct Capturer {
+    /// Created with the fonts of the dock's `Context` on the first capture.
+    ctx: Option<Context>,
+    /// Copies of the textures of `ctx`, its font atlas among them, kept up to date from its output.
+    textures: HashMap<TextureId, ColorImage>,
+}
+
+impl Capturer {
+    /// Paints what `add_contents` shows inside a body `size` large filled with `fill`, with the style of `ctx`.
+    ///
+    /// Textures which don't belong to the capturing `Context`, like images the app loaded itself, are painted grey.
+    /// Returns `None` if the body is empty.
+    fn capture(
+        &mut self,
+        ctx: &Context,
+        size: Vec2,
+        fill: Color32,
+        add_contents: impl FnOnce(&mut Ui),
+    ) -> Option<ColorImage> {
+        let [width, height] = [size.x.round() as usize, size.y.round() as usize];
+        if width == 0 || height == 0 {
+            return None;
+        }
+        let capturing = self
+            .ctx
+            .get_or_insert_with(|| {
+                let capturing = Context::default();
+                capturing.set_fonts(ctx.fonts().lock().fonts.definitions().clone());
+                capturing
+            })
+            .clone();
+        capturing.set_style(ctx.style());
+
+        let (time, max_texture_side) = {
+            let input = ctx.input();
+            (input.time, input.max_texture_side)
+        };
+        let input = RawInput {
+            screen_rect: Some(Rect::from_min_size(
+                Pos2::ZERO,
+                vec2(width as f32, height as f32),
+            )),
+            pixels_per_point: Some(1.0),
+            max_texture_side: Some(max_texture_side),
+            time: Some(time),
+            ..Default::default()
+        };
+        let output = capturing.run(input, |ctx| {
+            CentralPanel::default()
+                .frame(Frame::none().fill(fill))
+                .show(ctx, add_contents);
+        });
+        for (id, delta) in output.textures_delta.set {
+            self.set_texture(id, delta);
+        }
+
+        let mut image = ColorImage::new([width, height], Color32::TRANSPARENT);
+        for ClippedPrimitive {
+            clip_rect,
+            primitive,
+        } in capturing.tessellate(output.shapes)
+        {
+            if let Primitive::Mesh(mesh) = primitive {
+                let texture = self.textures.get(&mesh.texture_id);
+                paint_mesh(&mut image, clip_rect, &mesh, texture);
+            }
+        }
+        for id in output.textures_delta.free {
+            self.textures.remove(&id);
+        }
+
+        Some(scale_down(image, MAX_PREVIEW_SIDE))
+    }
+
+    /// Applies a change to a texture of the capturing `Context`.
+    fn set_texture(&mut self, id: TextureId, delta: ImageDelta) {
+        let image = match delta.image {
+            ImageData::Color(image) => image,
+            ImageData::Font(image) => ColorImage {
+                size: image.size,
+                pixels: image.srgba_pixels(1.0).collect(),
+            },
+        };
+        let [x, y] = match delta.pos {
+            Some(pos) => pos,
+            None => {
+                self.textures.insert(id, image);
+                return;
+            }
+        };
+        let texture = match self.textures.get_mut(&id) {
+            Some(texture) => texture,
+            None => return,
+        };
+        let [width, height] = texture.size;
+        for row in 0..image.height().min(height.saturating_sub(y)) {
+            for column in 0..image.width().min(width.saturating_sub(x)) {
+                texture.pixels[(y + row) * width + x + column] = image[(column, row)];
+            }
+        }
+    }
+}
+
+/// Paints the triangles of `mesh` onto `image` inside `clip_rect`, blending their premultiplied colors like egui's
+/// backends do. `texture` is sampled by the nearest texel.
+fn paint_mesh(image: &mut ColorImage, clip_rect: Rect, mesh: &Mesh, texture: Option<&ColorImage>) {
+    let [width, height] = image.size;
+    let clip_rect = clip_rect.intersect(Rect::from_min_size(
+        Pos2::ZERO,
+        vec2(width as f32, height as f32),
+    ));
+    if !clip_rect.is_positive() {
+        return;
+    }
+    let sample = |uv: Pos2| match texture {
+        Some(texture) if texture.width() > 0 && texture.height() > 0 => {
+            let x = ((uv.x * texture.width() as f32) as usize).min(texture.width() - 1);
+            let y = ((uv.y * texture.height() as f32) as usize).min(texture.height() - 1);
+            texture[(x, y)]
+        }
+        _ => Color32::from_gray(128),
+    };
+
+    for triangle in mesh.indices.chunks_exact(3) {
+        let vertex = |i: usize| mesh.vertices.get(triangle[i] as usize).copied();
+        let (a, b, c) = match (vertex(0), vertex(1), vertex(2)) {
+            (Some(a), Some(b), Some(c)) => (a, b, c),
+            _ => continue,
+        };
+        let area = edge(a.pos, b.pos, c.pos);
+        if area.abs() <= f32::EPSILON {
+            continue;
+        }
+        let bounds = Rect::from_points(&[a.pos, b.pos, c.pos]).intersect(clip_rect);
+        if !bounds.is_positive() {
+            continue;
+        }
+        let (x_min, x_max) = (
+            bounds.min.x.floor() as usize,
+            (bounds.max.x.ceil() as usize).min(width),
+        );
+        let (y_min, y_max) = (
+            bounds.min.y.floor() as usize,
+            (bounds.max.y.ceil() as usize).min(height),
+        );
+        for y in y_min..y_max {
+            for x in x_min..x_max {
+                // Pixel centers are nudged off the edges shared by two triangles, which would be painted twice.
+                let p = pos2(x as f32 + 0.5 + 1.0 / 4096.0, y as f32 + 0.5 + 1.0 / 8192.0);
+                if !clip_rect.contains(p) {
+                    continue;
+                }
+                let weights = [
+                    edge(b.pos, c.pos, p) / area,
+                    edge(c.pos, a.pos, p) / area,
+                    edge(a.pos, b.pos, p) / area,
+                ];
+                if weights.iter().any(|&weight| weight < 0.0) {
+                    continue;
+                }
+                let (uv, color) = interpolate([a, b, c], weights);
+                let source = multiply(sample(uv), color);
+                let pixel = &mut image.pixels[y * width + x];
+                *pixel = blend(*pixel, source);
+            }
+        }
+    }
+}
+
+/// Twice the signed area of the triangle `a`, `b`, `p`, which is positive on one side of the edge from `a` to `b`
+/// and negative on the other.
+fn edge(a: Pos2, b: Pos2, p: Pos2) -> f32 {
+    (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+/// The texture coordinates and color inside a triangle at the barycentric `weights` of its `vertices`.
+fn interpolate(vertices: [Vertex; 3], weights: [f32; 3]) -> (Pos2, Color32) {
+    let mut uv = Pos2::ZERO;
+    let mut color = [0.0; 4];
+    for (vertex, weight) in vertices.iter().zip(weights) {
+        uv += vertex.uv.to_vec2() * weight;
+        for (i, channel) in color.iter_mut().enumerate() {
+            *channel += vertex.color[i] as f32 * weight;
+        }
+    }
+    let [r, g, b, a] = color.map(|channel| channel.round().clamp(0.0, 255.0) as u8);
+    (uv, Color32::from_rgba_premultiplied(r, g, b, a))
+}
+
+/// Tints the premultiplied `color` by the premultiplied `tint`.
+fn multiply(color: Color32, tint: Color32) -> Color32 {
+    let [r, g, b, a] = [0, 1, 2, 3].map(|i| ((color[i] as u32 * tint[i] as u32 + 127) / 255) as u8);
+    Color32::from_rgba_premultiplied(r, g, b, a)
+}
+
+/// Paints the premultiplied `source` over `destination`.
+fn blend(destination: Color32, source: Color32) -> Color32 {
+    let keep = 255 - source.a() as u32;
+    let [r, g, b, a] = [0, 1, 2, 3].map(|i| {
+        let value = source[i] as u32 + (destination[i] as u32 * keep + 127) / 255;
+        value.min(255) as u8
+    });
+    Color32::from_rgba_premultiplied(r, g, b, a)
+}
+
+/// Averages blocks of pixels of `image` until its longest side is at most `max_side` long.
+fn scale_down(image: ColorImage, max_side: usize) -> ColorImage {
+    let [width, height] = image.size;
+    let factor = (width.max(height) + max_side - 1) / max_side;
+    if factor <= 1 {
+        return image;
+    }
+    let size = [(width / factor).max(1), (height / factor).max(1)];
+    let mut scaled = ColorImage::new(size, Color32::TRANSPARENT);
+    for y in 0..size[1] {
+        for x in 0..size[0] {
+            let mut sum = [0u32; 4];
+            let mut count = 0;
+            for source_y in y * factor..((y + 1) * factor).min(height) {
+                for source_x in x * factor..((x + 1) * factor).min(width) {
+                    let pixel = image[(source_x, source_y)];
+                    for (i, sum) in sum.iter_mut().enumerate() {
+                        *sum += pixel[i] as u32;
+                    }
+                    count += 1;
+                }
+            }
+            let [r, g, b, a] = sum.map(|sum| (sum / count.max(1)) as u8);
+            scaled[(x, y)] = Color32::from_rgba_premultiplied(r, g, b, a);
+        }
+    }
+    scaled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capture_paints_the_content() {
+        let ctx = Context::default();
+        let mut previews = Previews::default();
+        let id = Id::new("tab");
+        let input = RawInput {
+            time: Some(0.0),
+            ..Default::default()
+        };
+        let output = ctx.run(input, |ctx| {
+            previews.capture(ctx, id, vec2(100.0, 80.0), Color32::BLUE, |ui| {
+                let rect = Rect::from_min_size(ui.max_rect().min, vec2(50.0, 80.0));
+                ui.painter().rect_filled(rect, 0.0, Color32::RED);
+                ui.label("Content");
+            });
+        });
+
+        assert!(
+            !previews.capturer.textures.is_empty(),
+            "the font atlas should be copied"
+        );
+        let texture = previews.texture(id).unwrap();
+        let image = output
+            .textures_delta
+            .set
+            .into_iter()
+            .find_map(|(id, delta)| match delta.image {
+                ImageData::Color(image) if id == texture => Some(image),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(image.size, [100, 80]);
+        assert_eq!(image[(25, 60)], Color32::RED);
+        assert_eq!(image[(75, 60)], Color32::BLUE);
+        assert!(!previews.needs_capture(id, 0.5));
+        assert!(previews.needs_capture(id, 1.0));
+        assert!(previews.needs_capture(Id::new("other"), 0.0));
+    }
+
+    #[test]
+    fn paint_mesh_blends_over_the_background() {
+        let mut image = ColorImage::new([10, 10], Color32::BLUE);
+        let mut mesh = Mesh::default();
+        mesh.add_colored_rect(
+            Rect::from_min_max(pos2(0.0, 0.0), pos2(5.0, 10.0)),
+            Color32::RED,
+        );
+        let white = ColorImage::new([1, 1], Color32::WHITE);
+        let clip_rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(10.0, 8.0));
+        paint_mesh(&mut image, clip_rect, &mesh, Some(&white));
+
+        assert_eq!(image[(2, 2)], Color32::RED);
+        assert_eq!(image[(4, 7)], Color32::RED);
+        assert_eq!(image[(5, 2)], Color32::BLUE);
+        // Clipped away.
+        assert_eq!(image[(2, 9)], Color32::BLUE);
+
+        let mut half = Mesh::default();
+        half.add_colored_rect(
+            Rect::from_min_max(pos2(5.0, 0.0), pos2(10.0, 10.0)),
+            Color32::from_white_alpha(128),
+        );
+        paint_mesh(&mut image, clip_rect, &half, Some(&white));
+        let blended = image[(7, 2)];
+        assert_eq!(blended.a(), 255);
+        assert!(blended.r() == blended.g() && blended.b() > blended.r());
+    }
+
+    #[test]
+    fn scale_down_averages_blocks() {
+        let mut image = ColorImage::new([4, 2], Color32::BLACK);
+        image[(0, 0)] = Color32::WHITE;
+        image[(1, 1)] = Color32::WHITE;
+        let scaled = scale_down(image, 2);
+        assert_eq!(scaled.size, [2, 1]);
+        assert_eq!(scaled[(0, 0)], Color32::from_gray(127));
+        assert_eq!(scaled[(1, 0)], Color32::BLACK);
+    }
+}
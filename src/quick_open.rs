@@ -0,0 +1,139 @@
+use egui::*;
+
+use crate::tree::{NodeIndex, TabIndex};
+
+/// Persistent state of the quick-open palette.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct QuickOpen {
+    /// The palette is shown while this is `Some`.
+    query: Option<String>,
+    selected: usize,
+}
+
+/// A tab listed by the palette.
+pub(crate) struct Entry {
+    pub node: NodeIndex,
+    pub tab: TabIndex,
+    pub title: String,
+    /// Position of the tab in [`Tree::recent_tabs`](crate::Tree::recent_tabs), `usize::MAX` if it isn't there.
+    pub rank: usize,
+}
+
+impl QuickOpen {
+    pub fn is_open(&self) -> bool {
+        self.query.is_some()
+    }
+
+    pub fn open(&mut self) {
+        self.query = Some(String::new());
+        self.selected = 0;
+    }
+
+    pub fn close(&mut self) {
+        self.query = None;
+    }
+
+    /// Shows the palette centered at the top of `rect`.
+    ///
+    /// Returns the entry picked by the user, if any.
+    pub fn show(
+        &mut self,
+        ui: &Ui,
+        rect: Rect,
+        id: Id,
+        animation_time: f32,
+        mut entries: Vec<Entry>,
+    ) -> Option<Entry> {
+        let query = self.query.as_mut()?;
+
+        let mut scored: Vec<(usize, Entry)> = entries
+            .drain(..)
+            .filter_map(|entry| fuzzy_score(query, &entry.title).map(|score| (score, entry)))
+            .collect();
+        scored.sort_by_key(|(score, entry)| (*score, entry.rank));
+
+        let (up, down, enter, escape) = {
+            let input = ui.input();
+            (
+                input.key_pressed(Key::ArrowUp),
+                input.key_pressed(Key::ArrowDown),
+                input.key_pressed(Key::Enter),
+                input.key_pressed(Key::Escape),
+            )
+        };
+        if down {
+            self.selected += 1;
+        }
+        if up {
+            self.selected = self.selected.saturating_sub(1);
+        }
+        self.selected = self.selected.min(scored.len().saturating_sub(1));
+
+        let width = (rect.width() * 0.5).clamp(200.0, 500.0).min(rect.width());
+        let mut picked = None;
+
+        Area::new(id)
+            .order(Order::Foreground)
+            .fixed_pos(pos2(rect.center().x - width * 0.5, rect.min.y + 8.0))
+            .show(ui.ctx(), |ui| {
+                ui.style_mut().animation_time = animation_time;
+                Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_width(width);
+
+                    let response = ui.add(
+                        TextEdit::singleline(query)
+                            .hint_text("Go to tab…")
+                            .desired_width(f32::INFINITY),
+                    );
+                    response.request_focus();
+                    if response.changed() {
+                        self.selected = 0;
+                    }
+
+                    ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                        for (index, (_, entry)) in scored.iter().enumerate() {
+                            let label = ui.selectable_label(index == self.selected, &entry.title);
+                            if index == self.selected && (up || down) {
+                                label.scroll_to_me(None);
+                            }
+                            if label.clicked() {
+                                picked = Some(index);
+                            }
+                        }
+                    });
+                });
+            });
+
+        if enter && !scored.is_empty() {
+            picked = Some(self.selected);
+        }
+
+        if escape || picked.is_some() {
+            self.close();
+        }
+
+        picked.map(|index| scored.swap_remove(index).1)
+    }
+}
+
+/// Scores how well `query` matches `title` as a case-insensitive subsequence. Lower is better.
+///
+/// Returns `None` if not every character of `query` appears in `title` in order.
+pub(crate) fn fuzzy_score(query: &str, title: &str) -> Option<usize> {
+    let mut score = 0;
+    let mut gap = 0;
+    let mut title = title.chars().flat_map(char::to_lowercase);
+
+    for needle in query.chars().flat_map(char::to_lowercase) {
+        loop {
+            if title.next()? == needle {
+                score += gap;
+                gap = 0;
+                break;
+            }
+            gap += 1;
+        }
+    }
+
+    Some(score)
+}
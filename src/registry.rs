@@ -0,0 +1,128 @@
+use egui::*;
+
+use crate::Tree;
+
+/// Makes a tab of some kind out of its payload.
+pub type TabFactory<Tab> = Box<dyn Fn(&str) -> Option<Tab> + 'static>;
+
+/// A tab saved as the name of its kind and whatever that kind needs to make the tab again, like a file path.
+///
+/// See [`TabRegistry`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct SavedTab {
+    pub kind: String,
+    pub payload: String,
+}
+
+impl SavedTab {
+    pub fn new(kind: impl Into<String>, payload: impl Into<String>) -> Self {
+        Self {
+            kind: kind.into(),
+            payload: payload.into(),
+        }
+    }
+}
+
+/// Maps the names of the kinds of tabs an app has to the functions making them.
+///
+/// New tabs are made from an empty payload, like from the menu shown with [`TabRegistry::add_menu`], which fills the
+/// popup of the "+" buttons of tab bars when called from [`TabViewer::add_popup`](crate::TabViewer::add_popup).
+/// Saved tabs are made again from theirs, which lets a [`Tree`] of tabs which can't be serialized themselves be saved
+/// as a `Tree<SavedTab>`:
+///
+/// ```rust
+/// # use egui_dock::{SavedTab, TabRegistry, Tree};
+/// enum Tab {
+///     Editor { path: String },
+///     Console,
+/// }
+///
+/// let registry = TabRegistry::new()
+///     .with_kind("Editor", |payload| {
+///         Some(Tab::Editor {
+///             path: payload.to_owned(),
+///         })
+///     })
+///     .with_kind("Console", |_| Some(Tab::Console));
+///
+/// let tree = Tree::new(vec![Tab::Editor { path: "main.rs".to_owned() }, Tab::Console]);
+///
+/// // `saved` can be serialized with the `serde` feature.
+/// let saved: Tree<SavedTab> = tree.map_tabs(|tab| match tab {
+///     Tab::Editor { path } => SavedTab::new("Editor", path),
+///     Tab::Console => SavedTab::new("Console", ""),
+/// });
+/// let tree: Tree<Tab> = registry.load(saved);
+/// # assert_eq!(tree.tabs().count(), 2);
+/// ```
+pub struct TabRegistry<Tab> {
+    kinds: Vec<(String, TabFactory<Tab>)>,
+}
+
+impl<Tab> Default for TabRegistry<Tab> {
+    fn default() -> Self {
+        Self { kinds: Vec::new() }
+    }
+}
+
+impl<Tab> TabRegistry<Tab> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the kind of tab named `kind`, made from a payload by `factory`, replacing any kind with the same name.
+    ///
+    /// `factory` returns `None` if the payload isn't valid for the kind anymore, like a path to a deleted file.
+    pub fn with_kind(
+        mut self,
+        kind: impl Into<String>,
+        factory: impl Fn(&str) -> Option<Tab> + 'static,
+    ) -> Self {
+        let kind = kind.into();
+        self.kinds.retain(|(name, _)| *name != kind);
+        self.kinds.push((kind, Box::new(factory)));
+        self
+    }
+
+    /// Iterates over the names of the registered kinds, in the order they were registered.
+    pub fn kinds(&self) -> impl Iterator<Item = &str> + '_ {
+        self.kinds.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Makes a tab of the kind named `kind` from `payload`.
+    ///
+    /// Returns `None` if there's no such kind, or if its factory returned `None`.
+    pub fn create(&self, kind: &str, payload: &str) -> Option<Tab> {
+        self.kinds
+            .iter()
+            .find(|(name, _)| name == kind)
+            .and_then(|(_, factory)| factory(payload))
+    }
+
+    /// Makes the tab saved as `saved`, see [`TabRegistry::create`].
+    pub fn restore(&self, saved: &SavedTab) -> Option<Tab> {
+        self.create(&saved.kind, &saved.payload)
+    }
+
+    /// Turns a tree saved with [`Tree::map_tabs`] back into one of tabs. Tabs which can't be made again are dropped,
+    /// together with the leaves they leave empty.
+    pub fn load(&self, tree: Tree<SavedTab>) -> Tree<Tab> {
+        Tree::restore(tree, |saved| self.restore(&saved))
+    }
+
+    /// Shows a button for every registered kind, like inside the menu of an "add tab" button or in
+    /// [`TabViewer::add_popup`](crate::TabViewer::add_popup).
+    ///
+    /// Returns a new tab of the kind clicked, made from an empty payload.
+    pub fn add_menu(&self, ui: &mut Ui) -> Option<Tab> {
+        let mut added = None;
+        for (name, factory) in &self.kinds {
+            if ui.button(name.as_str()).clicked() {
+                ui.close_menu();
+                added = factory("");
+            }
+        }
+        added
+    }
+}
@@ -1,9 +1,47 @@
-use super::utils::*;
+use super::painter::{DockPainter, TabIcon, TabState};
+use crate::galley_cache::title_galley;
+use crate::TabBarPosition;
 use egui::style::Margin;
+use egui::widget_text::WidgetTextGalley;
 use egui::*;
+use std::collections::HashMap;
+
+/// Values derived from a [`Style`] and the current frame, worked out once per [`DockArea`](crate::DockArea)
+/// `show` call instead of once per tab or separator.
+pub(crate) struct Metrics {
+    pub pixels_per_point: f32,
+    /// Size of a single physical pixel in points.
+    pub px: f32,
+    pub tab_bar_height: f32,
+    pub separator_width: f32,
+    pub focus_outline_stroke: Stroke,
+    pub animation_time: f32,
+    pub separator_animation_time: f32,
+    pub tab_highlight_animation_time: f32,
+    pub collapse_animation_time: f32,
+}
+
+/// What tab bars do with tabs which don't fit, see [`Style::tab_overflow`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum TabOverflow {
+    /// The tab bar scrolls sideways, and the tabs are listed in a menu opened from its end.
+    #[default]
+    Scroll,
+    /// The tabs wrap onto more rows, the tab bar growing taller and the body of the leaf shorter.
+    Wrap,
+    /// The tabs share out the width of the tab bar, their titles cut short with an ellipsis. Tabs which still don't
+    /// fit scroll.
+    Shrink,
+}
 
 /// Specifies the look and feel of egui_dock.
+///
+/// With the `serde` feature it can be loaded from and saved to config files. Fields missing from them keep their
+/// `Default` value.
 #[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Style {
     pub padding: Option<Margin>,
 
@@ -13,8 +51,39 @@ pub struct Style {
     /// Color used when previewing where a tab will end up.
     pub selection_color: Color32,
 
+    /// Shows five targets in the middle of the leaf a tab is dragged over, one to split it on each side and one to
+    /// add the tab to it. Dropping onto a target docks the tab there, while elsewhere the closest side of the leaf is
+    /// picked as without them.
+    pub show_drop_targets: bool,
+    /// Width and height of each drop target.
+    pub drop_target_size: f32,
+    /// Color of the drop targets.
+    pub drop_target_color: Color32,
+    /// Color of the drop target under the pointer.
+    pub drop_target_hover_color: Color32,
+
+    /// Docks tabs dropped along the edges of the whole dock area beside every node there, splitting the root of the
+    /// tree instead of the leaf under the pointer. Where they'd go is previewed with `selection_color`.
+    pub show_edge_drop_zones: bool,
+    /// How far, in points, from the edges of the dock area tabs have to be dropped to be docked along them.
+    pub edge_drop_zone_width: f32,
+
     pub separator_width: f32,
     pub separator_extra: f32,
+    /// How far, in points, separators can be grabbed on either side of them, on top of their
+    /// [`Style::separator_width`]. Makes thin separators easier to grab without painting them any thicker.
+    pub separator_hit_extension: f32,
+    /// Fraction a separator goes back to when it's double-clicked. `None` leaves double-clicks alone.
+    pub separator_default_fraction: Option<f32>,
+    /// Fractions separators snap to while they're dragged within [`Style::separator_snap_distance`] of them, like
+    /// `0.5` to line leaves up in the middle. By `Default` there are none.
+    pub separator_snap_fractions: Vec<f32>,
+    /// How close, in points, a dragged separator has to get to one of [`Style::separator_snap_fractions`] to snap to
+    /// it.
+    pub separator_snap_distance: f32,
+    /// The smallest size, in points, separators can be dragged or the dock area shrunk to make a leaf. Nodes can
+    /// have minimums of their own as well, see [`Tree::set_min_size`](crate::Tree::set_min_size).
+    pub min_node_size: Vec2,
     pub separator_color: Color32,
 
     pub tab_bar_background_color: Color32,
@@ -23,13 +92,135 @@ pub struct Style {
     pub tab_rounding: Rounding,
     pub tab_background_color: Color32,
 
+    /// Width and height of the icons shown before tab titles, see [`TabViewer::tab_icon`](crate::TabViewer::tab_icon).
+    pub tab_icon_size: f32,
+    /// Space between the icon of a tab and its title.
+    pub tab_icon_spacing: f32,
+
+    /// The widest a tab can get, icon and close button included. Longer titles are cut short with an ellipsis and
+    /// shown whole in a tooltip. `None` leaves tabs as wide as their titles.
+    pub tab_max_width: Option<f32>,
+    /// What tab bars do with tabs which don't fit. By `Default` they scroll.
+    pub tab_overflow: TabOverflow,
+
+    /// Height of the tab bars. [`Style::accessibility_mode`] makes them at least 32 points high.
+    pub tab_bar_height: f32,
+
+    /// Colors of the stripes marking the tabs of groups, keyed by the names returned by
+    /// [`TabViewer::group`](crate::TabViewer::group). Groups which aren't listed use
+    /// [`Style::tab_group_default_color`].
+    pub tab_group_colors: HashMap<String, Color32>,
+    /// Color of the stripes marking the tabs of groups without a color of their own.
+    pub tab_group_default_color: Color32,
+    /// Starts every run of tabs of a group with a label showing the name of the group, which collapses the tabs of
+    /// the group when clicked, apart from the active one.
+    pub collapsible_tab_groups: bool,
+    /// Adds a "Pin" / "Unpin" entry to the menu of tab titles, which calls
+    /// [`TabViewer::set_pinned`](crate::TabViewer::set_pinned).
+    pub pinnable_tabs: bool,
+    /// Shows only the icon of pinned tabs which have one, see [`TabViewer::is_pinned`](crate::TabViewer::is_pinned).
+    pub compact_pinned_tabs: bool,
+
     pub tab_text_color_unfocused: Color32,
     pub tab_text_color_focused: Color32,
 
     pub close_tab_color: Color32,
     pub close_tab_active_color: Color32,
     pub close_tab_background_color: Color32,
+    /// Color of the dot marking tabs with unsaved changes, see
+    /// [`TabViewer::is_modified`](crate::TabViewer::is_modified).
+    pub modified_marker_color: Color32,
+    /// Color of the dot marking tabs with unsaved changes in the focused leaf.
+    pub modified_marker_active_color: Color32,
+    /// Background color of the badges next to tab titles, see [`TabViewer::badge`](crate::TabViewer::badge).
+    pub tab_badge_color: Color32,
+    /// Color of the text of tab badges, unless the badge text has its own color.
+    pub tab_badge_text_color: Color32,
+    /// Widest a tab badge gets, longer badge texts are cut off.
+    pub tab_badge_max_width: f32,
     pub show_close_buttons: bool,
+    /// Puts the close buttons on the left of the tab titles, like macOS does, instead of on the right.
+    pub close_buttons_on_left: bool,
+    /// Lays the tab bars out from right to left: the tabs start on the right, icons and close buttons swap sides and
+    /// the ends of the tab bars shown with [`TabViewer::tab_bar_leading_ui`](crate::TabViewer::tab_bar_leading_ui)
+    /// and [`TabViewer::tab_bar_trailing_ui`](crate::TabViewer::tab_bar_trailing_ui) too.
+    ///
+    /// Also turned on for the dock area when the `Ui` it's shown in prefers right-to-left layouts, see
+    /// [`Layout::prefer_right_to_left`](egui::Layout::prefer_right_to_left).
+    pub rtl: bool,
+    /// Shows a "+" button after the tabs of every tab bar, calling [`TabViewer::on_add`](crate::TabViewer::on_add)
+    /// when it's clicked, or opening the popup of [`TabViewer::add_popup`](crate::TabViewer::add_popup).
+    pub show_add_buttons: bool,
+    /// Double-clicking a tab maximizes its leaf to fill the whole dock area, and double-clicking it again restores
+    /// the layout, see [`Tree::enter_zen_mode`](crate::Tree::enter_zen_mode).
+    pub maximize_on_double_click: bool,
+
+    /// Middle-clicking a tab closes it, like its close button does.
+    pub close_on_middle_click: bool,
+
+    /// Shows a tooltip filled by [`TabViewer::tab_tooltip_ui`](crate::TabViewer::tab_tooltip_ui) once the pointer
+    /// rests on a tab title for [`Style::tab_tooltip_delay`].
+    pub show_tab_tooltips: bool,
+    /// Seconds the pointer has to rest on a tab title before its tooltip shows up.
+    pub tab_tooltip_delay: f32,
+
+    /// How far, in points, a tab has to be dragged sideways and up or down before it lifts off its tab bar and can
+    /// be dropped elsewhere. Pressing escape while dragging a tab puts it back.
+    pub tab_drag_threshold: Vec2,
+
+    /// Opacity of the title of a dragged tab following the pointer, and of the picture of its content shown under it
+    /// when there's one, see [`Style::capture_tab_previews`]. From `0.0` to `1.0`.
+    pub drag_preview_opacity: f32,
+
+    /// Width of the picture of the content of a dragged tab. Its height follows the shape of the leaf the tab was
+    /// dragged from.
+    pub drag_preview_width: f32,
+
+    /// Captures a picture of the content of active tabs about once a second, shown while they're dragged and when the
+    /// pointer rests on their title while they're inactive, unless
+    /// [`TabViewer::preview_texture`](crate::TabViewer::preview_texture) gives one.
+    ///
+    /// The tab's `ui` is run once more in an offscreen `Context` for each capture, and painted in software. Images
+    /// from textures loaded into the dock's own `Context` show up grey.
+    pub capture_tab_previews: bool,
+
+    /// Hides the tab bars until the pointer gets to their edge of the leaf, where they slide into view.
+    /// The bodies of the tabs take the space of hidden tab bars, like in fullscreen or presentation layouts.
+    pub auto_hide_tab_bars: bool,
+
+    /// Which edge of their leaf tab bars are shown along. Leaves can override it with
+    /// [`Tree::set_tab_bar_position`](crate::Tree::set_tab_bar_position).
+    pub tab_bar_position: TabBarPosition,
+    /// Width of the tab bars along the left or right edge of their leaf, whose tabs are stacked top to bottom.
+    pub vertical_tab_bar_width: f32,
+
+    /// Hides the tab bars of leaves with a single tab, leaving a thin handle which reveals the tab bar when the
+    /// pointer gets to it, so the tab can still be dragged. Leaves can override it with
+    /// [`Tree::set_hide_single_tab_bar`](crate::Tree::set_hide_single_tab_bar).
+    pub hide_single_tab_bars: bool,
+
+    /// Stroke of the outline drawn around the focused leaf.
+    pub focus_outline_stroke: Stroke,
+    pub show_focus_outline: bool,
+
+    /// Accessibility mode: thicker separators, taller tabs, larger close buttons, high-contrast tab text
+    /// and an always visible, stronger focus outline, all at once.
+    pub accessibility_mode: bool,
+
+    /// Disables every dock animation, for users who prefer reduced motion.
+    pub reduced_motion: bool,
+
+    /// Seconds it takes a separator to move to a fraction changed from code, like by applying a layout preset.
+    /// Separators moved from code snap to their new position when it's `0.0`.
+    pub separator_animation_time: f32,
+
+    /// Seconds it takes a tab to fade into its active look when it's activated, and out of it when another one is.
+    /// Hovered tabs fade half way in. Tabs switch their look at once when it's `0.0`.
+    pub tab_highlight_animation_time: f32,
+
+    /// Seconds it takes a leaf to shrink to its tab bar when it's collapsed, and to grow back when it's expanded,
+    /// see [`Tree::set_collapsed`](crate::Tree::set_collapsed). Leaves snap to their new size when it's `0.0`.
+    pub collapse_animation_time: f32,
 }
 
 impl Default for Style {
@@ -41,14 +232,35 @@ impl Default for Style {
             border_width: Default::default(),
 
             selection_color: Color32::from_rgb(0, 191, 255).linear_multiply(0.5),
+            show_drop_targets: false,
+            drop_target_size: 32.0,
+            drop_target_color: Color32::from_rgb(0, 191, 255),
+            drop_target_hover_color: Color32::from_rgb(128, 223, 255),
+            show_edge_drop_zones: false,
+            edge_drop_zone_width: 12.0,
             separator_width: 1.0,
             separator_extra: 175.0,
+            separator_hit_extension: 0.0,
+            separator_default_fraction: Some(0.5),
+            separator_snap_fractions: Vec::new(),
+            separator_snap_distance: 8.0,
+            min_node_size: Vec2::ZERO,
             separator_color: Color32::BLACK,
 
             tab_bar_background_color: Color32::WHITE,
 
             tab_outline_color: Color32::BLACK,
             tab_rounding: Default::default(),
+            tab_icon_size: 16.0,
+            tab_icon_spacing: 4.0,
+            tab_max_width: None,
+            tab_overflow: TabOverflow::Scroll,
+            tab_bar_height: 24.0,
+            tab_group_colors: HashMap::new(),
+            tab_group_default_color: Color32::from_rgb(0, 191, 255),
+            collapsible_tab_groups: false,
+            pinnable_tabs: false,
+            compact_pinned_tabs: false,
             tab_background_color: Color32::WHITE,
 
             tab_text_color_unfocused: Color32::DARK_GRAY,
@@ -57,7 +269,39 @@ impl Default for Style {
             close_tab_color: Color32::WHITE,
             close_tab_active_color: Color32::WHITE,
             close_tab_background_color: Color32::GRAY,
+            modified_marker_color: Color32::DARK_GRAY,
+            modified_marker_active_color: Color32::BLACK,
+            tab_badge_color: Color32::from_rgb(0, 191, 255),
+            tab_badge_text_color: Color32::WHITE,
+            tab_badge_max_width: 40.0,
             show_close_buttons: true,
+            close_buttons_on_left: false,
+            rtl: false,
+            show_add_buttons: false,
+            maximize_on_double_click: true,
+            close_on_middle_click: true,
+            show_tab_tooltips: false,
+            tab_tooltip_delay: 0.5,
+            tab_drag_threshold: vec2(30.0, 6.0),
+            drag_preview_opacity: 0.75,
+            drag_preview_width: 240.0,
+            capture_tab_previews: false,
+
+            auto_hide_tab_bars: false,
+            tab_bar_position: TabBarPosition::Top,
+            vertical_tab_bar_width: 150.0,
+            hide_single_tab_bars: false,
+
+            focus_outline_stroke: Stroke::new(1.0, Color32::from_rgb(0, 191, 255)),
+            show_focus_outline: false,
+
+            accessibility_mode: false,
+
+            reduced_motion: false,
+
+            separator_animation_time: 0.0,
+            tab_highlight_animation_time: 0.0,
+            collapse_animation_time: 0.0,
         }
     }
 }
@@ -67,17 +311,27 @@ impl Style {
     ///
     /// Fields overwritten by [`egui::Style`] are:
     /// - `selection_color`
+    /// - `drop_target_color`
+    /// - `drop_target_hover_color`
     /// - `tab_bar_background_color`
     /// - `tab_outline_color`
     /// - `tab_background_color`
     /// - `separator_color`
     /// - `border_color`
     /// - `close_tab_background_color`
+    /// - `modified_marker_color`
+    /// - `modified_marker_active_color`
+    /// - `tab_badge_color`
+    /// - `tab_badge_text_color`
     /// - `close_tab_color`
     /// - `close_tab_active_color`
+    /// - `focus_outline_stroke`
+    /// - `reduced_motion` (when `animation_time` is `0.0`)
     pub fn from_egui(style: &egui::Style) -> Self {
         Self {
             selection_color: style.visuals.selection.bg_fill.linear_multiply(0.5),
+            drop_target_color: style.visuals.selection.bg_fill,
+            drop_target_hover_color: style.visuals.selection.stroke.color,
 
             tab_bar_background_color: style.visuals.faint_bg_color,
             tab_outline_color: style.visuals.widgets.active.bg_fill,
@@ -90,201 +344,559 @@ impl Style {
             border_color: style.visuals.widgets.active.bg_fill,
 
             close_tab_background_color: style.visuals.widgets.active.bg_fill,
+            modified_marker_color: style.visuals.text_color(),
+            modified_marker_active_color: style.visuals.strong_text_color(),
+            tab_badge_color: style.visuals.selection.bg_fill,
+            tab_badge_text_color: style.visuals.selection.stroke.color,
             close_tab_color: style.visuals.text_color(),
             close_tab_active_color: style.visuals.strong_text_color(),
+
+            focus_outline_stroke: style.visuals.selection.stroke,
+
+            reduced_motion: style.animation_time <= 0.0,
             ..Self::default()
         }
     }
 
-    pub(crate) fn hsplit(&self, ui: &mut Ui, fraction: &mut f32, rect: Rect) -> (Rect, Rect, Rect) {
+    /// A style matching egui's default dark theme, [`Visuals::dark`].
+    pub fn dark() -> Self {
+        Self::from_egui(&egui::Style {
+            visuals: Visuals::dark(),
+            ..Default::default()
+        })
+    }
+
+    /// A style matching egui's default light theme, [`Visuals::light`].
+    pub fn light() -> Self {
+        Self::from_egui(&egui::Style {
+            visuals: Visuals::light(),
+            ..Default::default()
+        })
+    }
+
+    /// Blends between the styles `a` and `b`, like when animating a switch between the [`Style::dark`] and
+    /// [`Style::light`] presets. `t` goes from `0.0` for `a` to `1.0` for `b`.
+    ///
+    /// Colors, sizes and durations are interpolated; flags, positions and other settings which can't be blended
+    /// switch over from `a` to `b` half way.
+    pub fn lerp(a: &Self, b: &Self, t: f32) -> Self {
+        let t = t.clamp(0.0, 1.0);
+        let f = |a: f32, b: f32| emath::lerp(a..=b, t);
+        let c = |a: Color32, b: Color32| lerp_color(a, b, t);
+        let v = |a: Vec2, b: Vec2| a + (b - a) * t;
+        let mut style = if t < 0.5 { a.clone() } else { b.clone() };
+
+        style.padding = match (a.padding, b.padding) {
+            (Some(a), Some(b)) => Some(Margin {
+                left: f(a.left, b.left),
+                right: f(a.right, b.right),
+                top: f(a.top, b.top),
+                bottom: f(a.bottom, b.bottom),
+            }),
+            _ => style.padding,
+        };
+        style.border_color = c(a.border_color, b.border_color);
+        style.border_width = f(a.border_width, b.border_width);
+        style.selection_color = c(a.selection_color, b.selection_color);
+        style.drop_target_size = f(a.drop_target_size, b.drop_target_size);
+        style.drop_target_color = c(a.drop_target_color, b.drop_target_color);
+        style.drop_target_hover_color = c(a.drop_target_hover_color, b.drop_target_hover_color);
+        style.edge_drop_zone_width = f(a.edge_drop_zone_width, b.edge_drop_zone_width);
+        style.separator_width = f(a.separator_width, b.separator_width);
+        style.separator_extra = f(a.separator_extra, b.separator_extra);
+        style.separator_hit_extension = f(a.separator_hit_extension, b.separator_hit_extension);
+        style.separator_snap_distance = f(a.separator_snap_distance, b.separator_snap_distance);
+        style.min_node_size = v(a.min_node_size, b.min_node_size);
+        style.separator_color = c(a.separator_color, b.separator_color);
+        style.tab_bar_background_color = c(a.tab_bar_background_color, b.tab_bar_background_color);
+        style.tab_outline_color = c(a.tab_outline_color, b.tab_outline_color);
+        style.tab_rounding = Rounding {
+            nw: f(a.tab_rounding.nw, b.tab_rounding.nw),
+            ne: f(a.tab_rounding.ne, b.tab_rounding.ne),
+            sw: f(a.tab_rounding.sw, b.tab_rounding.sw),
+            se: f(a.tab_rounding.se, b.tab_rounding.se),
+        };
+        style.tab_background_color = c(a.tab_background_color, b.tab_background_color);
+        style.tab_icon_size = f(a.tab_icon_size, b.tab_icon_size);
+        style.tab_icon_spacing = f(a.tab_icon_spacing, b.tab_icon_spacing);
+        style.tab_max_width = match (a.tab_max_width, b.tab_max_width) {
+            (Some(a), Some(b)) => Some(f(a, b)),
+            _ => style.tab_max_width,
+        };
+        style.tab_bar_height = f(a.tab_bar_height, b.tab_bar_height);
+        style.tab_group_default_color = c(a.tab_group_default_color, b.tab_group_default_color);
+        style.tab_text_color_unfocused = c(a.tab_text_color_unfocused, b.tab_text_color_unfocused);
+        style.tab_text_color_focused = c(a.tab_text_color_focused, b.tab_text_color_focused);
+        style.close_tab_color = c(a.close_tab_color, b.close_tab_color);
+        style.close_tab_active_color = c(a.close_tab_active_color, b.close_tab_active_color);
+        style.close_tab_background_color =
+            c(a.close_tab_background_color, b.close_tab_background_color);
+        style.modified_marker_color = c(a.modified_marker_color, b.modified_marker_color);
+        style.modified_marker_active_color = c(
+            a.modified_marker_active_color,
+            b.modified_marker_active_color,
+        );
+        style.tab_badge_color = c(a.tab_badge_color, b.tab_badge_color);
+        style.tab_badge_text_color = c(a.tab_badge_text_color, b.tab_badge_text_color);
+        style.tab_badge_max_width = f(a.tab_badge_max_width, b.tab_badge_max_width);
+        style.tab_tooltip_delay = f(a.tab_tooltip_delay, b.tab_tooltip_delay);
+        style.tab_drag_threshold = v(a.tab_drag_threshold, b.tab_drag_threshold);
+        style.drag_preview_opacity = f(a.drag_preview_opacity, b.drag_preview_opacity);
+        style.drag_preview_width = f(a.drag_preview_width, b.drag_preview_width);
+        style.vertical_tab_bar_width = f(a.vertical_tab_bar_width, b.vertical_tab_bar_width);
+        style.focus_outline_stroke = Stroke::new(
+            f(a.focus_outline_stroke.width, b.focus_outline_stroke.width),
+            c(a.focus_outline_stroke.color, b.focus_outline_stroke.color),
+        );
+        style.separator_animation_time = f(a.separator_animation_time, b.separator_animation_time);
+        style.tab_highlight_animation_time = f(
+            a.tab_highlight_animation_time,
+            b.tab_highlight_animation_time,
+        );
+        style.collapse_animation_time = f(a.collapse_animation_time, b.collapse_animation_time);
+        style
+    }
+
+    /// Works out the values derived from the style for the current frame.
+    pub(crate) fn metrics(&self, ui: &Ui) -> Metrics {
         let pixels_per_point = ui.ctx().pixels_per_point();
+        Metrics {
+            pixels_per_point,
+            px: pixels_per_point.recip(),
+            tab_bar_height: self.tab_bar_height(),
+            separator_width: self.effective_separator_width(),
+            focus_outline_stroke: self.effective_focus_outline_stroke(),
+            animation_time: self.animation_time(ui),
+            separator_animation_time: if self.reduced_motion {
+                0.0
+            } else {
+                self.separator_animation_time
+            },
+            tab_highlight_animation_time: if self.reduced_motion {
+                0.0
+            } else {
+                self.tab_highlight_animation_time
+            },
+            collapse_animation_time: if self.reduced_motion {
+                0.0
+            } else {
+                self.collapse_animation_time
+            },
+        }
+    }
 
-        let mut separator = rect;
+    /// Duration of dock animations, which is `0.0` when [`Style::reduced_motion`] is enabled.
+    ///
+    /// Every animation in the dock should go through this.
+    pub(crate) fn animation_time(&self, ui: &Ui) -> f32 {
+        if self.reduced_motion {
+            0.0
+        } else {
+            ui.style().animation_time
+        }
+    }
 
-        let midpoint = rect.min.x + rect.width() * *fraction;
-        separator.min.x = midpoint - self.separator_width * 0.5;
-        separator.max.x = midpoint + self.separator_width * 0.5;
+    /// The area the nodes are laid out in when the dock is shown inside `rect`, which is `rect` without
+    /// [`Style::padding`].
+    pub(crate) fn inner_rect(&self, mut rect: Rect) -> Rect {
+        if let Some(margin) = self.padding {
+            rect.min += margin.left_top();
+            rect.max -= margin.right_bottom();
+        }
+        rect
+    }
 
-        let response = ui
-            .allocate_rect(separator, Sense::click_and_drag())
-            .on_hover_cursor(CursorIcon::ResizeHorizontal);
-
-        {
-            let delta = response.drag_delta().x;
-            let range = rect.max.x - rect.min.x;
-            let min = (self.separator_extra / range).min(1.0);
-            let max = 1.0 - min;
-            let (min, max) = (min.min(max), max.max(min));
-            *fraction = (*fraction + delta / range).clamp(min, max);
+    /// A copy of this style with the colors of tab titles faded to `opacity`.
+    pub(crate) fn faded(&self, opacity: f32) -> Self {
+        let opacity = opacity.clamp(0.0, 1.0);
+        let mut faded = self.clone();
+        for color in [
+            &mut faded.tab_outline_color,
+            &mut faded.tab_background_color,
+            &mut faded.tab_text_color_unfocused,
+            &mut faded.tab_text_color_focused,
+            &mut faded.close_tab_color,
+            &mut faded.close_tab_active_color,
+            &mut faded.close_tab_background_color,
+            &mut faded.modified_marker_color,
+            &mut faded.modified_marker_active_color,
+            &mut faded.tab_badge_color,
+            &mut faded.tab_badge_text_color,
+        ] {
+            *color = color.linear_multiply(opacity);
         }
+        faded
+    }
 
-        let midpoint = rect.min.x + rect.width() * *fraction;
-        separator.min.x = map_to_pixel(
-            midpoint - self.separator_width * 0.5,
-            pixels_per_point,
-            f32::round,
-        );
-        separator.max.x = map_to_pixel(
-            midpoint + self.separator_width * 0.5,
-            pixels_per_point,
-            f32::round,
-        );
+    /// The layout of a row of the tab bar, from right to left with [`Style::rtl`].
+    pub(crate) fn row_layout(&self) -> Layout {
+        if self.rtl {
+            Layout::right_to_left(Align::Center)
+        } else {
+            Layout::left_to_right(Align::Center)
+        }
+    }
 
-        (
-            rect.intersect(Rect::everything_right_of(separator.max.x)),
-            separator,
-            rect.intersect(Rect::everything_left_of(separator.min.x)),
-        )
+    /// Color of the stripes marking the tabs of the group named `group`.
+    pub(crate) fn tab_group_color(&self, group: &str) -> Color32 {
+        self.tab_group_colors
+            .get(group)
+            .copied()
+            .unwrap_or(self.tab_group_default_color)
     }
 
-    pub(crate) fn vsplit(&self, ui: &mut Ui, fraction: &mut f32, rect: Rect) -> (Rect, Rect, Rect) {
-        let pixels_per_point = ui.ctx().pixels_per_point();
+    /// Whether close buttons go on the left of tab titles, which [`Style::rtl`] swaps.
+    pub(crate) fn close_button_on_left(&self) -> bool {
+        self.close_buttons_on_left != self.rtl
+    }
+
+    /// Whether close buttons are shown, which is never the case without the `close_buttons` feature.
+    pub(crate) fn shows_close_buttons(&self) -> bool {
+        cfg!(feature = "close_buttons") && self.show_close_buttons
+    }
+
+    /// Height of the tab bar, taking [`Style::accessibility_mode`] into account.
+    pub(crate) fn tab_bar_height(&self) -> f32 {
+        if self.accessibility_mode {
+            self.tab_bar_height.max(32.0)
+        } else {
+            self.tab_bar_height
+        }
+    }
 
-        let mut separator = rect;
+    /// How far the tab bar reaches into its leaf from the edge at `position`, its height or, for vertical tab bars,
+    /// its width.
+    pub(crate) fn tab_bar_thickness(&self, position: TabBarPosition) -> f32 {
+        if position.is_vertical() {
+            self.vertical_tab_bar_width
+        } else {
+            self.tab_bar_height()
+        }
+    }
+
+    /// Width of the separators, taking [`Style::accessibility_mode`] into account.
+    pub(crate) fn effective_separator_width(&self) -> f32 {
+        if self.accessibility_mode {
+            self.separator_width.max(4.0)
+        } else {
+            self.separator_width
+        }
+    }
+
+    /// Stroke of the focus outlines, taking [`Style::accessibility_mode`] into account.
+    pub(crate) fn effective_focus_outline_stroke(&self) -> Stroke {
+        if self.accessibility_mode {
+            let color = self.focus_outline_stroke.color;
+            Stroke::new(
+                self.focus_outline_stroke.width.max(2.5),
+                Color32::from_rgb(color.r(), color.g(), color.b()),
+            )
+        } else {
+            self.focus_outline_stroke
+        }
+    }
 
-        let midpoint = rect.min.y + rect.height() * *fraction;
-        separator.min.y = midpoint - self.separator_width * 0.5;
-        separator.max.y = midpoint + self.separator_width * 0.5;
+    /// Lets the user drag the `separator` of a node occupying `rect`, updating its `fraction`.
+    ///
+    /// Returns `true` if the separator was moved, `false` otherwise.
+    pub(crate) fn drag_separator(
+        &self,
+        ui: &mut Ui,
+        id: Id,
+        fraction: &mut f32,
+        rect: Rect,
+        separator: Rect,
+        is_horizontal: bool,
+    ) -> bool {
+        let (cursor, delta, range) = if is_horizontal {
+            (CursorIcon::ResizeHorizontal, Vec2::X, rect.width())
+        } else {
+            (CursorIcon::ResizeVertical, Vec2::Y, rect.height())
+        };
 
         let response = ui
-            .allocate_rect(separator, Sense::click_and_drag())
-            .on_hover_cursor(CursorIcon::ResizeVertical);
-
-        {
-            let delta = response.drag_delta().y;
-            let range = rect.max.y - rect.min.y;
-            let min = (self.separator_extra / range).min(1.0);
-            let max = 1.0 - min;
-            let (min, max) = (min.min(max), max.max(min));
-            *fraction = (*fraction + delta / range).clamp(min, max);
+            .interact(
+                self.separator_hit_rect(separator, is_horizontal),
+                id,
+                Sense::click_and_drag(),
+            )
+            .on_hover_cursor(cursor);
+
+        let min = (self.separator_extra / range).min(1.0);
+        let max = 1.0 - min;
+        let (min, max) = (min.min(max), max.max(min));
+
+        if response.double_clicked() {
+            if let Some(default) = self.separator_default_fraction {
+                *fraction = default.clamp(min, max);
+                return true;
+            }
         }
 
-        let midpoint = rect.min.y + rect.height() * *fraction;
-        separator.min.y = map_to_pixel(
-            midpoint - self.separator_width * 0.5,
-            pixels_per_point,
-            f32::round,
-        );
-        separator.max.y = map_to_pixel(
-            midpoint + self.separator_width * 0.5,
-            pixels_per_point,
-            f32::round,
-        );
+        // Snapping follows where the separator would be without it, so dragging further lets go of a snap point.
+        let unsnapped_id = id.with("unsnapped");
+        if response.drag_started() {
+            ui.data().remove::<f32>(unsnapped_id);
+        }
+
+        let delta = response.drag_delta().dot(delta);
+        if delta == 0.0 {
+            return false;
+        }
 
-        (
-            rect.intersect(Rect::everything_above(separator.min.y)),
-            separator,
-            rect.intersect(Rect::everything_below(separator.max.y)),
-        )
+        if self.separator_snap_fractions.is_empty() {
+            *fraction = (*fraction + delta / range).clamp(min, max);
+            return true;
+        }
+        let unsnapped = ui.data().get_temp::<f32>(unsnapped_id).unwrap_or(*fraction);
+        let unsnapped = (unsnapped + delta / range).clamp(min, max);
+        ui.data().insert_temp(unsnapped_id, unsnapped);
+        let snap = self
+            .separator_snap_fractions
+            .iter()
+            .copied()
+            .filter(|snap| (snap - unsnapped).abs() * range <= self.separator_snap_distance)
+            .min_by(|a, b| (a - unsnapped).abs().total_cmp(&(b - unsnapped).abs()));
+        *fraction = snap.unwrap_or(unsnapped).clamp(min, max);
+        true
     }
 
-    /// `active` means "the tab that is opened in the parent panel".
+    /// The area the `separator` can be grabbed in, see [`Style::separator_hit_extension`].
+    pub(crate) fn separator_hit_rect(&self, separator: Rect, is_horizontal: bool) -> Rect {
+        if is_horizontal {
+            separator.expand2(vec2(self.separator_hit_extension, 0.0))
+        } else {
+            separator.expand2(vec2(0.0, self.separator_hit_extension))
+        }
+    }
+
+    /// `active` means "the tab that is opened in the parent panel", `closeable` whether the tab has a close button
+    /// when they're shown, and `modified` whether it shows a dot in place of the close button until it's hovered.
+    /// `max_width` is the widest the tab can get, [`Style::tab_max_width`] unless the tab bar has less room.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn tab_title(
         &self,
         ui: &mut Ui,
-        label: WidgetText,
+        metrics: &Metrics,
+        max_width: Option<f32>,
+        galley: WidgetTextGalley,
+        title_ui: &mut dyn FnMut(&mut Ui) -> bool,
+        icon: Option<TabIcon>,
+        badge: Option<WidgetTextGalley>,
         focused: bool,
         active: bool,
         is_being_dragged: bool,
+        closeable: bool,
+        modified: bool,
         id: Id,
+        dock_painter: &dyn DockPainter,
     ) -> (Response, bool, bool) {
-        let px = ui.ctx().pixels_per_point().recip();
-        let rounding = self.tab_rounding;
-
-        let galley = label.into_galley(ui, None, f32::INFINITY, TextStyle::Button);
+        let px = metrics.px;
+        let shows_close_button = self.shows_close_buttons() && closeable;
+        // The close button and the modified marker take turns in the same spot.
+        let has_button_slot = shows_close_button || modified;
 
         let x_text_gap = 5.0;
-        let x_size = Vec2::new(galley.size().y / 1.3, galley.size().y / 1.3);
+        let x_size = if self.accessibility_mode {
+            Vec2::splat(galley.size().y)
+        } else {
+            Vec2::splat(galley.size().y / 1.3)
+        };
 
         let offset = vec2(8.0, 0.0);
-        let text_size = galley.size();
+        // Titles shown by the tab viewer are sized after their width on the last frame.
+        let width_id = id.with("title_width");
+        let custom_width = ui.data().get_temp::<f32>(width_id);
+        let mut text_size = vec2(custom_width.unwrap_or(galley.size().x), galley.size().y);
 
-        let mut desired_size = text_size + offset * 2.0;
-        if self.show_close_buttons {
-            desired_size.x += x_size.x + x_text_gap;
+        let icon_width = if icon.is_some() {
+            self.tab_icon_size + self.tab_icon_spacing
+        } else {
+            0.0
+        };
+
+        // Badges are pills at least as wide as they're high, with half their height of room around the text.
+        let badge_size = badge.as_ref().map(|badge| {
+            let height = badge.size().y;
+            let width = (badge.size().x + height).min(self.tab_badge_max_width);
+            vec2(width.max(height), height)
+        });
+        let badge_width = badge_size.map_or(0.0, |size| size.x + x_text_gap);
+
+        let mut chrome_width = offset.x * 2.0 + icon_width + badge_width;
+        if has_button_slot {
+            chrome_width += x_size.x + x_text_gap;
         }
-        desired_size.y = 24.0;
 
-        let (rect, response) = ui.allocate_at_least(desired_size, Sense::hover());
+        // Titles too long for `max_width` are cut short, leaving room for an ellipsis.
+        let mut ellipsis = None;
+        if let Some(max_width) = max_width {
+            let max_text_width = (max_width - chrome_width).max(0.0);
+            if text_size.x > max_text_width {
+                text_size.x = max_text_width;
+                if custom_width.is_none() {
+                    ellipsis = Some(title_galley(ui, "…".into(), TextStyle::Button));
+                }
+            }
+        }
+
+        let desired_size = vec2(text_size.x + chrome_width, metrics.tab_bar_height);
+
+        let (rect, mut response) = ui.allocate_at_least(desired_size, Sense::hover());
+        if ellipsis.is_some() {
+            response = response.on_hover_text(galley.galley.text());
+        }
         let response = response.on_hover_cursor(CursorIcon::PointingHand);
 
-        let (x_rect, x_res) = if (active || response.hovered()) && self.show_close_buttons {
+        // Tabs scrolled out of the tab bar don't need any shapes, egui would only clip them away.
+        if !ui.is_rect_visible(rect) {
+            return (response, false, false);
+        }
+
+        // The text is moved right to make room for close buttons on its left.
+        let mut text_x = rect.min.x + offset.x;
+        let close_on_left = self.close_button_on_left();
+        if has_button_slot && close_on_left {
+            text_x += x_size.x + x_text_gap;
+        }
+
+        let x_rect = {
             let mut pos = rect.left_top();
-            pos.x += offset.x + text_size.x + x_text_gap + x_size.x / 2.0;
+            pos.x += if close_on_left {
+                offset.x + x_size.x / 2.0
+            } else {
+                offset.x + icon_width + text_size.x + badge_width + x_text_gap + x_size.x / 2.0
+            };
             pos.y += rect.size().y / 2.0;
-            let x_rect = Rect::from_center_size(pos, x_size);
-            (x_rect, Some(ui.interact(x_rect, id, Sense::click())))
+            Rect::from_center_size(pos, x_size)
+        };
+        // Modified tabs only show their close button while they're hovered.
+        let shows_x = if modified {
+            response.hovered()
         } else {
-            (Rect::NOTHING, None)
+            active || response.hovered()
         };
-        match (active, is_being_dragged) {
-            (true, false) => {
-                let mut tab = rect;
-                tab.min.x -= px;
-                tab.max.x += px;
-                ui.painter()
-                    .rect_filled(tab, rounding, self.tab_outline_color);
-
-                tab.min.x += px;
-                tab.max.x -= px;
-                tab.min.y += px;
-                ui.painter()
-                    .rect_filled(tab, rounding, self.tab_background_color);
-            }
-            (true, true) => {
-                let tab = rect;
-
-                ui.painter().rect_stroke(
-                    tab,
-                    self.tab_rounding,
-                    Stroke::new(1.0, self.tab_outline_color),
-                );
-            }
-            _ => (),
+        let x_res = (shows_x && shows_close_button)
+            .then(|| ui.interact(x_rect, id.with("close"), Sense::click()));
+        if let Some(x_res) = &x_res {
+            x_res.widget_info(|| {
+                WidgetInfo::labeled(
+                    WidgetType::Button,
+                    format!("Close {}", galley.galley.text()),
+                )
+            });
         }
 
-        let pos = Align2::LEFT_TOP
-            .anchor_rect(rect.shrink2(vec2(8.0, 5.0)))
-            .min;
-
-        let override_text_color = if galley.galley_has_color {
-            None // respect the color the user has chosen
-        } else if focused {
-            Some(self.tab_text_color_focused)
+        let state = TabState {
+            active,
+            focused,
+            dragged: is_being_dragged,
+            hovered: response.hovered(),
+        };
+        // Tabs fade in and out of their active look, and half way into it while hovered.
+        let highlight = if metrics.tab_highlight_animation_time > 0.0 && !is_being_dragged {
+            let target = if active {
+                1.0
+            } else if state.hovered {
+                0.5
+            } else {
+                0.0
+            };
+            ui.ctx().animate_value_with_time(
+                id.with("highlight"),
+                target,
+                metrics.tab_highlight_animation_time,
+            )
+        } else if active {
+            1.0
         } else {
-            Some(self.tab_text_color_unfocused)
+            0.0
         };
-        ui.painter().add(epaint::TextShape {
-            pos,
-            galley: galley.galley,
-            underline: Stroke::none(),
-            override_text_color,
-            angle: 0.0,
-        });
+        if highlight > 0.0 && highlight < 1.0 {
+            let mut faded = self.clone();
+            faded.tab_outline_color = self.tab_outline_color.linear_multiply(highlight);
+            faded.tab_background_color = self.tab_background_color.linear_multiply(highlight);
+            let faded_state = TabState {
+                active: true,
+                ..state
+            };
+            dock_painter.tab(ui.painter(), &faded, rect, faded_state, px);
+        } else {
+            dock_painter.tab(ui.painter(), self, rect, state, px);
+        }
 
-        if (active || response.hovered()) && self.show_close_buttons {
-            if x_res.as_ref().unwrap().hovered() {
-                ui.painter().rect_filled(
-                    x_rect,
-                    Rounding::same(2.0),
-                    self.close_tab_background_color,
-                );
-            }
-            let x_rect = x_rect.shrink(1.75);
+        // Badges go after the title, which is before it from right to left.
+        if let (Some(badge), Some(size)) = (badge, badge_size) {
+            let badge_x = if self.rtl {
+                text_x += badge_width;
+                text_x - badge_width
+            } else {
+                text_x + icon_width + text_size.x + x_text_gap
+            };
+            let badge_rect =
+                Rect::from_min_size(pos2(badge_x, rect.center().y - size.y * 0.5), size);
+            dock_painter.tab_badge(ui.painter(), self, badge_rect, badge, state);
+        }
 
-            let color = if focused || x_res.as_ref().unwrap().interact_pointer_pos().is_some() {
-                self.close_tab_active_color
+        if let Some(icon) = &icon {
+            // Icons go before the title, which is after it from right to left.
+            let icon_x = if self.rtl {
+                text_x + text_size.x + self.tab_icon_spacing
             } else {
-                self.close_tab_color
+                text_x
             };
-            ui.painter().line_segment(
-                [x_rect.left_top(), x_rect.right_bottom()],
-                Stroke::new(1.0, color),
+            let icon_rect = Rect::from_min_size(
+                pos2(icon_x, rect.center().y - self.tab_icon_size * 0.5),
+                Vec2::splat(self.tab_icon_size),
             );
-            ui.painter().line_segment(
-                [x_rect.right_top(), x_rect.left_bottom()],
-                Stroke::new(1.0, color),
+            dock_painter.tab_icon(ui.painter(), self, icon_rect, icon, state);
+            if !self.rtl {
+                text_x += icon_width;
+            }
+        }
+
+        let title_rect =
+            Rect::from_min_size(pos2(text_x, rect.min.y), vec2(text_size.x, rect.height()));
+        let mut child = ui.child_ui(title_rect, self.row_layout());
+        child.set_clip_rect(title_rect.intersect(ui.clip_rect()));
+        if title_ui(&mut child) {
+            let width = child.min_rect().width();
+            if custom_width != Some(width) {
+                ui.data().insert_temp(width_id, width);
+                ui.ctx().request_repaint();
+            }
+        } else {
+            if custom_width.is_some() {
+                ui.data().remove::<f32>(width_id);
+                ui.ctx().request_repaint();
+            }
+            let pos = pos2(text_x, rect.center().y - text_size.y * 0.5);
+            match ellipsis {
+                Some(ellipsis) => {
+                    let ellipsis_width = ellipsis.size().x.min(text_size.x);
+                    let mut clip_rect = title_rect;
+                    clip_rect.max.x -= ellipsis_width;
+                    let painter = ui
+                        .painter()
+                        .with_clip_rect(clip_rect.intersect(ui.clip_rect()));
+                    dock_painter.tab_title(&painter, self, pos, galley, state);
+                    let pos = pos2(clip_rect.max.x, pos.y);
+                    dock_painter.tab_title(ui.painter(), self, pos, ellipsis, state);
+                }
+                None => dock_painter.tab_title(ui.painter(), self, pos, galley, state),
+            }
+        }
+
+        if let Some(x_res) = &x_res {
+            dock_painter.close_button(
+                ui.painter(),
+                self,
+                x_rect,
+                x_res.hovered() || x_res.has_focus(),
+                focused || x_res.interact_pointer_pos().is_some(),
             );
+        } else if modified {
+            dock_painter.modified_marker(ui.painter(), self, x_rect, focused);
         }
 
         match x_res {
@@ -294,6 +906,11 @@ impl Style {
     }
 }
 
+/// Blends two colors in linear space, which keeps the colors in between from getting muddy.
+fn lerp_color(a: Color32, b: Color32, t: f32) -> Color32 {
+    emath::lerp(Rgba::from(a)..=Rgba::from(b), t).into()
+}
+
 #[derive(Default)]
 pub struct StyleBuilder {
     style: Style,
@@ -333,6 +950,51 @@ impl StyleBuilder {
         self
     }
 
+    /// Shows / Hides the targets to drop dragged tabs onto, see [`Style::show_drop_targets`].
+    /// By `Default` it's `false`.
+    #[inline(always)]
+    pub fn show_drop_targets(mut self, show_drop_targets: bool) -> Self {
+        self.style.show_drop_targets = show_drop_targets;
+        self
+    }
+
+    /// Sets `drop_target_size` for the width and height of each drop target. By `Default` it's `32.0`.
+    #[inline(always)]
+    pub fn with_drop_target_size(mut self, drop_target_size: f32) -> Self {
+        self.style.drop_target_size = drop_target_size;
+        self
+    }
+
+    /// Sets `drop_target_color` for the drop targets. By `Default` it's `(0, 191, 255)` (light blue).
+    #[inline(always)]
+    pub fn with_drop_target_color(mut self, drop_target_color: Color32) -> Self {
+        self.style.drop_target_color = drop_target_color;
+        self
+    }
+
+    /// Sets `drop_target_hover_color` for the drop target under the pointer. By `Default` it's `(128, 223, 255)`.
+    #[inline(always)]
+    pub fn with_drop_target_hover_color(mut self, drop_target_hover_color: Color32) -> Self {
+        self.style.drop_target_hover_color = drop_target_hover_color;
+        self
+    }
+
+    /// Shows / Hides the drop zones along the edges of the dock area, see [`Style::show_edge_drop_zones`].
+    /// By `Default` they're hidden.
+    #[inline(always)]
+    pub fn show_edge_drop_zones(mut self, show_edge_drop_zones: bool) -> Self {
+        self.style.show_edge_drop_zones = show_edge_drop_zones;
+        self
+    }
+
+    /// Sets `edge_drop_zone_width` for how close to the edges of the dock area tabs have to be dropped to be docked
+    /// along them. By `Default` it's `12.0`.
+    #[inline(always)]
+    pub fn with_edge_drop_zone_width(mut self, edge_drop_zone_width: f32) -> Self {
+        self.style.edge_drop_zone_width = edge_drop_zone_width;
+        self
+    }
+
     /// Sets `separator_size` for the rectangle separator between nodes. By `Default` it's `1.0`.
     #[inline(always)]
     pub fn with_separator_width(mut self, separator_width: f32) -> Self {
@@ -348,6 +1010,46 @@ impl StyleBuilder {
         self
     }
 
+    /// Sets `separator_hit_extension` for how far separators can be grabbed beside them. By `Default` it's `0.0`.
+    #[inline(always)]
+    pub fn with_separator_hit_extension(mut self, separator_hit_extension: f32) -> Self {
+        self.style.separator_hit_extension = separator_hit_extension;
+        self
+    }
+
+    /// Sets `separator_default_fraction` for where separators go back to when double-clicked. By `Default` it's
+    /// `Some(0.5)`.
+    #[inline(always)]
+    pub fn with_separator_default_fraction(
+        mut self,
+        separator_default_fraction: Option<f32>,
+    ) -> Self {
+        self.style.separator_default_fraction = separator_default_fraction;
+        self
+    }
+
+    /// Sets `separator_snap_fractions` for the fractions dragged separators snap to. By `Default` there are none.
+    #[inline(always)]
+    pub fn with_separator_snap_fractions(mut self, separator_snap_fractions: Vec<f32>) -> Self {
+        self.style.separator_snap_fractions = separator_snap_fractions;
+        self
+    }
+
+    /// Sets `separator_snap_distance` for how close dragged separators snap. By `Default` it's `8.0`.
+    #[inline(always)]
+    pub fn with_separator_snap_distance(mut self, separator_snap_distance: f32) -> Self {
+        self.style.separator_snap_distance = separator_snap_distance;
+        self
+    }
+
+    /// Sets `min_node_size`, the smallest size a leaf can be made, see [`Style::min_node_size`].
+    /// By `Default` it's `Vec2::ZERO`.
+    #[inline(always)]
+    pub fn with_min_node_size(mut self, min_node_size: Vec2) -> Self {
+        self.style.min_node_size = min_node_size;
+        self
+    }
+
     /// Sets `separator_color`for the rectangle separator. By `Default` it's [`Color32::BLACK`].
     #[inline(always)]
     pub fn with_separator_color(mut self, separator_color: Color32) -> Self {
@@ -376,6 +1078,81 @@ impl StyleBuilder {
         self
     }
 
+    /// Sets `tab_icon_size` for the width and height of tab icons. By `Default` it's `16.0`.
+    #[inline(always)]
+    pub fn with_tab_icon_size(mut self, tab_icon_size: f32) -> Self {
+        self.style.tab_icon_size = tab_icon_size;
+        self
+    }
+
+    /// Sets `tab_icon_spacing` for the space between tab icons and titles. By `Default` it's `4.0`.
+    #[inline(always)]
+    pub fn with_tab_icon_spacing(mut self, tab_icon_spacing: f32) -> Self {
+        self.style.tab_icon_spacing = tab_icon_spacing;
+        self
+    }
+
+    /// Sets `tab_max_width` for the widest a tab can get before its title is cut short. By `Default` it's `None`.
+    #[inline(always)]
+    pub fn with_tab_max_width(mut self, tab_max_width: Option<f32>) -> Self {
+        self.style.tab_max_width = tab_max_width;
+        self
+    }
+
+    /// Sets `tab_overflow` for what tab bars do with tabs which don't fit. By `Default` it's
+    /// [`TabOverflow::Scroll`].
+    #[inline(always)]
+    pub fn with_tab_overflow(mut self, tab_overflow: TabOverflow) -> Self {
+        self.style.tab_overflow = tab_overflow;
+        self
+    }
+
+    /// Sets `tab_bar_height` for the height of the tab bars. By `Default` it's `24.0`.
+    #[inline(always)]
+    pub fn with_tab_bar_height(mut self, tab_bar_height: f32) -> Self {
+        self.style.tab_bar_height = tab_bar_height;
+        self
+    }
+
+    /// Sets the color of the stripes marking the tabs of the group named `group`, see [`Style::tab_group_colors`].
+    #[inline(always)]
+    pub fn with_tab_group_color(mut self, group: impl Into<String>, color: Color32) -> Self {
+        self.style.tab_group_colors.insert(group.into(), color);
+        self
+    }
+
+    /// Sets `tab_group_default_color` for the stripes of groups without a color of their own. By `Default` it's
+    /// `Color32::from_rgb(0, 191, 255)`.
+    #[inline(always)]
+    pub fn with_tab_group_default_color(mut self, tab_group_default_color: Color32) -> Self {
+        self.style.tab_group_default_color = tab_group_default_color;
+        self
+    }
+
+    /// Shows / Hides the labels collapsing tab groups, see [`Style::collapsible_tab_groups`]. By `Default` they're
+    /// hidden.
+    #[inline(always)]
+    pub fn collapsible_tab_groups(mut self, collapsible_tab_groups: bool) -> Self {
+        self.style.collapsible_tab_groups = collapsible_tab_groups;
+        self
+    }
+
+    /// Shows / Hides the entry pinning tabs in the menu of tab titles, see [`Style::pinnable_tabs`]. By `Default`
+    /// it's hidden.
+    #[inline(always)]
+    pub fn pinnable_tabs(mut self, pinnable_tabs: bool) -> Self {
+        self.style.pinnable_tabs = pinnable_tabs;
+        self
+    }
+
+    /// Shows only the icon of pinned tabs, see [`Style::compact_pinned_tabs`]. By `Default` their title is shown
+    /// too.
+    #[inline(always)]
+    pub fn compact_pinned_tabs(mut self, compact_pinned_tabs: bool) -> Self {
+        self.style.compact_pinned_tabs = compact_pinned_tabs;
+        self
+    }
+
     /// Sets `tab_background_color` for the current tab background color.
     #[inline(always)]
     pub fn with_tab_background_color(mut self, tab_background: Color32) -> Self {
@@ -383,6 +1160,20 @@ impl StyleBuilder {
         self
     }
 
+    /// Sets `tab_text_color_unfocused` for the text color of tabs outside the focused leaf.
+    #[inline(always)]
+    pub fn with_tab_text_color_unfocused(mut self, tab_text_color_unfocused: Color32) -> Self {
+        self.style.tab_text_color_unfocused = tab_text_color_unfocused;
+        self
+    }
+
+    /// Sets `tab_text_color_focused` for the text color of tabs in the focused leaf.
+    #[inline(always)]
+    pub fn with_tab_text_color_focused(mut self, tab_text_color_focused: Color32) -> Self {
+        self.style.tab_text_color_focused = tab_text_color_focused;
+        self
+    }
+
     /// Sets `close_tab_color` for the close tab button color.
     #[inline(always)]
     pub fn with_close_tab_color(mut self, close_tab_color: Color32) -> Self {
@@ -407,6 +1198,44 @@ impl StyleBuilder {
         self
     }
 
+    /// Sets `modified_marker_color` for the dot marking tabs with unsaved changes.
+    #[inline(always)]
+    pub fn with_modified_marker_color(mut self, modified_marker_color: Color32) -> Self {
+        self.style.modified_marker_color = modified_marker_color;
+        self
+    }
+
+    /// Sets `modified_marker_active_color` for the dot marking tabs with unsaved changes in the focused leaf.
+    #[inline(always)]
+    pub fn with_modified_marker_active_color(
+        mut self,
+        modified_marker_active_color: Color32,
+    ) -> Self {
+        self.style.modified_marker_active_color = modified_marker_active_color;
+        self
+    }
+
+    /// Sets `tab_badge_color` for the background of tab badges.
+    #[inline(always)]
+    pub fn with_tab_badge_color(mut self, tab_badge_color: Color32) -> Self {
+        self.style.tab_badge_color = tab_badge_color;
+        self
+    }
+
+    /// Sets `tab_badge_text_color` for the text of tab badges.
+    #[inline(always)]
+    pub fn with_tab_badge_text_color(mut self, tab_badge_text_color: Color32) -> Self {
+        self.style.tab_badge_text_color = tab_badge_text_color;
+        self
+    }
+
+    /// Sets `tab_badge_max_width` for the widest a tab badge gets. By `Default` it's `40.0`.
+    #[inline(always)]
+    pub fn with_tab_badge_max_width(mut self, tab_badge_max_width: f32) -> Self {
+        self.style.tab_badge_max_width = tab_badge_max_width;
+        self
+    }
+
     /// Shows / Hides the tab close buttons.
     #[inline(always)]
     pub fn show_close_buttons(mut self, show_close_buttons: bool) -> Self {
@@ -414,6 +1243,173 @@ impl StyleBuilder {
         self
     }
 
+    /// Puts the tab close buttons on the left of the titles instead of on the right. By `Default` it's `false`.
+    #[inline(always)]
+    pub fn close_buttons_on_left(mut self, close_buttons_on_left: bool) -> Self {
+        self.style.close_buttons_on_left = close_buttons_on_left;
+        self
+    }
+
+    /// Lays the tab bars out from right to left, see [`Style::rtl`]. By `Default` it's `false`.
+    #[inline(always)]
+    pub fn rtl(mut self, rtl: bool) -> Self {
+        self.style.rtl = rtl;
+        self
+    }
+
+    /// Enables / Disables closing tabs by middle-clicking them, see [`Style::close_on_middle_click`].
+    /// By `Default` it's `true`.
+    #[inline(always)]
+    pub fn close_on_middle_click(mut self, close_on_middle_click: bool) -> Self {
+        self.style.close_on_middle_click = close_on_middle_click;
+        self
+    }
+
+    /// Shows / Hides the tooltips of tab titles, see [`Style::show_tab_tooltips`]. By `Default` they're hidden.
+    #[inline(always)]
+    pub fn show_tab_tooltips(mut self, show_tab_tooltips: bool) -> Self {
+        self.style.show_tab_tooltips = show_tab_tooltips;
+        self
+    }
+
+    /// Sets `tab_tooltip_delay` for how many seconds the pointer rests on a tab title before its tooltip shows up.
+    /// By `Default` it's `0.5`.
+    #[inline(always)]
+    pub fn with_tab_tooltip_delay(mut self, tab_tooltip_delay: f32) -> Self {
+        self.style.tab_tooltip_delay = tab_tooltip_delay;
+        self
+    }
+
+    /// Sets `tab_drag_threshold` for how far tabs have to be dragged before they lift off. By `Default` it's
+    /// `(30.0, 6.0)`.
+    #[inline(always)]
+    pub fn with_tab_drag_threshold(mut self, tab_drag_threshold: Vec2) -> Self {
+        self.style.tab_drag_threshold = tab_drag_threshold;
+        self
+    }
+
+    /// Sets `drag_preview_opacity` for how opaque dragged tabs are. By `Default` it's `0.75`.
+    #[inline(always)]
+    pub fn with_drag_preview_opacity(mut self, drag_preview_opacity: f32) -> Self {
+        self.style.drag_preview_opacity = drag_preview_opacity;
+        self
+    }
+
+    /// Sets `drag_preview_width` for the width of the picture of the content of dragged tabs. By `Default` it's
+    /// `240.0`.
+    #[inline(always)]
+    pub fn with_drag_preview_width(mut self, drag_preview_width: f32) -> Self {
+        self.style.drag_preview_width = drag_preview_width;
+        self
+    }
+
+    /// Captures / Doesn't capture pictures of the content of tabs, see [`Style::capture_tab_previews`]. By `Default`
+    /// they aren't captured.
+    #[inline(always)]
+    pub fn capture_tab_previews(mut self, capture_tab_previews: bool) -> Self {
+        self.style.capture_tab_previews = capture_tab_previews;
+        self
+    }
+
+    /// Hides / Shows the tab bars until the pointer gets to their edge of the leaf, see [`Style::auto_hide_tab_bars`].
+    /// By `Default` it's `false`.
+    #[inline(always)]
+    pub fn auto_hide_tab_bars(mut self, auto_hide_tab_bars: bool) -> Self {
+        self.style.auto_hide_tab_bars = auto_hide_tab_bars;
+        self
+    }
+
+    /// Shows / Hides the "+" buttons after the tabs, see [`Style::show_add_buttons`]. By `Default` it's `false`.
+    #[inline(always)]
+    pub fn show_add_buttons(mut self, show_add_buttons: bool) -> Self {
+        self.style.show_add_buttons = show_add_buttons;
+        self
+    }
+
+    /// Lets / Keeps double-clicking a tab maximize its leaf, see [`Style::maximize_on_double_click`].
+    /// By `Default` it's `true`.
+    #[inline(always)]
+    pub fn maximize_on_double_click(mut self, maximize_on_double_click: bool) -> Self {
+        self.style.maximize_on_double_click = maximize_on_double_click;
+        self
+    }
+
+    /// Sets `tab_bar_position` for the edge of their leaf tab bars are shown along. By `Default` it's
+    /// [`TabBarPosition::Top`].
+    #[inline(always)]
+    pub fn with_tab_bar_position(mut self, tab_bar_position: TabBarPosition) -> Self {
+        self.style.tab_bar_position = tab_bar_position;
+        self
+    }
+
+    /// Sets `vertical_tab_bar_width` for the width of the tab bars along the left or right edge of their leaf.
+    /// By `Default` it's `150.0`.
+    #[inline(always)]
+    pub fn with_vertical_tab_bar_width(mut self, vertical_tab_bar_width: f32) -> Self {
+        self.style.vertical_tab_bar_width = vertical_tab_bar_width;
+        self
+    }
+
+    /// Hides / Shows the tab bars of leaves with a single tab, see [`Style::hide_single_tab_bars`].
+    /// By `Default` it's `false`.
+    #[inline(always)]
+    pub fn hide_single_tab_bars(mut self, hide_single_tab_bars: bool) -> Self {
+        self.style.hide_single_tab_bars = hide_single_tab_bars;
+        self
+    }
+
+    /// Sets `focus_outline_stroke` for the outline drawn around the focused leaf.
+    #[inline(always)]
+    pub fn with_focus_outline_stroke(mut self, focus_outline_stroke: Stroke) -> Self {
+        self.style.focus_outline_stroke = focus_outline_stroke;
+        self
+    }
+
+    /// Shows / Hides the outline around the focused leaf. By `Default` it's `false`.
+    #[inline(always)]
+    pub fn show_focus_outline(mut self, show_focus_outline: bool) -> Self {
+        self.style.show_focus_outline = show_focus_outline;
+        self
+    }
+
+    /// Turns accessibility mode on / off, see [`Style::accessibility_mode`]. By `Default` it's `false`.
+    #[inline(always)]
+    pub fn with_accessibility_mode(mut self, accessibility_mode: bool) -> Self {
+        self.style.accessibility_mode = accessibility_mode;
+        self
+    }
+
+    /// Disables / enables all dock animations. By `Default` it's `false`.
+    #[inline(always)]
+    pub fn with_reduced_motion(mut self, reduced_motion: bool) -> Self {
+        self.style.reduced_motion = reduced_motion;
+        self
+    }
+
+    /// Sets the seconds it takes a separator to move to a fraction changed from code, see
+    /// [`Style::separator_animation_time`]. By `Default` it's `0.0`.
+    #[inline(always)]
+    pub fn with_separator_animation_time(mut self, separator_animation_time: f32) -> Self {
+        self.style.separator_animation_time = separator_animation_time;
+        self
+    }
+
+    /// Sets the seconds it takes a tab to fade in and out of its active look, see
+    /// [`Style::tab_highlight_animation_time`]. By `Default` it's `0.0`.
+    #[inline(always)]
+    pub fn with_tab_highlight_animation_time(mut self, tab_highlight_animation_time: f32) -> Self {
+        self.style.tab_highlight_animation_time = tab_highlight_animation_time;
+        self
+    }
+
+    /// Sets the seconds it takes a leaf to collapse to its tab bar or expand back, see
+    /// [`Style::collapse_animation_time`]. By `Default` it's `0.0`.
+    #[inline(always)]
+    pub fn with_collapse_animation_time(mut self, collapse_animation_time: f32) -> Self {
+        self.style.collapse_animation_time = collapse_animation_time;
+        self
+    }
+
     /// Returns `Style` with set values.
     #[inline(always)]
     pub fn build(self) -> Style {
@@ -0,0 +1,383 @@
+use egui::*;
+
+use crate::{Node, NodeId, NodeIndex, TabIndex, Tree};
+
+/// Identifies a [`Surface`] within a [`DockState`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct SurfaceIndex(pub usize);
+
+impl SurfaceIndex {
+    /// The main surface, which is shown inside the dock area itself.
+    #[inline(always)]
+    pub const fn main() -> Self {
+        Self(0)
+    }
+
+    /// Returns `true` if this is the main surface, `false` otherwise.
+    #[inline(always)]
+    pub const fn is_main(self) -> bool {
+        self.0 == 0
+    }
+}
+
+/// Position, size and other state of a floating window.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct WindowState {
+    /// Where the window is shown, kept up to date by [`DockArea`](crate::DockArea) while the window is moved and
+    /// resized.
+    pub rect: Rect,
+
+    /// Whether the window is minimized to its title bar, kept up to date by [`DockArea`](crate::DockArea).
+    pub collapsed: bool,
+
+    /// The leaf of the main surface the tabs of the window return to when the window is closed.
+    ///
+    /// If it's `None` or no longer a leaf, they return to the focused leaf of the main surface instead.
+    pub home: Option<NodeId>,
+
+    /// Keeps the window above the dock area and every other floating window, even while they're being clicked.
+    /// Meant for palettes and tool windows.
+    pub always_on_top: bool,
+}
+
+impl Default for WindowState {
+    fn default() -> Self {
+        Self {
+            rect: Rect::from_min_size(pos2(100.0, 100.0), vec2(400.0, 300.0)),
+            collapsed: false,
+            home: None,
+            always_on_top: false,
+        }
+    }
+}
+
+/// Something tabs can be docked onto, each holding a [`Tree`] of its own.
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum Surface<Tab> {
+    /// A removed surface, kept so the other surfaces keep their indices.
+    Empty,
+
+    /// The main surface, shown inside the dock area.
+    Main(Tree<Tab>),
+
+    /// A floating window shown above the dock area, an [`egui::Window`] inside the app rather than a native OS
+    /// window.
+    Window(Tree<Tab>, WindowState),
+}
+
+impl<Tab> Surface<Tab> {
+    /// Returns `true` if the surface is `Empty`, `false` otherwise.
+    pub const fn is_empty(&self) -> bool {
+        matches!(self, Self::Empty)
+    }
+
+    /// Returns the tree of the surface, or `None` if the surface is `Empty`.
+    pub fn tree(&self) -> Option<&Tree<Tab>> {
+        match self {
+            Self::Empty => None,
+            Self::Main(tree) | Self::Window(tree, _) => Some(tree),
+        }
+    }
+
+    /// Returns the tree of the surface, or `None` if the surface is `Empty`.
+    pub fn tree_mut(&mut self) -> Option<&mut Tree<Tab>> {
+        match self {
+            Self::Empty => None,
+            Self::Main(tree) | Self::Window(tree, _) => Some(tree),
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// The whole layout of a dock: the main surface and any number of floating windows, each with a [`Tree`] of its
+/// own.
+///
+/// Tabs can be moved freely between the surfaces, and serializing a `DockState` covers all of them. Show it with
+/// [`DockArea::from_state`](crate::DockArea::from_state).
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct DockState<Tab> {
+    /// The main surface always comes first.
+    surfaces: Vec<Surface<Tab>>,
+}
+
+impl<Tab> From<Tree<Tab>> for DockState<Tab> {
+    fn from(tree: Tree<Tab>) -> Self {
+        Self {
+            surfaces: vec![Surface::Main(tree)],
+        }
+    }
+}
+
+impl<Tab> DockState<Tab> {
+    /// Creates a new `DockState` with the given `Tab`s in the root node of its main surface.
+    pub fn new(tabs: Vec<Tab>) -> Self {
+        Tree::new(tabs).into()
+    }
+
+    /// Returns the tree of the main surface.
+    pub fn main_surface(&self) -> &Tree<Tab> {
+        match &self.surfaces[0] {
+            Surface::Main(tree) => tree,
+            _ => unreachable!("the main surface always comes first"),
+        }
+    }
+
+    /// Returns the tree of the main surface.
+    pub fn main_surface_mut(&mut self) -> &mut Tree<Tab> {
+        self.split_main_mut().0
+    }
+
+    /// Returns the tree of the main surface and the rest of the surfaces.
+    pub(crate) fn split_main_mut(&mut self) -> (&mut Tree<Tab>, &mut [Surface<Tab>]) {
+        match self.surfaces.split_first_mut() {
+            Some((Surface::Main(tree), rest)) => (tree, rest),
+            _ => unreachable!("the main surface always comes first"),
+        }
+    }
+
+    /// Returns the tree of the surface at `surface`, or `None` if there's no surface there.
+    pub fn surface(&self, surface: SurfaceIndex) -> Option<&Tree<Tab>> {
+        self.surfaces.get(surface.0)?.tree()
+    }
+
+    /// Returns the tree of the surface at `surface`, or `None` if there's no surface there.
+    pub fn surface_mut(&mut self, surface: SurfaceIndex) -> Option<&mut Tree<Tab>> {
+        self.surfaces.get_mut(surface.0)?.tree_mut()
+    }
+
+    /// Returns the tab being dragged as of the last frame, on any surface, or `None` if no tab is being dragged.
+    ///
+    /// See [`Tree::dragged_tab`].
+    pub fn dragged_tab(&self) -> Option<(SurfaceIndex, NodeIndex, TabIndex)> {
+        self.surfaces().find_map(|(surface_index, surface)| {
+            let (node_index, tab_index) = surface.tree()?.dragged_tab()?;
+            Some((surface_index, node_index, tab_index))
+        })
+    }
+
+    /// Returns the position and size of the floating window at `surface`, or `None` if it's not a window.
+    pub fn window_state(&self, surface: SurfaceIndex) -> Option<&WindowState> {
+        match self.surfaces.get(surface.0)? {
+            Surface::Window(_, window) => Some(window),
+            _ => None,
+        }
+    }
+
+    /// Returns the position and size of the floating window at `surface`, or `None` if it's not a window.
+    pub fn window_state_mut(&mut self, surface: SurfaceIndex) -> Option<&mut WindowState> {
+        match self.surfaces.get_mut(surface.0)? {
+            Surface::Window(_, window) => Some(window),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of surfaces, including `Empty` ones.
+    pub fn surfaces_count(&self) -> usize {
+        self.surfaces.len()
+    }
+
+    /// Returns an iterator over every surface which isn't `Empty`.
+    pub fn surfaces(&self) -> impl Iterator<Item = (SurfaceIndex, &Surface<Tab>)> + '_ {
+        self.surfaces
+            .iter()
+            .enumerate()
+            .filter(|(_, surface)| !surface.is_empty())
+            .map(|(index, surface)| (SurfaceIndex(index), surface))
+    }
+
+    /// Returns an iterator over every surface which isn't `Empty`.
+    pub fn surfaces_mut(&mut self) -> impl Iterator<Item = (SurfaceIndex, &mut Surface<Tab>)> + '_ {
+        self.surfaces
+            .iter_mut()
+            .enumerate()
+            .filter(|(_, surface)| !surface.is_empty())
+            .map(|(index, surface)| (SurfaceIndex(index), surface))
+    }
+
+    /// Opens a new floating window holding `tabs`.
+    ///
+    /// Returns the index of the new surface.
+    pub fn add_window(&mut self, tabs: Vec<Tab>, window: WindowState) -> SurfaceIndex {
+        let surface = Surface::Window(Tree::new(tabs), window);
+        match self.surfaces.iter().position(Surface::is_empty) {
+            Some(index) => {
+                self.surfaces[index] = surface;
+                SurfaceIndex(index)
+            }
+            None => {
+                self.surfaces.push(surface);
+                SurfaceIndex(self.surfaces.len() - 1)
+            }
+        }
+    }
+
+    /// Removes the floating window at `surface`.
+    ///
+    /// Returns its tree, or `None` if `surface` isn't a window.
+    pub fn remove_window(&mut self, surface: SurfaceIndex) -> Option<Tree<Tab>> {
+        match self.surfaces.get_mut(surface.0)? {
+            window @ Surface::Window(..) => match std::mem::replace(window, Surface::Empty) {
+                Surface::Window(tree, _) => Some(tree),
+                _ => unreachable!(),
+            },
+            _ => None,
+        }
+    }
+
+    /// Moves the tab at `src` to the end of the leaf at `dst`, possibly on another surface, and focuses that leaf.
+    ///
+    /// The leaf the tab came from is removed if that was its last tab, and so is its window if that was the last
+    /// tab of the window.
+    ///
+    /// # Panics
+    /// Panics if either surface doesn't exist, `src` isn't a tab or `dst` isn't a leaf.
+    pub fn move_tab(
+        &mut self,
+        src: (SurfaceIndex, NodeIndex, TabIndex),
+        dst: (SurfaceIndex, NodeIndex),
+    ) {
+        let (src_surface, src_node, _) = src;
+        if (src_surface, src_node) == dst {
+            return;
+        }
+        assert!(self.surfaces[dst.0 .0].tree().expect("no such surface")[dst.1].is_leaf());
+
+        let tab = self.take_tab(src);
+        let tree = self.surface_mut(dst.0).expect("no such surface");
+        tree[dst.1].append_tab(tab);
+        tree.set_focused_node(dst.1);
+
+        self.remove_emptied(src_surface, src_node);
+    }
+
+    /// Moves the tab at `src` into a new floating window at `rect`.
+    ///
+    /// If the tab came from the main surface, closing the window returns it to the leaf it came from.
+    ///
+    /// Returns the index of the new surface.
+    ///
+    /// # Panics
+    /// Panics if the surface doesn't exist or `src` isn't a tab.
+    pub fn detach_tab(
+        &mut self,
+        src: (SurfaceIndex, NodeIndex, TabIndex),
+        rect: Rect,
+    ) -> SurfaceIndex {
+        let home = if src.0.is_main() {
+            self.main_surface().node_id(src.1)
+        } else {
+            None
+        };
+        let tab = self.take_tab(src);
+        self.remove_emptied(src.0, src.1);
+        self.add_window(
+            vec![tab],
+            WindowState {
+                rect,
+                home,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Moves every tab of the leaf at `src` into a new floating window at `rect`, keeping the same tab active. The
+    /// window is an [`egui::Window`] inside the app, not a native OS window.
+    ///
+    /// If the leaf was in the main surface, closing the window returns the tabs to the leaf closest to where it was.
+    ///
+    /// Returns the index of the new surface.
+    ///
+    /// # Panics
+    /// Panics if the surface doesn't exist or `src` isn't a leaf.
+    pub fn detach_leaf(&mut self, src: (SurfaceIndex, NodeIndex), rect: Rect) -> SurfaceIndex {
+        let tree = self.surface_mut(src.0).expect("no such surface");
+        // The leaf itself goes away, so the tabs return next to where it was.
+        let home = if src.0.is_main() {
+            tree.nearest_leaf(src.1).and_then(|leaf| tree.node_id(leaf))
+        } else {
+            None
+        };
+        let (tabs, active) = match &mut tree[src.1] {
+            Node::Leaf { tabs, active, .. } => (std::mem::take(tabs), *active),
+            _ => panic!("not a leaf"),
+        };
+        self.remove_emptied(src.0, src.1);
+
+        let surface = self.add_window(
+            tabs,
+            WindowState {
+                rect,
+                home,
+                ..Default::default()
+            },
+        );
+        if let Some(tree) = self.surface_mut(surface) {
+            if let Some(root) = tree.root() {
+                tree.set_active_tab(root, active);
+            }
+        }
+        surface
+    }
+
+    /// Moves every tab of the floating window at `src` to the end of the leaf at `dst`, removing the window.
+    ///
+    /// # Panics
+    /// Panics if `src` isn't a window, the `dst` surface doesn't exist or `dst` isn't a leaf.
+    pub fn attach_window(&mut self, src: SurfaceIndex, dst: (SurfaceIndex, NodeIndex)) {
+        assert_ne!(src, dst.0, "can't attach a window to itself");
+        let tree = self.surface_mut(dst.0).expect("no such surface");
+        assert!(tree[dst.1].is_leaf(), "not a leaf");
+        // Only take the window once `dst` is known to be a leaf, so nothing is lost if it isn't.
+        let mut window = self.remove_window(src).expect("not a window");
+
+        let tree = self.surface_mut(dst.0).expect("no such surface");
+        for node in window.iter_mut() {
+            if let Node::Leaf { tabs, .. } = node {
+                for tab in tabs.drain(..) {
+                    tree[dst.1].append_tab(tab);
+                }
+            }
+        }
+        tree.set_focused_node(dst.1);
+    }
+
+    /// Removes the tab at `src` from its leaf, leaving the leaf in place even if it's empty.
+    fn take_tab(&mut self, (surface, node, tab): (SurfaceIndex, NodeIndex, TabIndex)) -> Tab {
+        let tree = self.surface_mut(surface).expect("no such surface");
+        if let Node::Leaf { active, .. } = &mut tree[node] {
+            if *active >= tab {
+                active.0 = active.0.saturating_sub(1);
+            }
+        }
+        tree[node].remove_tab(tab).expect("not a leaf")
+    }
+
+    /// Removes the leaf at `node` if it's been left empty, and the surface at `surface` if it's a window left empty.
+    fn remove_emptied(&mut self, surface: SurfaceIndex, node: NodeIndex) {
+        let tree = self.surface_mut(surface).expect("no such surface");
+        tree.remove_leaf_if_empty(node);
+        if tree.is_empty() && !surface.is_main() {
+            self.surfaces[surface.0] = Surface::Empty;
+        }
+    }
+}
+
+impl<Tab> DockState<Tab>
+where
+    Tab: PartialEq,
+{
+    /// Find the given tab on any of the surfaces.
+    ///
+    /// Returns which surface and node the tab is in, and where in that node the tab is in.
+    ///
+    /// In case there are several hits, only the first is returned.
+    pub fn find_tab(&self, needle_tab: &Tab) -> Option<(SurfaceIndex, NodeIndex, TabIndex)> {
+        self.surfaces().find_map(|(surface_index, surface)| {
+            let (node_index, tab_index) = surface.tree()?.find_tab(needle_tab)?;
+            Some((surface_index, node_index, tab_index))
+        })
+    }
+}
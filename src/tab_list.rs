@@ -0,0 +1,95 @@
+use egui::*;
+
+use crate::quick_open::{fuzzy_score, Entry};
+
+/// Persistent state of the menu listing the tabs of a leaf whose tab bar overflows.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TabList {
+    /// Id of the button the open menu belongs to, if any.
+    open: Option<Id>,
+    filter: String,
+}
+
+impl TabList {
+    /// Opens or closes the menu of `button` if it was clicked.
+    ///
+    /// Returns whether the menu of `button` is open.
+    pub fn toggle(&mut self, button: &Response) -> bool {
+        if button.clicked() {
+            if self.open == Some(button.id) {
+                self.open = None;
+            } else {
+                self.open = Some(button.id);
+                self.filter.clear();
+            }
+        }
+        self.open == Some(button.id)
+    }
+
+    /// Shows the menu below `button`, listing the `entries` matching the filter typed into it.
+    ///
+    /// Returns the entry picked by the user, if any.
+    pub fn show(
+        &mut self,
+        ui: &Ui,
+        button: &Response,
+        animation_time: f32,
+        mut entries: Vec<Entry>,
+    ) -> Option<Entry> {
+        if self.open != Some(button.id) {
+            return None;
+        }
+
+        let filter = &mut self.filter;
+        entries.retain(|entry| fuzzy_score(filter, &entry.title).is_some());
+
+        let width = 220.0;
+        let mut picked = None;
+        let mut enter = false;
+
+        let area = Area::new(button.id.with("tab_list"))
+            .order(Order::Foreground)
+            .fixed_pos(pos2(button.rect.max.x - width, button.rect.max.y))
+            .show(ui.ctx(), |ui| {
+                ui.style_mut().animation_time = animation_time;
+                Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_width(width);
+
+                    let response = ui.add(
+                        TextEdit::singleline(filter)
+                            .hint_text("Filter tabs…")
+                            .desired_width(f32::INFINITY),
+                    );
+                    enter = response.lost_focus() && ui.input().key_pressed(Key::Enter);
+                    response.request_focus();
+
+                    ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                        for (index, entry) in entries.iter().enumerate() {
+                            if ui.selectable_label(false, &entry.title).clicked() {
+                                picked = Some(index);
+                            }
+                        }
+                    });
+                });
+            })
+            .response;
+
+        if enter && !entries.is_empty() {
+            picked = picked.or(Some(0));
+        }
+
+        let (escape, clicked_outside) = {
+            let input = ui.input();
+            let clicked_outside = input.pointer.any_click()
+                && input.pointer.interact_pos().map_or(false, |pos| {
+                    !area.rect.contains(pos) && !button.rect.contains(pos)
+                });
+            (input.key_pressed(Key::Escape), clicked_outside)
+        };
+        if escape || clicked_outside || picked.is_some() {
+            self.open = None;
+        }
+
+        picked.map(|index| entries.swap_remove(index))
+    }
+}
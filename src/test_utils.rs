@@ -0,0 +1,778 @@
+//! Utilities for writing regression tests against a [`DockArea`](crate::DockArea), available with the `test_utils`
+//! feature.
+//!
+//! A [`DockHarness`] runs frames of a headless `egui::Context` and feeds them simulated input, like clicking tabs,
+//! dragging tabs between nodes and dragging separators. [`DockHarness::activate_tab`], [`DockHarness::drag_tab`] and
+//! [`DockHarness::resize_split`] find where to do so from the [`Tree`] itself, so tests don't need to know how the dock
+//! lays anything out:
+//!
+//! ```rust
+//! # use egui_dock::egui::{self, vec2};
+//! # use egui_dock::test_utils::DockHarness;
+//! # use egui_dock::{DockArea, TabIndex, Tree};
+//! # struct Viewer;
+//! # impl egui_dock::TabViewer for Viewer {
+//! #     type Tab = String;
+//! #     fn ui(&mut self, ui: &mut egui::Ui, tab: &mut String) { ui.label(tab.as_str()); }
+//! #     fn title(&mut self, tab: &mut String) -> egui::WidgetText { tab.as_str().into() }
+//! # }
+//! let mut tree = Tree::new(vec!["a".to_owned(), "b".to_owned()]);
+//! let mut viewer = Viewer;
+//! let mut harness = DockHarness::new(vec2(800.0, 600.0));
+//!
+//! harness.run(|ctx| {
+//!     DockArea::new(&mut tree).show(ctx, &mut viewer);
+//! });
+//! let root = tree.root().unwrap();
+//! harness.activate_tab(&mut tree, root, TabIndex(1), |ctx, tree| {
+//!     DockArea::new(tree).show(ctx, &mut viewer);
+//! });
+//!
+//! assert_eq!(tree.find_active().map(|(_, tab)| tab.as_str()), Some("b"));
+//! ```
+
+use egui::*;
+
+use crate::{Node, NodeIndex, Split, Style, TabIndex, Tree};
+
+/// How many frames a drag is spread over, so egui and the dock both notice the pointer moving.
+const DRAG_STEPS: usize = 8;
+
+/// Runs frames of a headless `egui::Context`, feeding them simulated input.
+///
+/// Every method running frames takes a `show` closure which should show the dock under test, usually with
+/// [`DockArea::show`](crate::DockArea::show). Between frames the tree can be inspected as usual.
+pub struct DockHarness {
+    ctx: Context,
+    screen_rect: Rect,
+    dock_id: Id,
+    style: Option<Style>,
+    time: f64,
+    pointer: Pos2,
+    modifiers: Modifiers,
+    events: Vec<Event>,
+}
+
+impl DockHarness {
+    /// Creates a harness with a screen of `size` points, at one pixel per point.
+    pub fn new(size: Vec2) -> Self {
+        Self {
+            ctx: Context::default(),
+            screen_rect: Rect::from_min_size(Pos2::ZERO, size),
+            dock_id: Id::new("egui_dock::DockArea"),
+            style: None,
+            time: 0.0,
+            pointer: Pos2::ZERO,
+            modifiers: Modifiers::default(),
+            events: Vec::new(),
+        }
+    }
+
+    /// Sets the id of the dock under test, needed if it was changed with [`DockArea::id`](crate::DockArea::id).
+    pub fn dock_id(mut self, id: Id) -> Self {
+        self.dock_id = id;
+        self
+    }
+
+    /// Sets the style of the dock under test, needed if it was changed with
+    /// [`DockArea::style`](crate::DockArea::style).
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    /// The headless context the frames are run with.
+    pub fn ctx(&self) -> &Context {
+        &self.ctx
+    }
+
+    /// Runs a single frame with the input queued since the last one.
+    pub fn run(&mut self, show: impl FnOnce(&Context)) -> FullOutput {
+        self.time += 1.0 / 60.0;
+        let input = RawInput {
+            screen_rect: Some(self.screen_rect),
+            pixels_per_point: Some(1.0),
+            time: Some(self.time),
+            predicted_dt: 1.0 / 60.0,
+            // Modifiers are only held for the frame their key is pressed in.
+            modifiers: std::mem::take(&mut self.modifiers),
+            events: std::mem::take(&mut self.events),
+            ..Default::default()
+        };
+        self.ctx.run(input, show)
+    }
+
+    /// Queues moving the pointer to `pos`.
+    pub fn move_pointer(&mut self, pos: Pos2) {
+        self.pointer = pos;
+        self.events.push(Event::PointerMoved(pos));
+    }
+
+    /// Queues pressing (or releasing, if `pressed` is `false`) the primary button where the pointer is.
+    pub fn pointer_button(&mut self, pressed: bool) {
+        self.events.push(Event::PointerButton {
+            pos: self.pointer,
+            button: PointerButton::Primary,
+            pressed,
+            modifiers: self.modifiers,
+        });
+    }
+
+    /// Queues pressing and releasing `key` while holding `modifiers` during the next frame.
+    pub fn key(&mut self, key: Key, modifiers: Modifiers) {
+        self.modifiers = modifiers;
+        for pressed in [true, false] {
+            self.events.push(Event::Key {
+                key,
+                pressed,
+                modifiers,
+            });
+        }
+    }
+
+    /// Clicks at `pos`, running a frame for each of moving there, pressing and releasing.
+    pub fn click(&mut self, pos: Pos2, mut show: impl FnMut(&Context)) {
+        self.move_pointer(pos);
+        self.run(&mut show);
+        self.pointer_button(true);
+        self.run(&mut show);
+        self.pointer_button(false);
+        self.run(&mut show);
+    }
+
+    /// Drags from `from` to `to`, moving the pointer over a few frames before releasing it.
+    ///
+    /// Used to drag tabs onto other nodes, or separators to resize nodes.
+    pub fn drag(&mut self, from: Pos2, to: Pos2, mut show: impl FnMut(&Context)) {
+        self.move_pointer(from);
+        self.run(&mut show);
+        self.pointer_button(true);
+        self.run(&mut show);
+        for step in 1..=DRAG_STEPS {
+            self.move_pointer(from + (to - from) * (step as f32 / DRAG_STEPS as f32));
+            self.run(&mut show);
+        }
+        self.pointer_button(false);
+        self.run(&mut show);
+        self.run(&mut show);
+    }
+
+    /// Clicks the title of `tab` in the leaf at `node`, as it was shown during the last frame.
+    ///
+    /// `show` is given the tree to show. Returns `false`, without running any frame, if the title wasn't shown.
+    pub fn activate_tab<Tab>(
+        &mut self,
+        tree: &mut Tree<Tab>,
+        node: NodeIndex,
+        tab: TabIndex,
+        mut show: impl FnMut(&Context, &mut Tree<Tab>),
+    ) -> bool {
+        let title = match tree.tab_title_rect(node, tab) {
+            Some(title) => title,
+            None => return false,
+        };
+        self.click(self.title_point(title), |ctx| show(ctx, tree));
+        true
+    }
+
+    /// Drags `tab` from the leaf at `src` onto the leaf at `dst`, into it if `split` is `None`, or splitting it
+    /// otherwise.
+    ///
+    /// `show` is given the tree to show. Returns `false`, without running any frame, if the title of the tab or the
+    /// leaf at `dst` wasn't shown during the last frame.
+    pub fn drag_tab<Tab>(
+        &mut self,
+        tree: &mut Tree<Tab>,
+        (src, tab): (NodeIndex, TabIndex),
+        dst: NodeIndex,
+        split: Option<Split>,
+        mut show: impl FnMut(&Context, &mut Tree<Tab>),
+    ) -> bool {
+        let (title, body) = match (tree.tab_title_rect(src, tab), self.body_rect(tree, dst)) {
+            (Some(title), Some(body)) if body.is_positive() => (title, body),
+            _ => return false,
+        };
+        // Close to the edge of the body the tab splits the leaf, like the drop preview shows.
+        let center = body.center();
+        let to = match split {
+            None => center,
+            Some(Split::Left) => center + (body.left_center() - center) * 0.9,
+            Some(Split::Right) => center + (body.right_center() - center) * 0.9,
+            Some(Split::Above) => center + (body.center_top() - center) * 0.9,
+            Some(Split::Below) => center + (body.center_bottom() - center) * 0.9,
+        };
+        self.drag(self.title_point(title), to, |ctx| show(ctx, tree));
+        true
+    }
+
+    /// Drags the separator of the parent node at `node` until roughly `fraction` of the node is left to its
+    /// left-most or top-most child.
+    ///
+    /// `show` is given the tree to show. Returns `false`, without running any frame, if the node isn't a parent.
+    pub fn resize_split<Tab>(
+        &mut self,
+        tree: &mut Tree<Tab>,
+        node: NodeIndex,
+        fraction: f32,
+        mut show: impl FnMut(&Context, &mut Tree<Tab>),
+    ) -> bool {
+        let (from, rect) = match (self.separator_center(tree, node), tree[node].rect()) {
+            (Some(from), Some(rect)) => (from, rect),
+            _ => return false,
+        };
+        let fraction = fraction.clamp(0.0, 1.0);
+        let to = if tree[node].is_horizontal() {
+            pos2(lerp(rect.x_range(), fraction), from.y)
+        } else {
+            pos2(from.x, lerp(rect.y_range(), fraction))
+        };
+        self.drag(from, to, |ctx| show(ctx, tree));
+        true
+    }
+
+    /// The style the dock under test is shown with.
+    fn dock_style(&self) -> Style {
+        match &self.style {
+            Some(style) => style.clone(),
+            None => Style::from_egui(&self.ctx.style()),
+        }
+    }
+
+    /// Where to press the tab title at `title`: on the title itself, clear of the close button at its other end.
+    fn title_point(&self, title: Rect) -> Pos2 {
+        let inset = title.height() * 0.5;
+        if self.dock_style().close_buttons_on_left {
+            title.right_center() - vec2(inset, 0.0)
+        } else {
+            title.left_center() + vec2(inset, 0.0)
+        }
+    }
+
+    /// Returns where the title of `tab` in the leaf at `node` was shown during the last frame, or `None` if it
+    /// wasn't shown.
+    pub fn tab_rect<Tab>(&self, tree: &Tree<Tab>, node: NodeIndex, tab: TabIndex) -> Option<Rect> {
+        tree.tab_title_rect(node, tab)
+    }
+
+    /// Returns where the body of the leaf at `node` was shown during the last frame, a good place to drop tabs.
+    pub fn body_rect<Tab>(&self, tree: &Tree<Tab>, node: NodeIndex) -> Option<Rect> {
+        match &tree[node] {
+            Node::Leaf { viewport, .. } => Some(*viewport),
+            _ => None,
+        }
+    }
+
+    /// Returns the center of the separator of the parent node at `node` as of the last frame, or `None` if the node
+    /// isn't a parent.
+    pub fn separator_center<Tab>(&self, tree: &Tree<Tab>, node: NodeIndex) -> Option<Pos2> {
+        if !tree[node].is_parent() {
+            return None;
+        }
+        let rect = tree[node].rect()?;
+        let [left, right] = tree.children(node)?;
+        let (left, right) = (tree[left].rect()?, tree[right].rect()?);
+        if tree[node].is_horizontal() {
+            // The left-most child is stored on the right of horizontal nodes.
+            Some(pos2((right.max.x + left.min.x) * 0.5, rect.center().y))
+        } else {
+            Some(pos2(rect.center().x, (left.max.y + right.min.y) * 0.5))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DockArea, DockEvent, DockPass, State, TabViewer};
+
+    struct Viewer;
+
+    impl TabViewer for Viewer {
+        type Tab = String;
+
+        fn ui(&mut self, ui: &mut Ui, tab: &mut String) {
+            ui.label(tab.as_str());
+        }
+
+        fn title(&mut self, tab: &mut String) -> WidgetText {
+            tab.as_str().into()
+        }
+    }
+
+    fn tabs(tree: &Tree<String>, node: NodeIndex) -> Vec<&str> {
+        match &tree[node] {
+            Node::Leaf { tabs, .. } => tabs.iter().map(String::as_str).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    #[test]
+    fn click_tab() {
+        let mut tree = Tree::new(vec!["a".to_owned(), "b".to_owned()]);
+        let mut harness = DockHarness::new(vec2(800.0, 600.0));
+        let show = |ctx: &Context, tree: &mut Tree<String>| {
+            DockArea::new(tree).show(ctx, &mut Viewer);
+        };
+        harness.run(|ctx| show(ctx, &mut tree));
+
+        let root = tree.root().unwrap();
+        let title = harness.tab_rect(&tree, root, TabIndex(1));
+        harness.click(title.unwrap().center(), |ctx| show(ctx, &mut tree));
+        assert_eq!(tree.find_active().map(|(_, tab)| tab.as_str()), Some("b"));
+    }
+
+    #[test]
+    fn activate_and_drag_tabs() {
+        let mut tree = Tree::new(vec!["a".to_owned(), "b".to_owned(), "c".to_owned()]);
+        let mut harness = DockHarness::new(vec2(800.0, 600.0));
+        let show = |ctx: &Context, tree: &mut Tree<String>| {
+            DockArea::new(tree).show(ctx, &mut Viewer);
+        };
+        harness.run(|ctx| show(ctx, &mut tree));
+
+        let leaf = tree.root().unwrap();
+        assert!(harness.activate_tab(&mut tree, leaf, TabIndex(2), show));
+        assert_eq!(tree.find_active().map(|(_, tab)| tab.as_str()), Some("c"));
+
+        // Dropping a tab close to the bottom of the body splits the leaf.
+        let src = (leaf, TabIndex(0));
+        assert!(harness.drag_tab(&mut tree, src, leaf, Some(Split::Below), show));
+        assert!(tree[tree.root().unwrap()].is_parent());
+        let (node, _) = tree.find_tab(&"a".to_owned()).unwrap();
+        assert_eq!(tabs(&tree, node), ["a"]);
+        assert_eq!(tabs(&tree, leaf), ["b", "c"]);
+        assert_eq!(tree.parent(node), tree.parent(leaf));
+    }
+
+    #[test]
+    fn shortcuts() {
+        let mut tree = Tree::new(vec!["a".to_owned(), "b".to_owned()]);
+        let root = tree.root().unwrap();
+        tree.set_focused_node(root);
+        let mut harness = DockHarness::new(vec2(800.0, 600.0));
+        let show = |ctx: &Context, tree: &mut Tree<String>| {
+            DockArea::new(tree).show(ctx, &mut Viewer);
+        };
+        harness.run(|ctx| show(ctx, &mut tree));
+
+        harness.key(Key::Num2, Modifiers::ALT);
+        harness.run(|ctx| show(ctx, &mut tree));
+        assert_eq!(tree.find_active().map(|(_, tab)| tab.as_str()), Some("b"));
+
+        // Without the modifiers the shortcut doesn't fire.
+        harness.key(Key::Num1, Modifiers::NONE);
+        harness.run(|ctx| show(ctx, &mut tree));
+        assert_eq!(tree.find_active().map(|(_, tab)| tab.as_str()), Some("b"));
+    }
+
+    /// `Ctrl+W` closes the active tab whether close buttons are shown or not, but leaves pinned tabs alone.
+    #[test]
+    fn close_active_tab_shortcut() {
+        struct PinningViewer;
+
+        impl TabViewer for PinningViewer {
+            type Tab = String;
+
+            fn ui(&mut self, ui: &mut Ui, tab: &mut String) {
+                ui.label(tab.as_str());
+            }
+
+            fn title(&mut self, tab: &mut String) -> WidgetText {
+                tab.as_str().into()
+            }
+
+            fn is_pinned(&self, tab: &String) -> bool {
+                tab.starts_with('*')
+            }
+        }
+
+        let mut tree = Tree::new(vec!["*a".to_owned(), "b".to_owned()]);
+        let root = tree.root().unwrap();
+        tree.set_focused_node(root);
+        tree.set_active_tab(root, TabIndex(1));
+        let style = Style {
+            show_close_buttons: false,
+            ..Style::default()
+        };
+        let mut harness = DockHarness::new(vec2(800.0, 600.0));
+        let mut show = |ctx: &Context| {
+            DockArea::new(&mut tree)
+                .style(style.clone())
+                .show(ctx, &mut PinningViewer);
+        };
+        harness.run(&mut show);
+
+        for _ in 0..2 {
+            harness.key(Key::W, Modifiers::COMMAND);
+            harness.run(&mut show);
+        }
+        assert_eq!(tabs(&tree, root), ["*a"]);
+    }
+
+    #[test]
+    fn shortcuts_need_focus() {
+        let mut tree = Tree::new(vec!["a".to_owned(), "b".to_owned()]);
+        let root = tree.root().unwrap();
+        tree.set_focused_node(root);
+        let mut text = String::new();
+        let mut harness = DockHarness::new(vec2(800.0, 600.0));
+        let mut show = |ctx: &Context, tree: &mut Tree<String>, focus_outside: bool| {
+            SidePanel::left("outside").show(ctx, |ui| {
+                let response = ui.text_edit_singleline(&mut text);
+                if focus_outside {
+                    response.request_focus();
+                }
+            });
+            DockArea::new(tree).show(ctx, &mut Viewer);
+        };
+        harness.run(|ctx| show(ctx, &mut tree, true));
+
+        harness.key(Key::W, Modifiers::COMMAND);
+        harness.run(|ctx| show(ctx, &mut tree, false));
+        assert_eq!(tabs(&tree, root), ["a", "b"]);
+    }
+
+    /// A tab closed by a shortcut is only removed at the end of the frame, the tree mustn't be split under it.
+    #[test]
+    fn close_and_split_in_one_frame() {
+        let mut tree = Tree::new(vec!["a".to_owned(), "b".to_owned()]);
+        let root = tree.root().unwrap();
+        tree.set_focused_node(root);
+        let mut harness = DockHarness::new(vec2(800.0, 600.0));
+        let shortcuts = crate::DockShortcuts {
+            split_horizontal: vec![crate::Shortcut::new(Modifiers::COMMAND, Key::S)],
+            ..Default::default()
+        };
+        let show = |ctx: &Context, tree: &mut Tree<String>| {
+            DockArea::new(tree)
+                .shortcuts(shortcuts.clone())
+                .show(ctx, &mut Viewer);
+        };
+        harness.run(|ctx| show(ctx, &mut tree));
+
+        harness.key(Key::W, Modifiers::COMMAND);
+        harness.key(Key::S, Modifiers::COMMAND);
+        harness.run(|ctx| show(ctx, &mut tree));
+        assert_eq!(tabs(&tree, root), ["b"]);
+    }
+
+    /// Closing the last tab of the focused leaf empties the tree, which mustn't be looked into afterwards.
+    #[test]
+    fn close_the_last_tab_of_the_focused_leaf() {
+        let mut tree = Tree::new(vec!["a".to_owned()]);
+        tree.set_focused_node(tree.root().unwrap());
+        let mut harness = DockHarness::new(vec2(800.0, 600.0));
+        let show = |ctx: &Context, tree: &mut Tree<String>| {
+            DockArea::new(tree).show(ctx, &mut Viewer);
+        };
+        harness.run(|ctx| show(ctx, &mut tree));
+
+        harness.key(Key::W, Modifiers::COMMAND);
+        harness.run(|ctx| show(ctx, &mut tree));
+        assert!(tree.is_empty());
+        assert_eq!(tree.focused_leaf(), None);
+        harness.run(|ctx| show(ctx, &mut tree));
+    }
+
+    #[test]
+    fn drag_separator() {
+        let mut tree = Tree::new(vec!["a".to_owned()]);
+        tree.split_below(tree.root().unwrap(), 0.5, vec!["b".to_owned()]);
+        let root = tree.root().unwrap();
+        let mut harness = DockHarness::new(vec2(800.0, 600.0));
+        let show = |ctx: &Context, tree: &mut Tree<String>| {
+            DockArea::new(tree).show(ctx, &mut Viewer);
+        };
+        harness.run(|ctx| show(ctx, &mut tree));
+
+        let from = harness.separator_center(&tree, root).unwrap();
+        harness.drag(from, from + vec2(0.0, 100.0), |ctx| show(ctx, &mut tree));
+        match tree[root] {
+            Node::Vertical { fraction, .. } => assert!(fraction > 0.6),
+            _ => panic!("the root should still be split"),
+        }
+    }
+
+    #[test]
+    fn resize_split() {
+        let mut tree = Tree::new(vec!["a".to_owned()]);
+        tree.split_below(tree.root().unwrap(), 0.5, vec!["b".to_owned()]);
+        let root = tree.root().unwrap();
+        let mut harness = DockHarness::new(vec2(800.0, 600.0));
+        let show = |ctx: &Context, tree: &mut Tree<String>| {
+            DockArea::new(tree).show(ctx, &mut Viewer);
+        };
+        harness.run(|ctx| show(ctx, &mut tree));
+
+        assert!(harness.resize_split(&mut tree, root, 0.7, show));
+        match tree[root] {
+            Node::Vertical { fraction, .. } => assert!((fraction - 0.7).abs() < 0.02),
+            _ => panic!("the root should still be split"),
+        }
+    }
+
+    /// Keyboard resizing stops where the layout keeps the children at their minimum size, and reports the fraction
+    /// the split really got.
+    #[test]
+    fn resize_mode_keeps_min_sizes() {
+        let mut tree = Tree::new(vec!["a".to_owned()]);
+        let [left, _] = tree.split_right(tree.root().unwrap(), 0.5, vec!["b".to_owned()]);
+        let root = tree.root().unwrap();
+        tree.set_focused_node(left);
+        let style = Style {
+            min_node_size: vec2(300.0, 0.0),
+            ..Style::default()
+        };
+        let mut harness = DockHarness::new(vec2(800.0, 600.0));
+        let mut resized = Vec::new();
+        let mut show = |ctx: &Context, tree: &mut Tree<String>| {
+            let events = DockArea::new(tree)
+                .style(style.clone())
+                .show(ctx, &mut Viewer);
+            resized.extend(events.into_iter().filter_map(|event| match event {
+                DockEvent::SplitResized { node, fraction, .. } => Some((node, fraction)),
+                _ => None,
+            }));
+        };
+        harness.run(|ctx| show(ctx, &mut tree));
+
+        let command_alt = Modifiers {
+            alt: true,
+            ..Modifiers::COMMAND
+        };
+        harness.key(Key::R, command_alt);
+        harness.run(|ctx| show(ctx, &mut tree));
+        for _ in 0..20 {
+            harness.key(Key::ArrowRight, Modifiers::NONE);
+            harness.run(|ctx| show(ctx, &mut tree));
+        }
+
+        let fraction = tree.fraction(root).unwrap();
+        let right = tree[tree.children(root).unwrap()[0]].rect().unwrap();
+        assert!(right.width() >= 300.0 - 1.0);
+        assert!(fraction > 0.5);
+        assert!(!resized.is_empty());
+        assert!(resized.iter().all(|&(node, _)| node == root));
+        assert_eq!(
+            resized.last().map(|&(_, fraction)| fraction),
+            Some(fraction)
+        );
+    }
+
+    /// The picture of a tab captured while it was active shows up once the pointer rests on its title.
+    #[test]
+    fn captured_tab_preview() {
+        let mut tree = Tree::new(vec!["a".to_owned(), "b".to_owned()]);
+        let root = tree.root().unwrap();
+        let style = Style {
+            capture_tab_previews: true,
+            ..Style::default()
+        };
+        let mut harness = DockHarness::new(vec2(800.0, 600.0));
+        let mut show = |ctx: &Context, tree: &mut Tree<String>| {
+            DockArea::new(tree)
+                .style(style.clone())
+                .show(ctx, &mut Viewer);
+        };
+        harness.run(|ctx| show(ctx, &mut tree));
+        assert!(harness.activate_tab(&mut tree, root, TabIndex(1), &mut show));
+
+        let title = tree.tab_title_rect(root, TabIndex(0)).unwrap();
+        harness.move_pointer(title.center());
+        harness.run(|ctx| show(ctx, &mut tree));
+        let output = harness.run(|ctx| show(ctx, &mut tree));
+        let previews = output
+            .shapes
+            .iter()
+            .filter(|clipped| match &clipped.1 {
+                Shape::Mesh(mesh) => mesh.texture_id != TextureId::default(),
+                _ => false,
+            })
+            .count();
+        assert_eq!(previews, 1);
+    }
+
+    #[test]
+    fn close_other_tabs_keeps_other_empty_leaves() {
+        let tabs_of = |names: &[&str]| names.iter().map(|&name| name.to_owned()).collect();
+        let mut tree = Tree::new(tabs_of(&["a", "b", "c"]));
+        let [leaf, empty] = tree.split_right(tree.root().unwrap(), 0.5, Vec::new());
+
+        let closed = tree.close_other_tabs(leaf, TabIndex(1), &mut Viewer);
+        let closed: Vec<_> = closed.into_iter().map(|(_, tab, _)| tab).collect();
+        assert_eq!(closed, [TabIndex(0), TabIndex(2)]);
+        assert_eq!(tabs(&tree, leaf), ["b"]);
+        assert_eq!(tree.parent(empty), tree.root());
+
+        tree.close_all_tabs(&mut Viewer);
+        assert_eq!(tree.root(), Some(empty));
+    }
+
+    /// What the dock remembers about tabs is forgotten once they're gone.
+    #[test]
+    fn state_forgets_removed_tabs() {
+        let mut tree = Tree::new(vec!["a".to_owned()]);
+        tree.split_below(tree.root().unwrap(), 0.5, vec!["b".to_owned()]);
+        let mut harness = DockHarness::new(vec2(800.0, 600.0));
+        let show = |ctx: &Context, tree: &mut Tree<String>| {
+            DockArea::new(tree).show(ctx, &mut Viewer);
+        };
+        let state = |harness: &DockHarness| {
+            harness
+                .ctx()
+                .data()
+                .get_temp::<State>(harness.dock_id)
+                .unwrap()
+        };
+        harness.run(|ctx| show(ctx, &mut tree));
+        let b = harness.dock_id.with(Id::new("b"));
+        assert!(state(&harness).tab_widths.contains_key(&b));
+        assert_eq!(state(&harness).active_tabs.len(), 2);
+        assert_eq!(state(&harness).tab_bar_scroll.len(), 2);
+
+        let (node, tab) = tree.find_tab(&"b".to_owned()).unwrap();
+        tree.remove_tab(node, tab);
+        harness.run(|ctx| show(ctx, &mut tree));
+        let state = state(&harness);
+        assert!(!state.tab_widths.contains_key(&b));
+        assert_eq!(state.tab_widths.len(), 1);
+        assert_eq!(state.active_tabs.len(), 1);
+        assert_eq!(state.tab_bar_scroll.len(), 1);
+    }
+
+    /// Tabs which don't fit wrap onto more rows, taking room from the body of their leaf.
+    #[test]
+    fn wrapping_tab_bar_grows_taller() {
+        let names = (0..8)
+            .map(|index| format!("tab number {}", index))
+            .collect();
+        let mut tree = Tree::new(names);
+        let [leaf, other] = tree.split_below(tree.root().unwrap(), 0.5, vec!["b".to_owned()]);
+        let run = |overflow, tree: &mut Tree<String>| {
+            let style = Style {
+                tab_overflow: overflow,
+                ..Style::default()
+            };
+            let mut harness = DockHarness::new(vec2(300.0, 600.0)).style(style.clone());
+            // The rows a tab bar needs are only known once it was shown.
+            for _ in 0..2 {
+                harness.run(|ctx| {
+                    DockArea::new(tree)
+                        .style(style.clone())
+                        .show(ctx, &mut Viewer);
+                });
+            }
+            harness
+        };
+
+        let harness = run(crate::TabOverflow::Scroll, &mut tree);
+        let scrolled = tree[leaf].rect().unwrap();
+        let scrolled_body = harness.body_rect(&tree, leaf).unwrap();
+
+        let mut harness = run(crate::TabOverflow::Wrap, &mut tree);
+        let wrapped = tree[leaf].rect().unwrap();
+        let wrapped_body = harness.body_rect(&tree, leaf).unwrap();
+        assert_eq!(wrapped, scrolled);
+        assert!(wrapped_body.height() < scrolled_body.height());
+        assert!(
+            wrapped.height() - wrapped_body.height() > scrolled.height() - scrolled_body.height()
+        );
+
+        let rows = |harness: &DockHarness| {
+            let state = harness
+                .ctx()
+                .data()
+                .get_temp::<State>(harness.dock_id)
+                .unwrap();
+            state.tab_bar_rows.clone()
+        };
+        assert_eq!(rows(&harness).len(), 2);
+        assert!(rows(&harness).values().any(|&rows| rows > 1));
+
+        // What the dock remembers about the rows is forgotten with the leaf.
+        tree.remove_leaf(other);
+        let style = Style {
+            tab_overflow: crate::TabOverflow::Wrap,
+            ..Style::default()
+        };
+        harness.run(|ctx| {
+            DockArea::new(&mut tree).style(style).show(ctx, &mut Viewer);
+        });
+        assert_eq!(rows(&harness).len(), 1);
+    }
+
+    #[test]
+    fn after_pass_hook() {
+        let mut state = crate::DockState::new(vec!["a".to_owned()]);
+        let window = state.add_window(vec!["b".to_owned()], Default::default());
+        let mut harness = DockHarness::new(vec2(800.0, 600.0));
+        let mut passes = Vec::new();
+        harness.run(|ctx| {
+            DockArea::from_state(&mut state)
+                .after_pass(|_, pass, surface, tree| {
+                    assert!(tree.dock_rect().is_some());
+                    passes.push((surface, pass));
+                })
+                .show(ctx, &mut Viewer);
+        });
+
+        let order = [
+            DockPass::Layout,
+            DockPass::Separators,
+            DockPass::TabBars,
+            DockPass::Contents,
+            DockPass::DragOverlay,
+        ];
+        for surface in [window, crate::SurfaceIndex::main()] {
+            let shown = passes
+                .iter()
+                .filter(|(shown, _)| *shown == surface)
+                .map(|&(_, pass)| pass);
+            assert!(shown.eq(order), "{:?}", passes);
+        }
+    }
+
+    /// Closing a window returns its tabs to its home leaf, or to the focused leaf if it has none or it was removed.
+    #[test]
+    fn closing_a_window_returns_its_tabs() {
+        for has_home in [Some(true), Some(false), None] {
+            let mut state = crate::DockState::new(vec!["a".to_owned()]);
+            let tree = state.main_surface_mut();
+            let [a, b] = tree.split_right(tree.root().unwrap(), 0.5, vec!["b".to_owned()]);
+            let [_, c] = tree.split_below(b, 0.5, vec!["c".to_owned()]);
+            let home = match has_home {
+                Some(true) => tree.node_id(b),
+                Some(false) => {
+                    let removed = tree.node_id(c);
+                    tree.remove_leaf(c);
+                    removed
+                }
+                None => None,
+            };
+            tree.set_focused_node(a);
+            let window = state.add_window(
+                vec!["w".to_owned()],
+                crate::WindowState {
+                    home,
+                    ..Default::default()
+                },
+            );
+
+            let mut harness = DockHarness::new(vec2(800.0, 600.0));
+            let show = |ctx: &Context, state: &mut crate::DockState<String>| {
+                DockArea::from_state(state).show(ctx, &mut Viewer);
+            };
+            harness.run(|ctx| show(ctx, &mut state));
+            // The close button of the title bar.
+            let rect = state.window_state(window).unwrap().rect;
+            harness.click(pos2(rect.max.x - 16.0, rect.min.y + 16.0), |ctx| {
+                show(ctx, &mut state)
+            });
+
+            assert!(state.window_state(window).is_none());
+            let (node, _) = state.main_surface().find_tab(&"w".to_owned()).unwrap();
+            let expected = if has_home == Some(true) { b } else { a };
+            assert_eq!(node, expected, "{:?}", has_home);
+        }
+    }
+}
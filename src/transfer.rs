@@ -0,0 +1,214 @@
+use egui::*;
+
+use crate::{DropTarget, NodeIndex, SurfaceIndex, TabIndex, Tree};
+
+/// A tab being dragged out of a dock area, kept in the egui memory so other dock areas can preview dropping it.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct DockDrag {
+    /// Id of the dock area the tab is dragged from.
+    pub dock: Id,
+    pub node: NodeIndex,
+    pub tab: TabIndex,
+}
+
+/// A tab dropped onto another dock area than its own, waiting to be moved there by [`transfer_tabs`].
+#[derive(Clone, Copy, Debug)]
+struct DockDrop {
+    from: Id,
+    src: (NodeIndex, TabIndex),
+    to: Id,
+    dst: NodeIndex,
+    target: DropTarget,
+}
+
+fn drag_id() -> Id {
+    Id::new("egui_dock::DockDrag")
+}
+
+fn drop_id() -> Id {
+    Id::new("egui_dock::DockDrop")
+}
+
+/// Publishes the tab the dock area `dock` has left over of a drag, which is one dragged outside of it, or withdraws
+/// the one it published before if there's none.
+///
+/// Only tabs of the main surface are shared, tabs of floating windows stay within their dock area.
+pub(crate) fn share_drag(
+    ctx: &Context,
+    dock: Id,
+    src: Option<(SurfaceIndex, NodeIndex, TabIndex)>,
+) {
+    let mut data = ctx.data();
+    match src.filter(|(surface, _, _)| surface.is_main()) {
+        Some((_, node, tab)) => data.insert_temp(drag_id(), DockDrag { dock, node, tab }),
+        None => {
+            let published = data.get_temp::<DockDrag>(drag_id());
+            if published.map_or(false, |drag| drag.dock == dock) {
+                data.remove::<DockDrag>(drag_id());
+            }
+        }
+    }
+}
+
+/// The tab being dragged out of some dock area, if any.
+pub(crate) fn dragged_tab(ctx: &Context) -> Option<DockDrag> {
+    ctx.data().get_temp(drag_id())
+}
+
+/// Records that `drag` was dropped onto `target` of the leaf `dst` of the dock area `to`, see [`transfer_tabs`].
+pub(crate) fn drop_tab(ctx: &Context, drag: DockDrag, to: Id, dst: NodeIndex, target: DropTarget) {
+    let mut data = ctx.data();
+    data.remove::<DockDrag>(drag_id());
+    data.insert_temp(
+        drop_id(),
+        DockDrop {
+            from: drag.dock,
+            src: (drag.node, drag.tab),
+            to,
+            dst,
+            target,
+        },
+    );
+}
+
+/// Moves a tab dragged from one dock area and dropped onto another between their trees.
+///
+/// Tabs can be dragged between the main surfaces of independent [`DockArea`](crate::DockArea)s, with the same drop
+/// previews as within a single one. Each dock area only owns its own tree though, so the tab is moved once both have
+/// been shown, by calling this with the id and tree of every dock area it could move between, once per frame:
+///
+/// ```rust
+/// # use egui_dock::{egui, transfer_tabs, DockArea, Tree};
+/// # struct Viewer;
+/// # impl egui_dock::TabViewer for Viewer {
+/// #     type Tab = String;
+/// #     fn ui(&mut self, _ui: &mut egui::Ui, _tab: &mut String) {}
+/// #     fn title(&mut self, tab: &mut String) -> egui::WidgetText { tab.as_str().into() }
+/// # }
+/// # fn ui(ctx: &egui::Context, tools: &mut Tree<String>, documents: &mut Tree<String>) {
+/// let tools_id = egui::Id::new("tools");
+/// let documents_id = egui::Id::new("documents");
+///
+/// egui::SidePanel::left("tools").show(ctx, |ui| {
+///     DockArea::new(tools).id(tools_id).show_inside(ui, &mut Viewer);
+/// });
+/// egui::CentralPanel::default().show(ctx, |ui| {
+///     DockArea::new(documents)
+///         .id(documents_id)
+///         .show_inside(ui, &mut Viewer);
+/// });
+///
+/// transfer_tabs(ctx, &mut [(tools_id, tools), (documents_id, documents)]);
+/// # }
+/// ```
+///
+/// Returns `true` if a tab was moved, `false` otherwise.
+pub fn transfer_tabs<Tab>(ctx: &Context, docks: &mut [(Id, &mut Tree<Tab>)]) -> bool {
+    let drop = match ctx.data().get_temp::<DockDrop>(drop_id()) {
+        Some(drop) => drop,
+        None => return false,
+    };
+    let src = docks.iter().position(|(id, _)| *id == drop.from);
+    let dst = docks.iter().position(|(id, _)| *id == drop.to);
+    let (src, dst) = match (src, dst) {
+        (Some(src), Some(dst)) if src != dst => (src, dst),
+        _ => return false,
+    };
+    ctx.data().remove::<DockDrop>(drop_id());
+
+    // Removed nodes leave their slot free, so nodes can have indices past the length of the tree.
+    let is_leaf =
+        |tree: &Tree<Tab>, node: NodeIndex| tree.node_id(node).is_some() && tree[node].is_leaf();
+
+    let (node, tab_index) = drop.src;
+    let tree = &mut *docks[src].1;
+    if !is_leaf(tree, node) || tab_index.0 >= tree[node].tabs_count() {
+        return false;
+    }
+    tree.record_history();
+    let tab = match tree.remove_tab(node, tab_index) {
+        Some(tab) => tab,
+        None => return false,
+    };
+
+    let tree = &mut *docks[dst].1;
+    tree.record_history();
+    if is_leaf(tree, drop.dst) {
+        tree.drop_new_tab(tab, drop.dst, drop.target);
+    } else {
+        tree.push_to_focused_leaf(tab);
+    }
+
+    ctx.request_repaint();
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tree::EDGE_SPLIT_SHARE, Split};
+
+    #[test]
+    fn transfer_to_leaf_past_the_length_of_the_tree() {
+        let ctx = Context::default();
+        let (tools_id, documents_id) = (Id::new("tools"), Id::new("documents"));
+        let mut tools = Tree::new(vec!["files", "outline"]);
+        let mut documents = Tree::new(vec!["main.rs"]);
+        let [main, lib] = documents.split_right(documents.root().unwrap(), 0.5, vec!["lib.rs"]);
+        let [lib, mod_rs] = documents.split_below(lib, 0.5, vec!["mod.rs"]);
+        documents.remove_leaf(main);
+        assert!(mod_rs.0 >= documents.len());
+
+        let drag = DockDrag {
+            dock: tools_id,
+            node: tools.root().unwrap(),
+            tab: TabIndex(1),
+        };
+        drop_tab(
+            &ctx,
+            drag,
+            documents_id,
+            mod_rs,
+            DropTarget::Tabs(Some(TabIndex(0))),
+        );
+        let mut docks = [(tools_id, &mut tools), (documents_id, &mut documents)];
+        assert!(transfer_tabs(&ctx, &mut docks));
+
+        assert_eq!(documents.find_tab(&"outline"), Some((mod_rs, TabIndex(0))));
+        assert_eq!(
+            documents.find_tab(&"lib.rs").map(|(node, _)| node),
+            Some(lib)
+        );
+        assert_eq!(tools.tabs().count(), 1);
+    }
+
+    #[test]
+    fn transfer_to_edge() {
+        let ctx = Context::default();
+        let (tools_id, documents_id) = (Id::new("tools"), Id::new("documents"));
+        let mut tools = Tree::new(vec!["files", "outline"]);
+        let mut documents = Tree::new(vec!["main.rs"]);
+        let [main, _] = documents.split_right(documents.root().unwrap(), 0.5, vec!["lib.rs"]);
+
+        let drag = DockDrag {
+            dock: tools_id,
+            node: tools.root().unwrap(),
+            tab: TabIndex(0),
+        };
+        drop_tab(
+            &ctx,
+            drag,
+            documents_id,
+            main,
+            DropTarget::Edge(Split::Left),
+        );
+        let mut docks = [(tools_id, &mut tools), (documents_id, &mut documents)];
+        assert!(transfer_tabs(&ctx, &mut docks));
+
+        // Docked beside the whole tree, taking the share of the edge drop zones.
+        let root = documents.root().unwrap();
+        let (files, _) = documents.find_tab(&"files").unwrap();
+        assert_eq!(documents.parent(files), Some(root));
+        assert_eq!(documents.fraction(root), Some(EDGE_SPLIT_SHARE));
+    }
+}
@@ -1,5 +1,12 @@
+use std::collections::VecDeque;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
 use egui::*;
 
+use crate::utils::*;
+use crate::{Style, TabViewer};
+
 /// Identifies a tab within a [`Node`].
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Ord, PartialOrd)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
@@ -15,6 +22,7 @@ impl From<usize> for TabIndex {
 // ----------------------------------------------------------------------------
 
 /// Represents an abstract node of a `Tree`.
+#[derive(Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub enum Node<Tab> {
     /// Empty node
@@ -65,6 +73,16 @@ impl<Tab> Node<Tab> {
         }
     }
 
+    /// Returns the area occupied by the node as of the last layout, or `None` if the node is `Empty`.
+    pub fn rect(&self) -> Option<Rect> {
+        match self {
+            Self::Empty => None,
+            Self::Leaf { rect, .. }
+            | Self::Vertical { rect, .. }
+            | Self::Horizontal { rect, .. } => Some(*rect),
+        }
+    }
+
     /// Returns `true` if the node is a `Empty`, `false` otherwise.
     pub const fn is_empty(&self) -> bool {
         matches!(self, Self::Empty)
@@ -151,11 +169,72 @@ impl<Tab> Node<Tab> {
             _ => Default::default(),
         }
     }
+
+    /// Returns a copy of the node with `f` applied to each of its tabs.
+    fn map_tabs<NewTab>(&self, f: &mut impl FnMut(&Tab) -> NewTab) -> Node<NewTab> {
+        match self {
+            Node::Empty => Node::Empty,
+            Node::Leaf {
+                rect,
+                viewport,
+                tabs,
+                active,
+            } => Node::Leaf {
+                rect: *rect,
+                viewport: *viewport,
+                tabs: tabs.iter().map(f).collect(),
+                active: *active,
+            },
+            Node::Vertical { rect, fraction } => Node::Vertical {
+                rect: *rect,
+                fraction: *fraction,
+            },
+            Node::Horizontal { rect, fraction } => Node::Horizontal {
+                rect: *rect,
+                fraction: *fraction,
+            },
+        }
+    }
+
+    /// Turns each tab of the node into the one `f` returns, dropping the tabs it returns `None` for.
+    fn filter_map_tabs<NewTab>(self, f: &mut impl FnMut(Tab) -> Option<NewTab>) -> Node<NewTab> {
+        match self {
+            Node::Empty => Node::Empty,
+            Node::Leaf {
+                rect,
+                viewport,
+                tabs,
+                active,
+            } => {
+                let mut kept_before_active = 0;
+                let mut new_tabs = Vec::with_capacity(tabs.len());
+                for (index, tab) in tabs.into_iter().enumerate() {
+                    if let Some(tab) = f(tab) {
+                        if index < active.0 {
+                            kept_before_active += 1;
+                        }
+                        new_tabs.push(tab);
+                    }
+                }
+                Node::Leaf {
+                    rect,
+                    viewport,
+                    active: TabIndex(kept_before_active.min(new_tabs.len().saturating_sub(1))),
+                    tabs: new_tabs,
+                }
+            }
+            Node::Vertical { rect, fraction } => Node::Vertical { rect, fraction },
+            Node::Horizontal { rect, fraction } => Node::Horizontal { rect, fraction },
+        }
+    }
 }
 
 // ----------------------------------------------------------------------------
 
 /// Wrapper around indices to the collection of nodes inside a `Tree`.
+///
+/// Each node keeps its index for as long as it's in the tree, other nodes being added or removed don't move it.
+/// Once the node is removed its index may be given to a node added later, see [`NodeId`] to tell them apart.
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 pub struct NodeIndex(pub usize);
@@ -168,22 +247,31 @@ impl From<usize> for NodeIndex {
 }
 
 impl NodeIndex {
-    /// Returns the index of the root node.
+    /// Returns the index of the root node of a tree which was never split.
+    #[deprecated(note = "the root node gets a new index when it's split, use `Tree::root` instead")]
     pub const fn root() -> Self {
         Self(0)
     }
 
-    /// Returns the index of the node to the left of the current one.
+    /// Returns the index the left child of the current node had when nodes were stored by their position.
+    #[deprecated(
+        note = "nodes are no longer stored by their position, use `Tree::children` instead"
+    )]
     pub const fn left(self) -> Self {
         Self(self.0 * 2 + 1)
     }
 
-    /// Returns the index of the node to the right of the current one.
+    /// Returns the index the right child of the current node had when nodes were stored by their position.
+    #[deprecated(
+        note = "nodes are no longer stored by their position, use `Tree::children` instead"
+    )]
     pub const fn right(self) -> Self {
         Self(self.0 * 2 + 2)
     }
 
-    /// Returns the index of the parent node or `None` if current node is the root.
+    /// Returns the index the parent of the current node had when nodes were stored by their position, or `None` for
+    /// the root.
+    #[deprecated(note = "nodes are no longer stored by their position, use `Tree::parent` instead")]
     pub const fn parent(self) -> Option<Self> {
         if self.0 > 0 {
             Some(Self((self.0 - 1) / 2))
@@ -192,47 +280,134 @@ impl NodeIndex {
         }
     }
 
-    /// Returns the number of nodes leading from the root to the current node, including `self`.
+    /// Returns the number of nodes which led from the root to the current node when nodes were stored by their
+    /// position, including `self`.
+    #[deprecated(
+        note = "nodes are no longer stored by their position, walk up with `Tree::parent` instead"
+    )]
     pub const fn level(self) -> usize {
         (usize::BITS - (self.0 + 1).leading_zeros()) as usize
     }
 
-    /// Returns true if current node is the left node of its parent, false otherwise.
+    /// Returns true if the current node was the left node of its parent when nodes were stored by their position.
+    #[deprecated(
+        note = "nodes are no longer stored by their position, use `Tree::children` instead"
+    )]
     pub const fn is_left(self) -> bool {
         self.0 % 2 != 0
     }
 
-    /// Returns true if current node is the right node of its parent, false otherwise.
+    /// Returns true if the current node was the right node of its parent when nodes were stored by their position.
+    #[deprecated(
+        note = "nodes are no longer stored by their position, use `Tree::children` instead"
+    )]
     pub const fn is_right(self) -> bool {
         self.0 % 2 == 0
     }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Handle to a node of a `Tree` which never refers to another node.
+///
+/// Unlike a [`NodeIndex`], which is given to a new node once the node at it is removed, a `NodeId` stops referring
+/// to anything when its node is removed. Look up the index of the node with [`Tree::node_index`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct NodeId {
+    index: usize,
+    generation: u64,
+}
+
+/// Where a node is in the tree, stored at the same index as the node.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+struct Slot {
+    parent: Option<NodeIndex>,
+    /// Left and right child of a parent node.
+    children: Option<[NodeIndex; 2]>,
+    /// Tells the node apart from the ones stored at the same index before it.
+    generation: u64,
+}
+
+/// Values kept for a few nodes of a [`Tree`], like the minimum sizes set with [`Tree::set_min_size`].
+///
+/// Nodes are looked up by their [`NodeId`], so values don't move on to nodes added in the place of removed ones.
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(transparent)
+)]
+struct NodeMap<T>(Vec<(NodeId, T)>);
+
+impl<T> Default for NodeMap<T> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+impl<T> NodeMap<T> {
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn get(&self, id: NodeId) -> Option<&T> {
+        self.0
+            .iter()
+            .find(|(node, _)| *node == id)
+            .map(|(_, value)| value)
+    }
+
+    fn get_mut(&mut self, id: NodeId) -> Option<&mut T> {
+        self.0
+            .iter_mut()
+            .find(|(node, _)| *node == id)
+            .map(|(_, value)| value)
+    }
+
+    /// Sets the value of the node `id`, or removes it if `value` is `None`. Returns the value it had.
+    fn set(&mut self, id: NodeId, value: Option<T>) -> Option<T> {
+        let previous = self.remove(id);
+        if let Some(value) = value {
+            self.0.push((id, value));
+        }
+        previous
+    }
 
-    const fn children_at(self, level: usize) -> std::ops::Range<usize> {
-        let base = 1 << level;
-        let s = (self.0 + 1) * base - 1;
-        let e = (self.0 + 2) * base - 1;
-        s..e
+    fn remove(&mut self, id: NodeId) -> Option<T> {
+        let position = self.0.iter().position(|(node, _)| *node == id)?;
+        Some(self.0.remove(position).1)
     }
 
-    const fn children_left(self, level: usize) -> std::ops::Range<usize> {
-        let base = 1 << level;
-        let s = (self.0 + 1) * base - 1;
-        let e = (self.0 + 1) * base + base / 2 - 1;
-        s..e
+    /// Keeps the values of the nodes `keep` returns `true` for.
+    fn retain(&mut self, mut keep: impl FnMut(NodeId) -> bool) {
+        self.0.retain(|(node, _)| keep(*node));
     }
+}
+
+// ----------------------------------------------------------------------------
 
-    const fn children_right(self, level: usize) -> std::ops::Range<usize> {
-        let base = 1 << level;
-        let s = (self.0 + 1) * base + base / 2 - 1;
-        let e = (self.0 + 2) * base - 1;
-        s..e
+/// Callbacks for the nodes of a [`Tree`] walked depth-first with [`Tree::visit`].
+///
+/// Children are visited in the order they're shown in, the left-most or top-most one first.
+pub trait NodeVisitor<Tab> {
+    /// Called for a `Horizontal` or `Vertical` node at `depth` before its children. Return `false` to skip them.
+    fn split(&mut self, _node_index: NodeIndex, _node: &Node<Tab>, _depth: usize) -> bool {
+        true
     }
+
+    /// Called for a `Horizontal` or `Vertical` node after its children, unless [`NodeVisitor::split`] skipped them.
+    fn end_split(&mut self, _node_index: NodeIndex, _node: &Node<Tab>, _depth: usize) {}
+
+    /// Called for a leaf at `depth`.
+    fn leaf(&mut self, node_index: NodeIndex, tabs: &[Tab], active: TabIndex, depth: usize);
 }
 
 // ----------------------------------------------------------------------------
 
 /// Direction in which a new node is created relatively to the parent node at which the split occurs.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Split {
     Left,
     Right,
@@ -243,10 +418,69 @@ pub enum Split {
 // ----------------------------------------------------------------------------
 
 /// Binary tree representing the relationships between `Node`s.
-#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+///
+/// A `Tree` is `Send` and `Sync` whenever `Tab` is, so it can be moved to other threads or shared behind a `Mutex`.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(remote = "Self"),
+    serde(bound(
+        serialize = "Tab: serde::Serialize",
+        deserialize = "Tab: serde::Deserialize<'de>"
+    ))
+)]
 pub struct Tree<Tab> {
+    /// The nodes, with `Node::Empty` in the slots no node is using.
     tree: Vec<Node<Tab>>,
     focused_node: Option<NodeIndex>,
+    /// Links between the nodes, missing from trees serialized back when nodes were stored by their position.
+    #[cfg_attr(feature = "serde", serde(default))]
+    slots: Vec<Slot>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    root: Option<NodeIndex>,
+    /// Generation of the next node added, see [`NodeId`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    next_generation: u64,
+    /// Slots no node is using, given to the next nodes added.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    free: Vec<NodeIndex>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    history: Option<Box<History<Tab>>>,
+    /// Ids of the tabs in the order they were last activated, most recent first.
+    #[cfg_attr(feature = "serde", serde(default))]
+    recent: Vec<Id>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    hidden: Vec<HiddenTab<Tab>>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    zen: Option<Box<Zen<Tab>>>,
+    /// Pairs of parent nodes whose separators are kept aligned.
+    #[cfg_attr(feature = "serde", serde(default))]
+    links: Vec<[NodeId; 2]>,
+    /// Leaves showing their tabs as child windows, see [`Tree::set_mdi`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    mdi: Vec<NodeId>,
+    /// Parent nodes resized with another policy than [`ResizePolicy::Proportional`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    resize_policies: NodeMap<ResizePolicy>,
+    /// Nodes which can't be made smaller than a size of their own, see [`Tree::set_min_size`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    min_sizes: NodeMap<Vec2>,
+    /// Leaves overriding [`Style::hide_single_tab_bars`], see [`Tree::set_hide_single_tab_bar`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    single_tab_bars: NodeMap<bool>,
+    /// Leaves showing their tab bar elsewhere than [`Style::tab_bar_position`], see [`Tree::set_tab_bar_position`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    tab_bar_positions: NodeMap<TabBarPosition>,
+    /// Leaves collapsed to their tab bar, with the fraction their parent had before, see [`Tree::set_collapsed`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    collapsed: NodeMap<f32>,
+    /// Nodes shown with a style of their own, see [`Tree::set_node_style`].
+    #[cfg_attr(feature = "serde", serde(default))]
+    node_styles: NodeMap<Arc<Style>>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    tab_insert_policy: TabInsertPolicy,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    geometry: Geometry,
 }
 
 impl<Tab> Default for Tree<Tab> {
@@ -254,10 +488,262 @@ impl<Tab> Default for Tree<Tab> {
         Self {
             tree: Default::default(),
             focused_node: Default::default(),
+            slots: Default::default(),
+            root: None,
+            next_generation: 0,
+            free: Default::default(),
+            history: None,
+            recent: Vec::new(),
+            hidden: Vec::new(),
+            zen: None,
+            links: Vec::new(),
+            mdi: Vec::new(),
+            resize_policies: NodeMap::default(),
+            min_sizes: NodeMap::default(),
+            single_tab_bars: NodeMap::default(),
+            tab_bar_positions: NodeMap::default(),
+            collapsed: NodeMap::default(),
+            node_styles: NodeMap::default(),
+            tab_insert_policy: TabInsertPolicy::default(),
+            geometry: Geometry::default(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<Tab: serde::Serialize> serde::Serialize for Tree<Tab> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Tree::serialize(self, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, Tab: serde::Deserialize<'de>> serde::Deserialize<'de> for Tree<Tab> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut tree = Tree::deserialize(deserializer)?;
+        tree.link_slots();
+        Ok(tree)
+    }
+}
+
+/// What [`Tree::enter_zen_mode`] leaves of the layout.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum ZenMode {
+    /// Only the focused leaf, tab bar included.
+    FocusedLeaf,
+    /// Only the active tab of the focused leaf, taking up the whole dock area without a tab bar.
+    ActiveTab,
+}
+
+/// Where [`Tree::drop_tab`] puts a tab, like the places a dragged tab can be dropped on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DropTarget {
+    /// Into the tab bar of the leaf, before the tab at the index (as it was before the tab was taken out), or after
+    /// the last tab.
+    Tabs(Option<TabIndex>),
+    /// Into a new leaf split off the leaf, on the given side of it.
+    Split(Split),
+    /// Into a new leaf along the given edge of the whole tree, see
+    /// [`Style::show_edge_drop_zones`](crate::Style::show_edge_drop_zones).
+    Edge(Split),
+}
+
+/// Share of the dock area taken by leaves docked along one of its edges, see [`DropTarget::Edge`].
+pub(crate) const EDGE_SPLIT_SHARE: f32 = 0.25;
+
+/// How a parent node shares out a change of its size between its children, see [`Tree::set_resize_policy`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum ResizePolicy {
+    /// Both children keep their share of the node.
+    #[default]
+    Proportional,
+    /// The left-most or top-most child keeps its size in points, the other one takes the change.
+    KeepFirst,
+    /// The right-most or bottom-most child keeps its size in points, the other one takes the change.
+    KeepSecond,
+}
+
+/// Which edge of its leaf a tab bar is shown along, see [`Style::tab_bar_position`] and
+/// [`Tree::set_tab_bar_position`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum TabBarPosition {
+    /// Above the body, like tabs of documents.
+    #[default]
+    Top,
+    /// Below the body, like tabs of output and console panels.
+    Bottom,
+    /// Left of the body, with the tabs stacked top to bottom, like a list of open files.
+    Left,
+    /// Right of the body, with the tabs stacked top to bottom.
+    Right,
+}
+
+impl TabBarPosition {
+    /// Whether the tab bar runs along the left or right edge, stacking its tabs top to bottom.
+    pub(crate) fn is_vertical(self) -> bool {
+        matches!(self, TabBarPosition::Left | TabBarPosition::Right)
+    }
+
+    /// Splits the rect of a leaf into its tab bar, reaching `height` points into the leaf from its edge, and the rest
+    /// of it. For vertical tab bars `height` is their width.
+    pub(crate) fn split(self, rect: Rect, height: f32) -> (Rect, Rect) {
+        match self {
+            TabBarPosition::Top => {
+                let y = rect.min.y + height;
+                (
+                    rect.intersect(Rect::everything_above(y)),
+                    rect.intersect(Rect::everything_below(y)),
+                )
+            }
+            TabBarPosition::Bottom => {
+                let y = rect.max.y - height;
+                (
+                    rect.intersect(Rect::everything_below(y)),
+                    rect.intersect(Rect::everything_above(y)),
+                )
+            }
+            TabBarPosition::Left => {
+                let x = rect.min.x + height;
+                (
+                    rect.intersect(Rect::everything_left_of(x)),
+                    rect.intersect(Rect::everything_right_of(x)),
+                )
+            }
+            TabBarPosition::Right => {
+                let x = rect.max.x - height;
+                (
+                    rect.intersect(Rect::everything_right_of(x)),
+                    rect.intersect(Rect::everything_left_of(x)),
+                )
+            }
+        }
+    }
+}
+
+/// Where [`Tree::push_to_focused_leaf`] and [`Tree::push_to_first_leaf`] put new tabs in their leaf, see
+/// [`Tree::set_tab_insert_policy`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub enum TabInsertPolicy {
+    /// After the last tab.
+    #[default]
+    Append,
+    /// Right after the active tab, like editors opening a document next to the current one.
+    AfterActive,
+    /// Right before the active tab.
+    BeforeActive,
+}
+
+impl TabInsertPolicy {
+    /// Inserts `tab` into `tabs`, making it the `active` one if `activate` is set.
+    fn insert<Tab>(self, tabs: &mut Vec<Tab>, active: &mut TabIndex, tab: Tab, activate: bool) {
+        let index = match self {
+            _ if tabs.is_empty() => 0,
+            Self::Append => tabs.len(),
+            Self::AfterActive => (active.0 + 1).min(tabs.len()),
+            Self::BeforeActive => active.0.min(tabs.len()),
+        };
+        tabs.insert(index, tab);
+        if activate || tabs.len() == 1 {
+            *active = TabIndex(index);
+        } else if index <= active.0 {
+            active.0 += 1;
         }
     }
 }
 
+/// Where [`Tree::push_to`] puts a tab.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TabDestination {
+    /// The focused leaf, like [`Tree::push_to_focused_leaf`].
+    FocusedLeaf,
+    /// The leaf at the given index, or the focused leaf if there's no leaf there.
+    Node(NodeIndex),
+    /// The leaf next to the focused leaf on the given side, like a documentation panel opened beside the code. If
+    /// there's none, the focused leaf is split and keeps the given fraction of its area, like with [`Tree::split`].
+    Beside(Split, f32),
+    /// A new leaf along the given edge of the dock area, taking up the rest of the given fraction like with
+    /// [`Tree::split_root`].
+    Edge(Split, f32),
+}
+
+/// The layout put aside by [`Tree::enter_zen_mode`], with an empty node where the focused leaf was.
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+struct Zen<Tab> {
+    mode: ZenMode,
+    layout: Snapshot<Tab>,
+    leaf: NodeIndex,
+}
+
+/// A tab taken out of its tab bar with [`Tree::hide_tab`], together with where it was.
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+struct HiddenTab<Tab> {
+    tab: Tab,
+    leaf: Option<NodeId>,
+    index: TabIndex,
+}
+
+/// How many tab ids [`Tree::recent_tabs`] remembers.
+const RECENT_CAPACITY: usize = 128;
+
+/// Where a leaf is, as solved by [`Tree::solve_leaf_layout`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LeafLayout {
+    pub node: NodeIndex,
+    /// The whole area of the leaf.
+    pub rect: Rect,
+    /// The area of the body of the leaf, next to its tab bar.
+    pub body: Rect,
+}
+
+/// Where the tree was shown, on top of the rects stored in the nodes themselves.
+#[derive(Clone, Debug, Default)]
+struct Geometry {
+    rect: Option<Rect>,
+    tab_titles: Vec<(NodeIndex, TabIndex, Rect)>,
+    tab_bars: Vec<(NodeIndex, Rect)>,
+    dragged_tab: Option<(NodeIndex, TabIndex)>,
+    /// Scratch buffers for the rects the nodes had before they're laid out again, and how small each node can be.
+    previous_rects: Vec<Option<Rect>>,
+    min_sizes: Vec<Vec2>,
+    /// Scratch buffer for the order the nodes are laid out in, see [`Tree::top_down`].
+    order: Vec<NodeIndex>,
+    /// Sizes set with [`Tree::set_fixed_size`], turned into fractions once the nodes have rects.
+    fixed_sizes: NodeMap<f32>,
+    /// Leaves on their way to or from being collapsed, with how far they're collapsed and the fraction their parent
+    /// has while they're expanded, see [`Style::collapse_animation_time`].
+    collapsing: NodeMap<(f32, f32)>,
+}
+
+/// Layouts recorded before structural changes, for [`Tree::undo`] and [`Tree::redo`].
+struct History<Tab> {
+    capacity: usize,
+    undo: VecDeque<Snapshot<Tab>>,
+    redo: Vec<Snapshot<Tab>>,
+    /// Clones the tree. It's picked where `Tab: Clone` is known, so layouts can be recorded for any `Tab`.
+    snapshot: fn(&Tree<Tab>) -> Snapshot<Tab>,
+}
+
+/// The layout of a [`Tree`], restored by undoing a change or leaving zen mode.
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+struct Snapshot<Tab> {
+    tree: Vec<Node<Tab>>,
+    slots: Vec<Slot>,
+    root: Option<NodeIndex>,
+    free: Vec<NodeIndex>,
+    focused_node: Option<NodeIndex>,
+}
+
+/// Clones the layout and the tabs, but not the undo history.
+impl<Tab: Clone> Clone for Tree<Tab> {
+    fn clone(&self) -> Self {
+        self.map_tabs(Tab::clone)
+    }
+}
+
 impl<Tab> std::ops::Index<NodeIndex> for Tree<Tab> {
     type Output = Node<Tab>;
 
@@ -278,11 +764,485 @@ impl<Tab> Tree<Tab> {
     /// Creates a new `Tree` with given `Vec` of `Tab`s in its root node.
     #[inline(always)]
     pub fn new(tabs: Vec<Tab>) -> Self {
-        let root = Node::leaf_with(tabs);
-        Self {
-            tree: vec![root],
-            focused_node: None,
+        let mut tree = Self::default();
+        let root = tree.insert_node(Node::leaf_with(tabs), None);
+        tree.root = Some(root);
+        tree
+    }
+
+    /// Returns the [`NodeId`] of the node at `node_index`, or `None` if there's no node there.
+    pub fn node_id(&self, node_index: NodeIndex) -> Option<NodeId> {
+        let slot = self.slot(node_index)?;
+        Some(NodeId {
+            index: node_index.0,
+            generation: slot.generation,
+        })
+    }
+
+    /// Returns the index of the node identified by `node_id`, or `None` if it was removed.
+    pub fn node_index(&self, node_id: NodeId) -> Option<NodeIndex> {
+        let node_index = NodeIndex(node_id.index);
+        let slot = self.slot(node_index)?;
+        (slot.generation == node_id.generation).then_some(node_index)
+    }
+
+    /// Returns the index of the root node, or `None` if the tree is empty.
+    pub fn root(&self) -> Option<NodeIndex> {
+        self.root
+    }
+
+    /// Returns the index of the parent of the node at `node_index`, or `None` if it's the root or there's no node
+    /// there.
+    pub fn parent(&self, node_index: NodeIndex) -> Option<NodeIndex> {
+        self.slot(node_index)?.parent
+    }
+
+    /// Returns the indices of the left and right child of the node at `node_index`, or `None` if it isn't a parent
+    /// node.
+    ///
+    /// The left child of a `Horizontal` node is shown on the right of its separator, the left child of a `Vertical`
+    /// node above it.
+    pub fn children(&self, node_index: NodeIndex) -> Option<[NodeIndex; 2]> {
+        self.slot(node_index)?.children
+    }
+
+    /// Returns the area the tree was shown in the last time it was shown, or `None` if it hasn't been shown yet.
+    pub fn dock_rect(&self) -> Option<Rect> {
+        self.geometry.rect
+    }
+
+    /// Returns the area of the node at `node_index` as of the last layout, or `None` if there's no node there.
+    ///
+    /// Together with [`Tree::tab_title_rect`] and [`Tree::dock_rect`], this is useful for highlighting parts of the
+    /// dock or placing popups next to them once [`DockArea::show`](crate::DockArea::show) returned.
+    pub fn node_rect(&self, node_index: NodeIndex) -> Option<Rect> {
+        self.tree.get(node_index.0).and_then(Node::rect)
+    }
+
+    /// Returns the share of the parent node at `node_index` taken by its left-most or top-most child, or `None` if
+    /// there's no parent node there.
+    pub fn fraction(&self, node_index: NodeIndex) -> Option<f32> {
+        match self.tree.get(node_index.0) {
+            Some(Node::Horizontal { fraction, .. } | Node::Vertical { fraction, .. }) => {
+                Some(*fraction)
+            }
+            _ => None,
+        }
+    }
+
+    /// Gives the left-most or top-most child of the parent node at `node_index` a `fraction` (in range 0..=1) of it,
+    /// like dragging its separator would. Minimum sizes still apply once the tree is laid out, and
+    /// [`DockArea`](crate::DockArea) animates the separator there with [`Style::separator_animation_time`].
+    ///
+    /// Returns `false` if there's no parent node at `node_index`.
+    ///
+    /// ```rust
+    /// # use egui_dock::{NodeIndex, Tree};
+    /// let mut tree = Tree::new(vec!["files"]);
+    /// tree.split_right(tree.root().unwrap(), 0.2, vec!["editor"]);
+    ///
+    /// // Equalize every split.
+    /// for node_index in (0..tree.len()).map(NodeIndex) {
+    ///     tree.set_fraction(node_index, 0.5);
+    /// }
+    /// assert_eq!(tree.fraction(tree.root().unwrap()), Some(0.5));
+    /// ```
+    pub fn set_fraction(&mut self, node_index: NodeIndex, fraction: f32) -> bool {
+        match self.tree.get_mut(node_index.0) {
+            Some(Node::Horizontal { fraction: f, .. } | Node::Vertical { fraction: f, .. }) => {
+                *f = fraction.clamp(0.0, 1.0);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the area of the title of the tab at `tab_index` in the leaf at `node_index`, as of the last time it was
+    /// shown.
+    ///
+    /// Returns `None` if the title wasn't shown, like when it was scrolled out of its tab bar. Tabs closed, moved or
+    /// added since may make it refer to another tab until the tree is shown again.
+    pub fn tab_title_rect(&self, node_index: NodeIndex, tab_index: TabIndex) -> Option<Rect> {
+        self.geometry
+            .tab_titles
+            .iter()
+            .find(|(node, tab, _)| *node == node_index && *tab == tab_index)
+            .map(|(_, _, rect)| *rect)
+    }
+
+    /// Finds where something dropped at `pos` would land, like a file dragged in from an asset browser or the OS, the
+    /// same way a dragged tab would: a tab or the tab bar of a leaf, one of its sides, or an edge of the dock area.
+    /// The rects from the last time the tree was shown with `style` are used, including those of its tab bars, so
+    /// wrapped and hidden tab bars are taken into account.
+    ///
+    /// Returns the leaf, the target to pass to [`Tree::drop_new_tab`] along with it, and the area to highlight, which
+    /// [`DockPainter::drop_preview`](crate::DockPainter::drop_preview) paints like for dragged tabs. Returns `None` if
+    /// `pos` isn't over a leaf.
+    pub fn hit_test(&self, style: &Style, pos: Pos2) -> Option<(NodeIndex, DropTarget, Rect)> {
+        let (node_index, rect) =
+            self.tree
+                .iter()
+                .enumerate()
+                .find_map(|(index, node)| match node {
+                    Node::Leaf { rect, .. } if rect.contains(pos) => {
+                        Some((NodeIndex(index), *rect))
+                    }
+                    _ => None,
+                })?;
+
+        // Along the edges of the dock area things are docked beside the whole tree.
+        let edge = self
+            .dock_rect()
+            .filter(|_| self.root.map_or(false, |root| self[root].is_parent()))
+            .and_then(|dock| crate::edge_drop_zone(style, dock, pos));
+        if let Some((split, highlight)) = edge {
+            return Some((node_index, DropTarget::Edge(split), highlight));
+        }
+
+        let style = self.node_style(node_index).unwrap_or(style);
+        let position = self.shown_tab_bar_position(node_index, style);
+        let tab_bar = self
+            .geometry
+            .tab_bars
+            .iter()
+            .find(|(node, _)| *node == node_index)
+            .map(|(_, tab_bar)| *tab_bar);
+        let tab = self
+            .geometry
+            .tab_titles
+            .iter()
+            .find(|(node, _, title)| *node == node_index && title.contains(pos))
+            .map(|(_, tab, title)| (*title, *tab));
+        let hover = crate::HoverData {
+            rect,
+            tabs: tab_bar.filter(|tab_bar| !self.is_mdi(node_index) && tab_bar.contains(pos)),
+            tab,
+            dst: node_index,
+            pointer: pos,
+            vertical: position.is_vertical(),
+        };
+        let (split, highlight, tab_index) = hover.resolve(style);
+        let target = match split {
+            Some(split) => DropTarget::Split(split),
+            None => DropTarget::Tabs(tab_index),
+        };
+        Some((node_index, target, highlight))
+    }
+
+    /// Returns the tab being dragged out of its leaf as of the last time the tree was shown, or `None` if no tab
+    /// of the tree is being dragged.
+    ///
+    /// Useful for reacting to drags elsewhere in the app, like dimming panels a tab can't be dropped into. See also
+    /// [`DockState::dragged_tab`](crate::DockState::dragged_tab).
+    pub fn dragged_tab(&self) -> Option<(NodeIndex, TabIndex)> {
+        self.geometry.dragged_tab
+    }
+
+    /// Starts recording where the tree is shown, in `rect`.
+    pub(crate) fn begin_geometry(&mut self, rect: Rect) {
+        self.geometry.rect = Some(rect);
+        self.geometry.tab_titles.clear();
+        self.geometry.tab_bars.clear();
+        self.geometry.dragged_tab = None;
+    }
+
+    /// Records which tab is being dragged out of its leaf.
+    pub(crate) fn set_dragged_tab(&mut self, dragged_tab: Option<(NodeIndex, TabIndex)>) {
+        self.geometry.dragged_tab = dragged_tab;
+    }
+
+    /// Records where the title of the tab at `tab_index` in the leaf at `node_index` is shown.
+    pub(crate) fn set_tab_title_rect(
+        &mut self,
+        node_index: NodeIndex,
+        tab_index: TabIndex,
+        rect: Rect,
+    ) {
+        self.geometry.tab_titles.push((node_index, tab_index, rect));
+    }
+
+    /// Records where the tab bar of the leaf at `node_index` is shown, taller than a single row if its tabs wrap.
+    pub(crate) fn set_tab_bar_rect(&mut self, node_index: NodeIndex, rect: Rect) {
+        self.geometry.tab_bars.push((node_index, rect));
+    }
+
+    /// Returns the links of the node at `node_index`, or `None` if there's no node there.
+    fn slot(&self, node_index: NodeIndex) -> Option<&Slot> {
+        match self.tree.get(node_index.0) {
+            Some(Node::Empty) | None => None,
+            Some(_) => self.slots.get(node_index.0),
+        }
+    }
+
+    /// Fills `order` with the index of every node reachable from the root, each one after its parent.
+    fn top_down(&self, order: &mut Vec<NodeIndex>) {
+        order.clear();
+        order.extend(self.root);
+        let mut next = 0;
+        while let Some(&node_index) = order.get(next) {
+            order.extend(self.children(node_index).into_iter().flatten());
+            next += 1;
+        }
+    }
+
+    /// Links the nodes of a deserialized tree the way their position did back when nodes were stored by it, the
+    /// children of the node at `i` being at `2 * i + 1` and `2 * i + 2`, and collects the slots no node is using.
+    #[cfg(feature = "serde")]
+    fn link_slots(&mut self) {
+        if self.slots.len() != self.tree.len() {
+            let is_node =
+                |index: usize| self.tree.get(index).map_or(false, |node| !node.is_empty());
+            self.root = is_node(0).then_some(NodeIndex(0));
+            self.slots = (0..self.tree.len())
+                .map(|index| match self.tree[index] {
+                    Node::Empty => Slot::default(),
+                    ref node => Slot {
+                        parent: (index > 0).then(|| NodeIndex((index - 1) / 2)),
+                        children: node
+                            .is_parent()
+                            .then(|| [NodeIndex(index * 2 + 1), NodeIndex(index * 2 + 2)]),
+                        generation: index as u64,
+                    },
+                })
+                .collect();
+            self.next_generation = self.tree.len() as u64;
+        }
+        self.free = (0..self.tree.len())
+            .filter(|&index| self.tree[index].is_empty())
+            .map(NodeIndex)
+            .collect();
+    }
+
+    /// Solves the layout: assigns every node its rect, starting from `rect` for the root node and dividing parent
+    /// nodes' areas according to their fractions.
+    ///
+    /// This is a pure computation which doesn't need a `Ui`. [`DockArea`](crate::DockArea) calls it every frame,
+    /// afterwards the rects can be read through [`Node::rect`].
+    pub fn layout(&mut self, rect: Rect, separator_width: f32, pixels_per_point: f32) {
+        self.layout_with_min_size(
+            rect,
+            separator_width,
+            pixels_per_point,
+            Vec2::ZERO,
+            Vec2::ZERO,
+        );
+    }
+
+    /// Like [`Tree::layout`], also moving the separators so that no leaf is smaller than `min_leaf_size`, see
+    /// [`Style::min_node_size`], and collapsed leaves are `collapsed_size` wide beside their sibling, or high above
+    /// or below it.
+    pub(crate) fn layout_with_min_size(
+        &mut self,
+        rect: Rect,
+        separator_width: f32,
+        pixels_per_point: f32,
+        min_leaf_size: Vec2,
+        collapsed_size: Vec2,
+    ) {
+        profile_function!();
+
+        let root = match self.root {
+            Some(root) => root,
+            None => return,
+        };
+        let mut previous = std::mem::take(&mut self.geometry.previous_rects);
+        previous.clear();
+        previous.extend(self.tree.iter().map(Node::rect));
+        let mut order = std::mem::take(&mut self.geometry.order);
+        self.top_down(&mut order);
+        let mut min_sizes = std::mem::take(&mut self.geometry.min_sizes);
+        self.min_sizes(min_leaf_size, separator_width, &order, &mut min_sizes);
+        self[root].set_rect(rect);
+        for &node_index in &order {
+            let index = node_index.0;
+            if !self.apply_fixed_size(node_index, separator_width) {
+                if let Some(previous) = previous[index].filter(|rect| rect.is_positive()) {
+                    self.apply_resize_policy(node_index, previous);
+                }
+            }
+            let (first, second) = self.min_child_sizes(node_index, &min_sizes);
+            let is_horizontal = self.tree[index].is_horizontal();
+            if let Node::Horizontal { fraction, rect } | Node::Vertical { fraction, rect } =
+                &mut self.tree[index]
+            {
+                let length = if is_horizontal {
+                    rect.width()
+                } else {
+                    rect.height()
+                };
+                *fraction = clamp_fraction(*fraction, length, first, second, separator_width);
+            }
+            let rect = self.tree[index].rect().unwrap_or(Rect::NOTHING);
+            if let Some(collapsed) =
+                self.collapsed_fraction(node_index, rect, separator_width, collapsed_size)
+            {
+                if let Node::Horizontal { fraction, .. } | Node::Vertical { fraction, .. } =
+                    &mut self.tree[index]
+                {
+                    *fraction = collapsed;
+                }
+            }
+            if let Some((left, _, right)) =
+                self.split_rects(node_index, separator_width, pixels_per_point)
+            {
+                if let Some([left_child, right_child]) = self.children(node_index) {
+                    self[left_child].set_rect(left);
+                    self[right_child].set_rect(right);
+                }
+            }
         }
+        self.geometry.previous_rects = previous;
+        self.geometry.min_sizes = min_sizes;
+        self.geometry.order = order;
+    }
+
+    /// Solves the layout [`DockArea`](crate::DockArea) would show the tree with inside `rect`, without modifying
+    /// the tree or needing a `Ui`.
+    ///
+    /// Takes [`Style::padding`], the separator width (including its [`Style::accessibility_mode`] minimum), sizes set
+    /// with [`Tree::set_fixed_size`] and resize policies into account, like the dock does. `rect` is where the tree
+    /// itself goes, so leave out the height of a [`DockArea::status_bar`](crate::DockArea::status_bar). Returns the
+    /// rect of every node indexed by [`NodeIndex`], with `Rect::NOTHING` for `Empty` nodes.
+    ///
+    /// Useful for testing layouts deterministically:
+    ///
+    /// ```rust
+    /// # use egui_dock::egui::{pos2, Rect};
+    /// # use egui_dock::{NodeIndex, Style, Tree};
+    /// let mut tree = Tree::new(vec!["a"]);
+    /// let [a, b] = tree.split_left(tree.root().unwrap(), 0.25, vec!["b"]);
+    ///
+    /// let rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(400.0, 300.0));
+    /// let rects = tree.solve_layout(rect, &Style::default(), 1.0);
+    /// assert!(rects[b.0].width() < rects[a.0].width());
+    /// ```
+    pub fn solve_layout(&self, rect: Rect, style: &Style, pixels_per_point: f32) -> Vec<Rect> {
+        let separator_width = style.effective_separator_width();
+        let mut order = Vec::new();
+        self.top_down(&mut order);
+        let mut min_sizes = Vec::new();
+        self.min_sizes(style.min_node_size, separator_width, &order, &mut min_sizes);
+        let mut rects = vec![Rect::NOTHING; self.tree.len()];
+        if let Some(root) = self.root {
+            rects[root.0] = style.inner_rect(rect);
+        }
+        for NodeIndex(index) in order {
+            let (fraction, previous, is_horizontal) = match &self.tree[index] {
+                Node::Horizontal { fraction, rect } => (*fraction, *rect, true),
+                Node::Vertical { fraction, rect } => (*fraction, *rect, false),
+                _ => continue,
+            };
+            let (first, second) = self.min_child_sizes(NodeIndex(index), &min_sizes);
+            let length = if is_horizontal {
+                rects[index].width()
+            } else {
+                rects[index].height()
+            };
+            // Like in `layout`, the node was last laid out in `previous`.
+            let fraction = match self.fixed_fraction(NodeIndex(index), length, separator_width) {
+                Some(fixed) => fixed,
+                None if previous.is_positive() => {
+                    self.resized_fraction(NodeIndex(index), fraction, previous, rects[index])
+                }
+                None => fraction,
+            };
+            let fraction = clamp_fraction(fraction, length, first, second, separator_width);
+            let fraction = self
+                .collapsed_fraction(
+                    NodeIndex(index),
+                    rects[index],
+                    separator_width,
+                    vec2(style.vertical_tab_bar_width, style.tab_bar_height()),
+                )
+                .unwrap_or(fraction);
+            let (left, _, right) = split_rect(
+                rects[index],
+                fraction,
+                is_horizontal,
+                separator_width,
+                pixels_per_point,
+            );
+            let [left_child, right_child] = match self.children(NodeIndex(index)) {
+                Some(children) => children,
+                None => continue,
+            };
+            for (child, rect) in [(left_child, left), (right_child, right)] {
+                if let Some(slot) = rects.get_mut(child.0) {
+                    if !self.tree[child.0].is_empty() {
+                        *slot = rect;
+                    }
+                }
+            }
+        }
+        rects
+    }
+
+    /// Solves the layout like [`Tree::solve_layout`], returning the area of every leaf and of its body, next to its
+    /// tab bar.
+    ///
+    /// Meant for renderers drawing outside of egui, like a game view compositor lining its render targets up with the
+    /// leaves. Toolbars shown with [`TabViewer::toolbar`] aren't taken into account, since only the viewer knows them.
+    ///
+    /// ```rust
+    /// # use egui_dock::egui::{pos2, Rect};
+    /// # use egui_dock::{NodeIndex, Style, Tree};
+    /// let mut tree = Tree::new(vec!["scene"]);
+    /// tree.split_below(tree.root().unwrap(), 0.75, vec!["console"]);
+    ///
+    /// let rect = Rect::from_min_max(pos2(0.0, 0.0), pos2(800.0, 600.0));
+    /// for leaf in tree.solve_leaf_layout(rect, &Style::default(), 1.0) {
+    ///     assert!(leaf.body.height() < leaf.rect.height());
+    /// }
+    /// ```
+    pub fn solve_leaf_layout(
+        &self,
+        rect: Rect,
+        style: &Style,
+        pixels_per_point: f32,
+    ) -> Vec<LeafLayout> {
+        let hides_tab_bars = self.zen_mode() == Some(ZenMode::ActiveTab);
+        self.solve_layout(rect, style, pixels_per_point)
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| self.tree[*index].is_leaf())
+            .map(|(index, rect)| {
+                let node = NodeIndex(index);
+                let body = if self.is_mdi(node) || hides_tab_bars {
+                    rect
+                } else {
+                    let position = self.shown_tab_bar_position(node, style);
+                    position.split(rect, style.tab_bar_thickness(position)).1
+                };
+                LeafLayout {
+                    node,
+                    rect,
+                    body: expand_to_pixel(body, pixels_per_point),
+                }
+            })
+            .collect()
+    }
+
+    /// Divides the rect of the parent node at `node_index` according to its fraction.
+    ///
+    /// Returns the rects of its left child, the separator and its right child, or `None` if the node isn't a parent.
+    pub(crate) fn split_rects(
+        &self,
+        node_index: NodeIndex,
+        separator_width: f32,
+        pixels_per_point: f32,
+    ) -> Option<(Rect, Rect, Rect)> {
+        let (rect, fraction, is_horizontal) = match self.tree.get(node_index.0)? {
+            Node::Horizontal { rect, fraction } => (*rect, *fraction, true),
+            Node::Vertical { rect, fraction } => (*rect, *fraction, false),
+            _ => return None,
+        };
+        Some(split_rect(
+            rect,
+            fraction,
+            is_horizontal,
+            separator_width,
+            pixels_per_point,
+        ))
     }
 
     /// Returns the viewport `Rect` and the `Tab` inside the first leaf node, or `None` of no leaf exists in the `Tree`.
@@ -305,49 +1265,167 @@ impl<Tab> Tree<Tab> {
     /// Returns the number of nodes in the `Tree`.
     #[inline(always)]
     pub fn len(&self) -> usize {
-        self.tree.len()
+        self.tree.len() - self.free.len()
     }
 
     /// Returns `true` if the number of nodes in the tree is 0, `false` otherwise.
     #[inline(always)]
     pub fn is_empty(&self) -> bool {
-        self.tree.is_empty()
+        self.root.is_none()
     }
 
-    /// Returns `Iter` of the underlying collection of nodes.
+    /// Returns `Iter` of the underlying collection of nodes, with `Node::Empty` in the slots no node is using.
     pub fn iter(&self) -> std::slice::Iter<'_, Node<Tab>> {
         self.tree.iter()
     }
 
-    /// Returns `IterMut` of the underlying collection of nodes.
+    /// Returns `IterMut` of the underlying collection of nodes, with `Node::Empty` in the slots no node is using.
     pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, Node<Tab>> {
         self.tree.iter_mut()
     }
 
-    /// Creates two new nodes by splitting a given `parent` node and assigns them as its children. The first (old) node
-    /// inherits content of the `parent` from before the split, and the second (new) has `tabs`.
-    ///
-    /// `fraction` (in range 0..=1) specifies how much of the `parent` node's area the old node is will occupy after the
-    /// split.
-    ///
-    /// The new node is placed relatively to the old node, in the direction specified by `split`.
-    ///
-    /// Returns the indices of the old node and the new node.
-    pub fn split_tabs(
-        &mut self,
-        parent: NodeIndex,
-        split: Split,
-        fraction: f32,
-        tabs: Vec<Tab>,
-    ) -> [NodeIndex; 2] {
-        self.split(parent, split, fraction, Node::leaf_with(tabs))
+    /// Returns the index of every slot, used or not, to walk the nodes while changing the tree.
+    pub(crate) fn node_indices(&self) -> impl Iterator<Item = NodeIndex> {
+        (0..self.tree.len()).map(NodeIndex)
     }
 
-    /// Creates two new nodes by splitting a given `parent` node and assigns them as its children. The first (old) node
-    /// inherits content of the `parent` from before the split, and the second (new) has `tabs`.
-    ///
-    /// `fraction` (in range 0..=1) specifies how much of the `parent` node's area the old node is will occupy after the
-    /// split.
+    /// Returns an iterator over every node which isn't `Empty`, together with its index.
+    ///
+    /// Nodes come in the order of the slots they're stored in, which isn't the order they're shown in. Use
+    /// [`Tree::visit`] for that.
+    pub fn nodes(&self) -> impl Iterator<Item = (NodeIndex, &Node<Tab>)> + '_ {
+        self.tree
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| !node.is_empty())
+            .map(|(index, node)| (NodeIndex(index), node))
+    }
+
+    /// Returns a mutable iterator over every node which isn't `Empty`, together with its index.
+    pub fn nodes_mut(&mut self) -> impl Iterator<Item = (NodeIndex, &mut Node<Tab>)> + '_ {
+        self.tree
+            .iter_mut()
+            .enumerate()
+            .filter(|(_, node)| !node.is_empty())
+            .map(|(index, node)| (NodeIndex(index), node))
+    }
+
+    /// Returns an iterator over the indices of every leaf.
+    pub fn leaves(&self) -> impl Iterator<Item = NodeIndex> + '_ {
+        self.nodes()
+            .filter(|(_, node)| node.is_leaf())
+            .map(|(index, _)| index)
+    }
+
+    /// Walks the tree depth-first from the root, calling `visitor` for every node in the order they're shown in.
+    ///
+    /// ```rust
+    /// # use egui_dock::{Node, NodeIndex, NodeVisitor, TabIndex, Tree};
+    /// /// Prints the tree as an outline.
+    /// struct Outline;
+    ///
+    /// impl NodeVisitor<&str> for Outline {
+    ///     fn split(&mut self, _: NodeIndex, node: &Node<&str>, depth: usize) -> bool {
+    ///         let kind = if node.is_horizontal() { "horizontal" } else { "vertical" };
+    ///         println!("{:indent$}{kind}", "", indent = depth * 2);
+    ///         true
+    ///     }
+    ///
+    ///     fn leaf(&mut self, _: NodeIndex, tabs: &[&str], _: TabIndex, depth: usize) {
+    ///         println!("{:indent$}{tabs:?}", "", indent = depth * 2);
+    ///     }
+    /// }
+    ///
+    /// let mut tree = Tree::new(vec!["editor"]);
+    /// tree.split_below(tree.root().unwrap(), 0.7, vec!["console"]);
+    /// tree.visit(&mut Outline);
+    /// ```
+    pub fn visit(&self, visitor: &mut impl NodeVisitor<Tab>) {
+        if let Some(root) = self.root {
+            self.visit_node(root, 0, visitor);
+        }
+    }
+
+    fn visit_node(&self, node_index: NodeIndex, depth: usize, visitor: &mut impl NodeVisitor<Tab>) {
+        let node = match self.tree.get(node_index.0) {
+            Some(node) => node,
+            None => return,
+        };
+        match node {
+            Node::Empty => {}
+            Node::Leaf { tabs, active, .. } => visitor.leaf(node_index, tabs, *active, depth),
+            Node::Horizontal { .. } | Node::Vertical { .. } => {
+                if visitor.split(node_index, node, depth) {
+                    let children = match self.shown_children(node_index) {
+                        Some(children) => children,
+                        None => return,
+                    };
+                    for child in children {
+                        self.visit_node(child, depth + 1, visitor);
+                    }
+                    visitor.end_split(node_index, node, depth);
+                }
+            }
+        }
+    }
+
+    /// Returns an iterator over every tab in the `Tree` together with the leaf it's in and its index in that leaf.
+    pub fn tabs(&self) -> impl Iterator<Item = (NodeIndex, TabIndex, &Tab)> + '_ {
+        self.tree
+            .iter()
+            .enumerate()
+            .filter_map(|(node_index, node)| match node {
+                Node::Leaf { tabs, .. } => Some((node_index, tabs)),
+                _ => None,
+            })
+            .flat_map(|(node_index, tabs)| {
+                tabs.iter()
+                    .enumerate()
+                    .map(move |(tab_index, tab)| (NodeIndex(node_index), TabIndex(tab_index), tab))
+            })
+    }
+
+    /// Returns a mutable iterator over every tab in the `Tree` together with the leaf it's in and its index in that
+    /// leaf.
+    pub fn tabs_mut(&mut self) -> impl Iterator<Item = (NodeIndex, TabIndex, &mut Tab)> + '_ {
+        self.tree
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(node_index, node)| match node {
+                Node::Leaf { tabs, .. } => Some((node_index, tabs)),
+                _ => None,
+            })
+            .flat_map(|(node_index, tabs)| {
+                tabs.iter_mut()
+                    .enumerate()
+                    .map(move |(tab_index, tab)| (NodeIndex(node_index), TabIndex(tab_index), tab))
+            })
+    }
+
+    /// Creates two new nodes by splitting a given `parent` node and assigns them as its children. The first (old) node
+    /// inherits content of the `parent` from before the split, and the second (new) has `tabs`.
+    ///
+    /// `fraction` (in range 0..=1) specifies how much of the `parent` node's area the old node is will occupy after the
+    /// split.
+    ///
+    /// The new node is placed relatively to the old node, in the direction specified by `split`.
+    ///
+    /// Returns the indices of the old node and the new node.
+    pub fn split_tabs(
+        &mut self,
+        parent: NodeIndex,
+        split: Split,
+        fraction: f32,
+        tabs: Vec<Tab>,
+    ) -> [NodeIndex; 2] {
+        self.split(parent, split, fraction, Node::leaf_with(tabs))
+    }
+
+    /// Creates two new nodes by splitting a given `parent` node and assigns them as its children. The first (old) node
+    /// inherits content of the `parent` from before the split, and the second (new) has `tabs`.
+    ///
+    /// `fraction` (in range 0..=1) specifies how much of the `parent` node's area the old node is will occupy after the
+    /// split.
     ///
     /// The new node is placed above the old node.
     ///
@@ -431,31 +1509,125 @@ impl<Tab> Tree<Tab> {
         fraction: f32,
         new: Node<Tab>,
     ) -> [NodeIndex; 2] {
-        let old = self[parent].split(split, fraction);
-        assert!(old.is_leaf());
+        assert!(self[parent].is_leaf());
+        self.split_node(parent, split, fraction, new)
+    }
 
-        {
-            let index = self.tree.iter().rposition(|n| !n.is_empty()).unwrap_or(0);
-            let level = NodeIndex(index).level();
-            self.tree.resize_with(1 << (level + 1), || Node::Empty);
-        }
+    /// Puts a new parent node in the place of the node at `old`, with `old` and `new` as its children, and focuses
+    /// `new`.
+    fn split_node(
+        &mut self,
+        old: NodeIndex,
+        split: Split,
+        fraction: f32,
+        new: Node<Tab>,
+    ) -> [NodeIndex; 2] {
+        let grandparent = self.parent(old);
+        let mut parent = Node::Empty;
+        parent.split(split, fraction);
+        let parent = self.insert_node(parent, grandparent);
+        let new = self.insert_node(new, Some(parent));
+        self.slots[parent.0].children = Some(match split {
+            Split::Right | Split::Above => [new, old],
+            Split::Left | Split::Below => [old, new],
+        });
+        self.replace_child(grandparent, old, parent);
+        self.slots[old.0].parent = Some(parent);
+
+        self.focused_node = Some(new);
 
-        let index = match split {
-            Split::Right | Split::Above => [parent.right(), parent.left()],
-            Split::Left | Split::Below => [parent.left(), parent.right()],
+        [old, new]
+    }
+
+    /// Stores `node` in a slot no node is using, as a child of `parent`.
+    fn insert_node(&mut self, node: Node<Tab>, parent: Option<NodeIndex>) -> NodeIndex {
+        let index = match self.free.pop() {
+            Some(index) => index,
+            None => {
+                self.tree.push(Node::Empty);
+                self.slots.push(Slot::default());
+                NodeIndex(self.tree.len() - 1)
+            }
         };
+        self.tree[index.0] = node;
+        self.slots[index.0] = Slot {
+            parent,
+            children: None,
+            generation: self.next_generation,
+        };
+        self.next_generation += 1;
+        index
+    }
+
+    /// Splits the whole tree in two, moving every node one level down to make room for a `new` node along the side
+    /// of the dock area given by `split`, like a panel docked to the edge of an IDE.
+    ///
+    /// `fraction` is shared out between the old root and the new node like with [`Tree::split`]. Returns the indices
+    /// of the old root and the new node.
+    ///
+    /// # Panics
+    /// Panics if the tree is empty.
+    pub fn split_root(&mut self, split: Split, fraction: f32, new: Node<Tab>) -> [NodeIndex; 2] {
+        let root = self.root.expect("the tree is empty");
+        self.split_node(root, split, fraction, new)
+    }
 
-        self[index[0]] = old;
-        self[index[1]] = new;
+    /// Frees the slot of the node at `index`, leaving an `Empty` node there.
+    fn free_node(&mut self, index: NodeIndex) {
+        self.tree[index.0] = Node::Empty;
+        self.slots[index.0] = Slot::default();
+        self.free.push(index);
+    }
 
-        self.focused_node = Some(index[1]);
+    /// Makes `new` the child of `parent` in the place of `old`, or the root node if `parent` is `None`.
+    fn replace_child(&mut self, parent: Option<NodeIndex>, old: NodeIndex, new: NodeIndex) {
+        match parent {
+            Some(parent) => {
+                for child in self.slots[parent.0].children.iter_mut().flatten() {
+                    if *child == old {
+                        *child = new;
+                    }
+                }
+            }
+            None => self.root = Some(new),
+        }
+        self.slots[new.0].parent = parent;
+    }
 
-        index
+    /// Replaces the whole tree with a root `node` and focuses it.
+    fn set_root(&mut self, node: Node<Tab>) {
+        self.tree.clear();
+        self.slots.clear();
+        self.free.clear();
+        let root = self.insert_node(node, None);
+        self.root = Some(root);
+        self.focused_node = Some(root);
+    }
+
+    /// Returns the other child of the parent of the node at `node_index`.
+    fn sibling(&self, node_index: NodeIndex) -> Option<NodeIndex> {
+        let [left, right] = self.children(self.parent(node_index)?)?;
+        Some(if left == node_index { right } else { left })
+    }
+
+    /// Returns the leaf closest to the node at `node_index`, not counting the ones below it.
+    pub(crate) fn nearest_leaf(&self, mut node_index: NodeIndex) -> Option<NodeIndex> {
+        while let Some(next) = self.sibling(node_index) {
+            if let Some(Node::Leaf { .. }) = self.tree.get(next.0) {
+                return Some(next);
+            }
+            if let Some(leaf) = self.first_leaf(next) {
+                return Some(leaf);
+            }
+            node_index = self
+                .parent(node_index)
+                .expect("a node with a sibling has a parent");
+        }
+        None
     }
 
     fn first_leaf(&self, top: NodeIndex) -> Option<NodeIndex> {
-        let left = top.left();
-        let right = top.right();
+        let [left, right] = self.children(top)?;
         match (self.tree.get(left.0), self.tree.get(right.0)) {
             (Some(&Node::Leaf { .. }), _) => Some(left),
             (_, Some(&Node::Leaf { .. })) => Some(right),
@@ -477,114 +1649,322 @@ impl<Tab> Tree<Tab> {
         }
     }
 
-    /// Removes the first node containing 0 tabs.
-    pub fn remove_empty_leaf(&mut self) {
-        let mut nodes = self.tree.iter().enumerate();
-        let node = nodes.find_map(|(index, node)| match node {
-            Node::Leaf { tabs, .. } if tabs.is_empty() => Some(index),
-            _ => None,
-        });
+    /// Removes every leaf without tabs and every split missing a child, promoting their siblings in their place, then
+    /// merges nested splits of the same orientation.
+    ///
+    /// Leaves are already removed when their last tab is closed or dragged away, so this is only needed after
+    /// changing the tree by hand, like clearing [`Node::Leaf`] tabs through [`Tree::iter_mut`] or loading a layout
+    /// which left gaps. A run of nodes side by side can be nested either way, splits of the same orientation are
+    /// merged into one chain going through their right-most or bottom-most child, every node keeping its share of the
+    /// space. Rows and columns of nodes then have the same structure however they were built.
+    ///
+    /// Returns `true` if the tree changed.
+    pub fn simplify(&mut self) -> bool {
+        let mut changed = false;
+        loop {
+            // A split missing a child gets an empty leaf in its place, which is removed like any other.
+            let half_empty = self.node_indices().find_map(|node| {
+                let child = self
+                    .children(node)?
+                    .into_iter()
+                    .find(|child| self.tree[child.0].is_empty());
+                child.filter(|_| self.tree[node.0].is_parent())
+            });
+            if let Some(child) = half_empty {
+                self.tree[child.0] = Node::leaf_with(Vec::new());
+            }
+            match self.remove_empty_leaf() {
+                Some(_) => changed = true,
+                None => break,
+            }
+        }
+        while let Some(node) = self
+            .node_indices()
+            .find(|&node| self.nested_split(node).is_some())
+        {
+            self.merge_nested_split(node);
+            changed = true;
+        }
+        changed
+    }
+
+    /// Returns the children of the parent node at `node_index` in the order they're shown in, the left-most or
+    /// top-most one first.
+    fn shown_children(&self, node_index: NodeIndex) -> Option<[NodeIndex; 2]> {
+        let [left, right] = self.children(node_index)?;
+        // The left-most child of horizontal nodes is their right one.
+        Some(if self.tree[node_index.0].is_horizontal() {
+            [right, left]
+        } else {
+            [left, right]
+        })
+    }
+
+    /// Returns the left-most or top-most child of the parent node at `node_index` if it's split the same way.
+    fn nested_split(&self, node_index: NodeIndex) -> Option<NodeIndex> {
+        let [first, _] = self.shown_children(node_index)?;
+        let (node, child) = (&self.tree[node_index.0], &self.tree[first.0]);
+        let nested = (node.is_horizontal() && child.is_horizontal())
+            || (node.is_vertical() && child.is_vertical());
+        nested.then_some(first)
+    }
 
-        let node = match node {
-            Some(node) => NodeIndex(node),
+    /// Moves the nested split of [`Tree::nested_split`] to the other side of the parent node at `node_index`, from
+    /// holding its first two children to holding its last two.
+    fn merge_nested_split(&mut self, node_index: NodeIndex) {
+        let nested = match self.nested_split(node_index) {
+            Some(nested) => nested,
             None => return,
         };
+        let ([_, last], [first, middle]) =
+            match (self.shown_children(node_index), self.shown_children(nested)) {
+                (Some(outer), Some(inner)) => (outer, inner),
+                _ => return,
+            };
+        let (outer, inner) = match (&self.tree[node_index.0], &self.tree[nested.0]) {
+            (
+                Node::Horizontal {
+                    fraction: outer, ..
+                }
+                | Node::Vertical {
+                    fraction: outer, ..
+                },
+                Node::Horizontal {
+                    fraction: inner, ..
+                }
+                | Node::Vertical {
+                    fraction: inner, ..
+                },
+            ) => (*outer, *inner),
+            _ => return,
+        };
+
+        // The first child keeps its share of the whole, the other two share out the rest like before.
+        let first_share = outer * inner;
+        let rest = 1.0 - first_share;
+        let middle_fraction = if rest > f32::EPSILON {
+            outer * (1.0 - inner) / rest
+        } else {
+            0.5
+        };
+        if let Node::Horizontal { fraction, .. } | Node::Vertical { fraction, .. } =
+            &mut self.tree[node_index.0]
+        {
+            *fraction = first_share;
+        }
+        if let Node::Horizontal { fraction, .. } | Node::Vertical { fraction, .. } =
+            &mut self.tree[nested.0]
+        {
+            *fraction = middle_fraction;
+        }
+
+        self.set_shown_children(node_index, [first, nested]);
+        self.set_shown_children(nested, [middle, last]);
+    }
+
+    /// Makes `children` the children of the parent node at `node_index`, in the order they're shown in.
+    fn set_shown_children(&mut self, node_index: NodeIndex, [first, second]: [NodeIndex; 2]) {
+        self.slots[node_index.0].children = Some(if self.tree[node_index.0].is_horizontal() {
+            [second, first]
+        } else {
+            [first, second]
+        });
+        self.slots[first.0].parent = Some(node_index);
+        self.slots[second.0].parent = Some(node_index);
+    }
+
+    /// Removes the first node containing 0 tabs, see [`Tree::remove_leaf`].
+    ///
+    /// Returns the index the removed leaf was at, if there was one.
+    pub fn remove_empty_leaf(&mut self) -> Option<NodeIndex> {
+        let node = self
+            .tree
+            .iter()
+            .position(|node| node.is_leaf() && node.tabs_count() == 0)
+            .map(NodeIndex)?;
+        self.remove_leaf(node);
+        Some(node)
+    }
+
+    /// Removes the leaf at `node_index` together with its tabs, with its sibling taking the place of their parent.
+    /// Other nodes keep their index.
+    ///
+    /// Unlike [`Tree::remove_empty_leaf`], this leaves any other empty leaf in place, like those kept on purpose to
+    /// show [`DockArea::empty_area`](crate::DockArea::empty_area) in. Returns the tabs of the leaf, or `None` if
+    /// there's no leaf at `node_index`.
+    pub fn remove_leaf(&mut self, node_index: NodeIndex) -> Option<Vec<Tab>> {
+        let tabs = match self.tree.get_mut(node_index.0) {
+            Some(Node::Leaf { tabs, .. }) => std::mem::take(tabs),
+            _ => return None,
+        };
 
-        let parent = match node.parent() {
+        let parent = match self.parent(node_index) {
             Some(val) => val,
             None => {
                 self.tree.clear();
-                return;
+                self.slots.clear();
+                self.free.clear();
+                self.root = None;
+                self.focused_node = None;
+                return Some(tabs);
             }
         };
 
-        if Some(node) == self.focused_node {
-            self.focused_node = None;
-            let mut node = node;
-            while let Some(parent) = node.parent() {
-                let next = if node.is_left() {
-                    parent.right()
-                } else {
-                    parent.left()
-                };
-                if let Some(Node::Leaf { .. }) = self.tree.get(next.0) {
-                    self.focused_node = Some(next);
-                    break;
-                }
-                if let Some(node) = self.first_leaf(next) {
-                    self.focused_node = Some(node);
-                    break;
-                }
-                node = parent;
-            }
+        if Some(node_index) == self.focused_node {
+            self.focused_node = self.nearest_leaf(node_index);
         }
 
-        self[parent] = Node::Empty;
-        self[node] = Node::Empty;
+        let sibling = self.sibling(node_index);
+        let grandparent = self.parent(parent);
+        self.free_node(node_index);
+        self.free_node(parent);
+        if let Some(sibling) = sibling {
+            self.replace_child(grandparent, parent, sibling);
+        }
+        Some(tabs)
+    }
 
-        let mut level = 0;
+    /// Removes the leaf at `node_index` if it has no tabs left, like after its last tab was closed or moved away.
+    ///
+    /// Returns `true` if the leaf was removed.
+    pub(crate) fn remove_leaf_if_empty(&mut self, node_index: NodeIndex) -> bool {
+        match self.tree.get(node_index.0) {
+            Some(Node::Leaf { tabs, .. }) if tabs.is_empty() => {
+                self.remove_leaf(node_index);
+                true
+            }
+            _ => false,
+        }
+    }
 
-        if node.is_left() {
-            'left_end: loop {
-                let dst = parent.children_at(level);
-                let src = parent.children_right(level + 1);
-                for (dst, src) in dst.zip(src) {
-                    if src >= self.tree.len() {
-                        break 'left_end;
-                    }
-                    if Some(NodeIndex(src)) == self.focused_node {
-                        self.focused_node = Some(NodeIndex(dst));
-                    }
-                    self.tree[dst] = std::mem::replace(&mut self.tree[src], Node::Empty);
-                }
-                level += 1;
+    /// Push a tab to the first leaf it finds, or makes it the only leaf if the tree has none.
+    pub fn push_to_first_leaf(&mut self, tab: Tab) {
+        let policy = self.tab_insert_policy;
+        for (index, node) in &mut self.tree.iter_mut().enumerate() {
+            if let Node::Leaf { tabs, active, .. } = node {
+                policy.insert(tabs, active, tab, true);
+                self.focused_node = Some(NodeIndex(index));
+                return;
             }
-        } else {
-            'right_end: loop {
-                let dst = parent.children_at(level);
-                let src = parent.children_left(level + 1);
-                for (dst, src) in dst.zip(src) {
-                    if src >= self.tree.len() {
-                        break 'right_end;
+        }
+        self.set_root(Node::leaf(tab));
+    }
+
+    /// Pushes `tab` to the given `destination`, making it the active tab and focusing its leaf. The tab's place within
+    /// the leaf follows the [`TabInsertPolicy`].
+    ///
+    /// Returns where the tab ended up.
+    ///
+    /// ```
+    /// # use egui_dock::{Split, TabDestination, Tree};
+    /// let mut tree = Tree::new(vec!["main.rs"]);
+    /// let main = tree.root().unwrap();
+    /// tree.set_focused_node(main);
+    /// let beside = TabDestination::Beside(Split::Right, 0.6);
+    /// let (docs, _) = tree.push_to(beside, "Vec");
+    /// assert_eq!(tree.push_to(TabDestination::Node(docs), "HashMap").0, docs);
+    ///
+    /// // Tabs opened beside `main.rs` again go to the same leaf, even before the tree was shown.
+    /// tree.set_focused_node(main);
+    /// assert_eq!(tree.push_to(beside, "BTreeMap").0, docs);
+    /// assert_eq!(tree.tabs().count(), 4);
+    /// ```
+    pub fn push_to(&mut self, destination: TabDestination, tab: Tab) -> (NodeIndex, TabIndex) {
+        let focused = self
+            .focused_node
+            .filter(|node| self.tree.get(node.0).map_or(false, Node::is_leaf));
+        match destination {
+            TabDestination::FocusedLeaf => self.push_to_focused_leaf(tab),
+            TabDestination::Node(node) => {
+                self.set_focused_leaf(node);
+                self.push_to_focused_leaf(tab);
+            }
+            TabDestination::Beside(split, fraction) => match focused {
+                Some(focused) => match self.adjacent_leaf(focused, split) {
+                    Some(leaf) => {
+                        self.focused_node = Some(leaf);
+                        self.push_to_focused_leaf(tab);
                     }
-                    if Some(NodeIndex(src)) == self.focused_node {
-                        self.focused_node = Some(NodeIndex(dst));
+                    None => {
+                        self.split(focused, split, fraction, Node::leaf(tab));
                     }
-                    self.tree[dst] = std::mem::replace(&mut self.tree[src], Node::Empty);
+                },
+                None => self.push_to_focused_leaf(tab),
+            },
+            TabDestination::Edge(split, fraction) => {
+                if self.root.is_some() {
+                    self.split_root(split, fraction, Node::leaf(tab));
+                } else {
+                    self.push_to_focused_leaf(tab);
                 }
-                level += 1;
             }
         }
+        self.focused_tab()
+            .expect("the pushed tab is the active tab of the focused leaf")
     }
 
-    /// Push a tab to the first leaf it finds or creates a leaf if an empty spot is encountered.
-    pub fn push_to_first_leaf(&mut self, tab: Tab) {
-        for (index, node) in &mut self.tree.iter_mut().enumerate() {
-            match node {
-                Node::Leaf { tabs, active, .. } => {
-                    *active = TabIndex(tabs.len());
-                    tabs.push(tab);
-                    self.focused_node = Some(NodeIndex(index));
-                    return;
-                }
-                Node::Empty => {
-                    *node = Node::leaf(tab);
-                    self.focused_node = Some(NodeIndex(index));
-                    return;
+    /// Pushes `tab` to the focused leaf, or to the first leaf if none is focused, without making it the active tab
+    /// or changing the focused leaf. Useful for tabs opened by background tasks, like a log, which shouldn't pull the
+    /// user away from what they're doing.
+    pub fn push_in_background(&mut self, tab: Tab) {
+        let policy = self.tab_insert_policy;
+        let leaf = self
+            .focused_node
+            .filter(|node| self.tree.get(node.0).map_or(false, Node::is_leaf))
+            .or_else(|| self.tree.iter().position(Node::is_leaf).map(NodeIndex));
+        match leaf {
+            Some(leaf) => {
+                if let Node::Leaf { tabs, active, .. } = &mut self.tree[leaf.0] {
+                    policy.insert(tabs, active, tab, false);
                 }
-                _ => {}
+            }
+            None => {
+                let focused = self.focused_node;
+                self.push_to_first_leaf(tab);
+                self.focused_node = focused;
             }
         }
-        assert!(self.tree.is_empty());
-        self.tree.push(Node::leaf_with(vec![tab]));
-        self.focused_node = Some(NodeIndex(0));
+    }
+
+    /// Sets where [`Tree::push_to_focused_leaf`] and [`Tree::push_to_first_leaf`] put new tabs in their leaf. By
+    /// `Default` it's [`TabInsertPolicy::Append`].
+    pub fn set_tab_insert_policy(&mut self, policy: TabInsertPolicy) {
+        self.tab_insert_policy = policy;
+    }
+
+    /// Returns where new tabs are put in their leaf, see [`Tree::set_tab_insert_policy`].
+    pub fn tab_insert_policy(&self) -> TabInsertPolicy {
+        self.tab_insert_policy
     }
 
     /// Currently focused leaf.
+    ///
+    /// [`DockEvent::FocusChanged`](crate::DockEvent::FocusChanged) reports when the user focuses another leaf.
     pub fn focused_leaf(&self) -> Option<NodeIndex> {
         self.focused_node
     }
 
+    /// Returns the active tab of the focused leaf, the one keyboard shortcuts of the app are usually meant for.
+    pub fn focused_tab(&self) -> Option<(NodeIndex, TabIndex)> {
+        let node_index = self.focused_node?;
+        match self.tree.get(node_index.0)? {
+            Node::Leaf { tabs, active, .. } if active.0 < tabs.len() => Some((node_index, *active)),
+            _ => None,
+        }
+    }
+
+    /// Focuses the leaf at `node_index`. Unlike [`Tree::set_focused_node`], the focus is left alone if there's no
+    /// leaf at `node_index`.
+    ///
+    /// Returns `true` if the leaf was focused, `false` otherwise.
+    pub fn set_focused_leaf(&mut self, node_index: NodeIndex) -> bool {
+        let is_leaf = self.tree.get(node_index.0).map_or(false, Node::is_leaf);
+        if is_leaf {
+            self.focused_node = Some(node_index);
+        }
+        is_leaf
+    }
+
     /// Sets the currently focused leaf to `node_index` if the node at `node_index` is a leaf.
     pub fn set_focused_node(&mut self, node_index: NodeIndex) {
         if let Some(Node::Leaf { .. }) = self.tree.get(node_index.0) {
@@ -594,6 +1974,86 @@ impl<Tab> Tree<Tab> {
         }
     }
 
+    /// Returns the leaf nearest to `node_index` in the given `direction`, or `None` if there's no leaf on that side.
+    ///
+    /// Only leaves overlapping `node_index` on the perpendicular axis are considered. The lookup uses the rects
+    /// from the last time the tree was shown. If `node_index` wasn't shown yet, like in a tree built before the first
+    /// frame, the leaf of the sibling on that side of the nearest split is taken instead.
+    pub fn adjacent_leaf(&self, node_index: NodeIndex, direction: Split) -> Option<NodeIndex> {
+        let from = match self.tree.get(node_index.0) {
+            Some(Node::Leaf { rect, .. }) => *rect,
+            _ => return None,
+        };
+        if !from.is_positive() {
+            return self.adjacent_leaf_in_tree(node_index, direction);
+        }
+
+        self.tree
+            .iter()
+            .enumerate()
+            .filter_map(|(index, node)| match node {
+                Node::Leaf { rect, .. } if index != node_index.0 => Some((index, *rect)),
+                _ => None,
+            })
+            .filter_map(|(index, to)| {
+                let (gap, offset) = match direction {
+                    Split::Left if overlaps(from.y_range(), to.y_range()) => {
+                        (from.min.x - to.max.x, from.center().y - to.center().y)
+                    }
+                    Split::Right if overlaps(from.y_range(), to.y_range()) => {
+                        (to.min.x - from.max.x, from.center().y - to.center().y)
+                    }
+                    Split::Above if overlaps(from.x_range(), to.x_range()) => {
+                        (from.min.y - to.max.y, from.center().x - to.center().x)
+                    }
+                    Split::Below if overlaps(from.x_range(), to.x_range()) => {
+                        (to.min.y - from.max.y, from.center().x - to.center().x)
+                    }
+                    _ => return None,
+                };
+                // Separators leave a small gap between neighbouring leaves, so allow a little overlap too.
+                (gap > -1.0).then_some((index, gap, offset.abs()))
+            })
+            .min_by(|(_, lhs_gap, lhs_offset), (_, rhs_gap, rhs_offset)| {
+                lhs_gap
+                    .total_cmp(rhs_gap)
+                    .then(lhs_offset.total_cmp(rhs_offset))
+            })
+            .map(|(index, _, _)| NodeIndex(index))
+    }
+
+    /// Like [`Tree::adjacent_leaf`], going by the structure of the tree rather than by rects: walks up to the nearest
+    /// split with a sibling on the `direction` side and down that sibling to its leaf nearest to `node_index`.
+    fn adjacent_leaf_in_tree(&self, node_index: NodeIndex, direction: Split) -> Option<NodeIndex> {
+        let forward = matches!(direction, Split::Right | Split::Below);
+        let along = |node: NodeIndex| match direction {
+            Split::Left | Split::Right => self.tree[node.0].is_horizontal(),
+            Split::Above | Split::Below => self.tree[node.0].is_vertical(),
+        };
+
+        let mut child = node_index;
+        let mut node = loop {
+            let parent = self.parent(child)?;
+            let [first, second] = self.shown_children(parent)?;
+            if along(parent) {
+                match (forward, child == first) {
+                    (true, true) => break second,
+                    (false, false) => break first,
+                    _ => {}
+                }
+            }
+            child = parent;
+        };
+        while let Some([first, second]) = self.shown_children(node) {
+            node = if along(node) && !forward {
+                second
+            } else {
+                first
+            };
+        }
+        self.tree[node.0].is_leaf().then_some(node)
+    }
+
     /// Sets which is the active tab within a specific node.
     pub fn set_active_tab(&mut self, node_index: NodeIndex, tab_index: TabIndex) {
         if let Some(Node::Leaf { active, .. }) = self.tree.get_mut(node_index.0) {
@@ -601,67 +2061,2063 @@ impl<Tab> Tree<Tab> {
         }
     }
 
-    /// Pushes `tab` to the currently focused leaf.
-    ///
-    /// If no leaf is focused it will be pushed to the first available leaf.
+    /// Activates the tab at `tab_index` of the leaf at `node_index` and focuses the leaf, like clicking the tab does.
     ///
-    /// If no leaf is available then a new leaf will be created.
-    pub fn push_to_focused_leaf(&mut self, tab: Tab) {
-        match self.focused_node {
-            Some(node) => {
-                if self.tree.is_empty() {
-                    self.tree.push(Node::leaf(tab));
-                    self.focused_node = Some(NodeIndex::root());
-                } else {
-                    match &mut self[node] {
-                        Node::Empty => {
-                            self[node] = Node::leaf(tab);
-                            self.focused_node = Some(node);
-                        }
-                        Node::Leaf { tabs, active, .. } => {
-                            *active = TabIndex(tabs.len());
-                            tabs.push(tab);
-                            self.focused_node = Some(node);
-                        }
-                        _ => {
-                            self.push_to_first_leaf(tab);
-                        }
-                    }
-                }
+    /// Returns `false` if there's no such tab.
+    pub fn set_focused_tab(&mut self, node_index: NodeIndex, tab_index: TabIndex) -> bool {
+        match self.tree.get_mut(node_index.0) {
+            Some(Node::Leaf { tabs, active, .. }) if tab_index.0 < tabs.len() => {
+                *active = tab_index;
+                self.focused_node = Some(node_index);
+                true
             }
-            None => {
-                if self.tree.is_empty() {
-                    self.tree.push(Node::leaf(tab));
-                    self.focused_node = Some(NodeIndex::root());
-                } else {
-                    self.push_to_first_leaf(tab);
+            _ => false,
+        }
+    }
+
+    /// Removes the tab at `tab_index` of the leaf at `node_index`, and the leaf too if that was its last tab.
+    ///
+    /// Returns the removed tab, or `None` if there's no such tab.
+    pub fn remove_tab(&mut self, node_index: NodeIndex, tab_index: TabIndex) -> Option<Tab> {
+        let tab = match self.tree.get_mut(node_index.0) {
+            Some(Node::Leaf { tabs, active, .. }) if tab_index.0 < tabs.len() => {
+                if *active >= tab_index {
+                    active.0 = active.0.saturating_sub(1);
                 }
+                tabs.remove(tab_index.0)
             }
-        }
+            _ => return None,
+        };
+        self.remove_leaf_if_empty(node_index);
+        Some(tab)
     }
-}
 
-impl<Tab> Tree<Tab>
-where
-    Tab: PartialEq,
-{
-    /// Find the given tab.
+    /// Closes every tab of the tree, like a "Close All" entry of a menu would. Hidden tabs are left alone.
     ///
-    /// Returns which node the tab is in, and where in that node the tab is in.
+    /// Tabs are closed the way the user closes them: ones which aren't [`TabViewer::closeable`] stay, and so do ones
+    /// whose [`TabViewer::on_close`] vetoes closing. [Pinned](TabViewer::is_pinned) tabs stay too. Leaves left
+    /// without tabs are removed.
     ///
-    /// The returned [`NodeIndex`] will always point to a [`Node::Leaf`].
+    /// Returns the closed tabs, with the leaf and the index they had.
+    pub fn close_all_tabs(
+        &mut self,
+        tab_viewer: &mut impl TabViewer<Tab = Tab>,
+    ) -> Vec<(NodeIndex, TabIndex, Tab)> {
+        self.close_tabs_where(tab_viewer, |_, _| true)
+    }
+
+    /// Closes every tab of the leaf at `node_index` but the one at `tab_index`, like the "Close Others" entry of the
+    /// menu of a tab title. Tabs are closed like with [`Tree::close_all_tabs`].
     ///
-    /// In case there are several hits, only the first is returned.
-    pub fn find_tab(&self, needle_tab: &Tab) -> Option<(NodeIndex, TabIndex)> {
-        for (node_index, node) in self.tree.iter().enumerate() {
-            if let Node::Leaf { tabs, .. } = node {
-                for (tab_index, tab) in tabs.iter().enumerate() {
-                    if tab == needle_tab {
-                        return Some((node_index.into(), tab_index.into()));
+    /// Returns the closed tabs, with the leaf and the index they had.
+    pub fn close_other_tabs(
+        &mut self,
+        node_index: NodeIndex,
+        tab_index: TabIndex,
+        tab_viewer: &mut impl TabViewer<Tab = Tab>,
+    ) -> Vec<(NodeIndex, TabIndex, Tab)> {
+        self.close_tabs_where(tab_viewer, |node, tab| {
+            node == node_index && tab != tab_index
+        })
+    }
+
+    /// Closes the tabs of the leaf at `node_index` after the one at `tab_index`, like the "Close to the Right" entry
+    /// of the menu of a tab title. Tabs are closed like with [`Tree::close_all_tabs`].
+    ///
+    /// Returns the closed tabs, with the leaf and the index they had.
+    pub fn close_tabs_to_right(
+        &mut self,
+        node_index: NodeIndex,
+        tab_index: TabIndex,
+        tab_viewer: &mut impl TabViewer<Tab = Tab>,
+    ) -> Vec<(NodeIndex, TabIndex, Tab)> {
+        self.close_tabs_where(tab_viewer, |node, tab| {
+            node == node_index && tab > tab_index
+        })
+    }
+
+    /// Closes the tabs `closes` picks which the tab viewer lets close, keeping the active tab of every leaf active if
+    /// it stays, or else the closest one before it. Only the leaves this empties are removed.
+    fn close_tabs_where(
+        &mut self,
+        tab_viewer: &mut impl TabViewer<Tab = Tab>,
+        mut closes: impl FnMut(NodeIndex, TabIndex) -> bool,
+    ) -> Vec<(NodeIndex, TabIndex, Tab)> {
+        let mut closed = Vec::new();
+        let mut emptied = Vec::new();
+        for (node_index, node) in self.tree.iter_mut().enumerate() {
+            if let Node::Leaf { tabs, active, .. } = node {
+                let node_index = NodeIndex(node_index);
+                let was_empty = tabs.is_empty();
+                let mut kept = Vec::with_capacity(tabs.len());
+                let mut new_active = 0;
+                for (index, mut tab) in std::mem::take(tabs).into_iter().enumerate() {
+                    if closes(node_index, TabIndex(index))
+                        && !tab_viewer.is_pinned(&tab)
+                        && tab_viewer.closeable(&tab)
+                        && tab_viewer.on_close(&mut tab)
+                    {
+                        closed.push((node_index, TabIndex(index), tab));
+                    } else {
+                        if index <= active.0 {
+                            new_active = kept.len();
+                        }
+                        kept.push(tab);
                     }
                 }
+                if kept.is_empty() && !was_empty {
+                    emptied.push(node_index);
+                }
+                *tabs = kept;
+                *active = TabIndex(new_active);
             }
         }
-        None
+        for node_index in emptied {
+            self.remove_leaf_if_empty(node_index);
+        }
+        closed
+    }
+
+    /// Moves the tab at `src` to the end of the leaf at `dst`, activating it and focusing that leaf. The leaf the tab
+    /// was in is removed if that was its last tab.
+    ///
+    /// Returns where the tab ended up, or `None` if there's no such tab or `dst` isn't a leaf.
+    pub fn move_tab(
+        &mut self,
+        src: (NodeIndex, TabIndex),
+        dst: NodeIndex,
+    ) -> Option<(NodeIndex, TabIndex)> {
+        let (src_node, src_tab) = src;
+        if src_tab.0 >= self.tree.get(src_node.0)?.tabs_count() || !self.tree.get(dst.0)?.is_leaf()
+        {
+            return None;
+        }
+
+        let tab = match &mut self[src_node] {
+            Node::Leaf { tabs, active, .. } => {
+                if *active >= src_tab {
+                    active.0 = active.0.saturating_sub(1);
+                }
+                tabs.remove(src_tab.0)
+            }
+            _ => return None,
+        };
+        self[dst].append_tab(tab);
+        self.remove_leaf_if_empty(src_node);
+
+        let tab_index = TabIndex(self[dst].tabs_count() - 1);
+        self.set_focused_tab(dst, tab_index);
+        Some((dst, tab_index))
+    }
+
+    /// Moves the tab at `src` out of its leaf into a new leaf split off on the `split` side of it, like dropping a
+    /// dragged tab onto that side of its own leaf does. `fraction` is how much of the area the old leaf keeps, see
+    /// [`Tree::split`]. The new leaf is focused.
+    ///
+    /// ```rust
+    /// # use egui_dock::{Split, TabIndex, Tree};
+    /// let mut tree = Tree::new(vec!["main.rs", "lib.rs"]);
+    /// let editor = tree.split_tab((tree.root().unwrap(), TabIndex(1)), Split::Right, 0.5);
+    /// assert_eq!(tree.find_tab(&"lib.rs"), editor.map(|node| (node, TabIndex(0))));
+    /// ```
+    ///
+    /// Returns the new leaf, or `None` if there's no such tab or it's the only tab of its leaf.
+    pub fn split_tab(
+        &mut self,
+        src: (NodeIndex, TabIndex),
+        split: Split,
+        fraction: f32,
+    ) -> Option<NodeIndex> {
+        let (node, tab_index) = src;
+        let tabs_count = self.tree.get(node.0)?.tabs_count();
+        if tab_index.0 >= tabs_count || tabs_count < 2 {
+            return None;
+        }
+        let tab = match &mut self[node] {
+            Node::Leaf { tabs, active, .. } => {
+                if *active >= tab_index {
+                    active.0 = active.0.saturating_sub(1);
+                }
+                tabs.remove(tab_index.0)
+            }
+            _ => return None,
+        };
+        let [_, new] = self.split(node, split, fraction, Node::leaf(tab));
+        self.set_focused_node(new);
+        Some(new)
+    }
+
+    /// Moves the tab at `src` to `target` of the leaf at `dst`, like dropping a dragged tab there does, without
+    /// needing a `Ui`. The leaf the tab came from is removed if that was its last tab.
+    ///
+    /// ```rust
+    /// # use egui_dock::{DropTarget, Split, TabIndex, Tree};
+    /// let mut tree = Tree::new(vec!["main.rs", "lib.rs"]);
+    /// let root = tree.root().unwrap();
+    /// let (node, _) = tree
+    ///     .drop_tab((root, TabIndex(0)), root, DropTarget::Split(Split::Below))
+    ///     .unwrap();
+    /// assert_eq!(tree.find_tab(&"main.rs"), Some((node, TabIndex(0))));
+    /// ```
+    ///
+    /// Returns where the tab ended up, and the index of the leaf removed if one was emptied, or `None` if there's no
+    /// such tab or `dst` isn't a leaf. Other nodes keep their index.
+    pub fn drop_tab(
+        &mut self,
+        src: (NodeIndex, TabIndex),
+        dst: NodeIndex,
+        target: DropTarget,
+    ) -> Option<(NodeIndex, Option<NodeIndex>)> {
+        let (src_node, src_tab) = src;
+        let is_leaf = |node: NodeIndex| self.tree.get(node.0).map_or(false, Node::is_leaf);
+        if !is_leaf(dst) || !is_leaf(src_node) || src_tab.0 >= self[src_node].tabs_count() {
+            return None;
+        }
+
+        if let Node::Leaf { active, .. } = &mut self[src_node] {
+            if *active >= src_tab {
+                active.0 = active.0.saturating_sub(1);
+            }
+        }
+        let tab = self[src_node].remove_tab(src_tab)?;
+
+        // Tabs moved further along their own tab bar leave a gap behind them.
+        let target = match target {
+            DropTarget::Tabs(Some(index)) if src_node == dst && index > src_tab => {
+                DropTarget::Tabs(Some(TabIndex(index.0 - 1)))
+            }
+            target => target,
+        };
+        let dst = self.place_tab(tab, dst, target);
+
+        let removed = self.remove_leaf_if_empty(src_node).then_some(src_node);
+        for node in self.tree.iter_mut() {
+            if let Node::Leaf { tabs, active, .. } = node {
+                if active.0 >= tabs.len() {
+                    active.0 = 0;
+                }
+            }
+        }
+        Some((dst, removed))
+    }
+
+    /// Puts a new `tab` at `target` of the leaf at `dst`, like [`Tree::drop_tab`] does with a tab taken from its leaf.
+    ///
+    /// Returns where the tab ended up, or `None` if `dst` isn't a leaf.
+    pub fn drop_new_tab(
+        &mut self,
+        tab: Tab,
+        dst: NodeIndex,
+        target: DropTarget,
+    ) -> Option<(NodeIndex, TabIndex)> {
+        if !self.tree.get(dst.0).map_or(false, Node::is_leaf) {
+            return None;
+        }
+        let dst = self.place_tab(tab, dst, target);
+        match &self[dst] {
+            Node::Leaf { active, .. } => Some((dst, *active)),
+            _ => None,
+        }
+    }
+
+    /// Puts `tab` at `target` of the leaf at `dst`, making it the active tab of its leaf and focusing it.
+    fn place_tab(&mut self, tab: Tab, dst: NodeIndex, target: DropTarget) -> NodeIndex {
+        match target {
+            DropTarget::Edge(split) => {
+                let fraction = match split {
+                    Split::Left | Split::Above => EDGE_SPLIT_SHARE,
+                    Split::Right | Split::Below => 1.0 - EDGE_SPLIT_SHARE,
+                };
+                self.split_root(split, fraction, Node::leaf(tab))[1]
+            }
+            DropTarget::Split(split) => self.split(dst, split, 0.5, Node::leaf(tab))[1],
+            DropTarget::Tabs(index) => {
+                match index {
+                    Some(index) => self[dst].insert_tab(index, tab),
+                    None => self[dst].append_tab(tab),
+                }
+                self.set_focused_node(dst);
+                dst
+            }
+        }
+    }
+
+    /// Pushes `tab` to the currently focused leaf.
+    ///
+    /// If no leaf is focused it will be pushed to the first available leaf.
+    ///
+    /// If no leaf is available then a new leaf will be created.
+    pub fn push_to_focused_leaf(&mut self, tab: Tab) {
+        let policy = self.tab_insert_policy;
+        match self
+            .focused_node
+            .map(|node| (node, self.tree.get_mut(node.0)))
+        {
+            Some((node, Some(Node::Leaf { tabs, active, .. }))) => {
+                policy.insert(tabs, active, tab, true);
+                self.focused_node = Some(node);
+            }
+            _ => self.push_to_first_leaf(tab),
+        }
+    }
+}
+
+impl<Tab> Tree<Tab> {
+    /// Returns a copy of the tree, with the same layout, with `f` applied to every tab, including hidden ones.
+    ///
+    /// Useful for saving a tree of tabs which can't be serialized themselves, like with
+    /// [`TabRegistry`](crate::TabRegistry). The undo history isn't copied.
+    pub fn map_tabs<NewTab>(&self, mut f: impl FnMut(&Tab) -> NewTab) -> Tree<NewTab> {
+        Tree {
+            tree: self.tree.iter().map(|node| node.map_tabs(&mut f)).collect(),
+            focused_node: self.focused_node,
+            slots: self.slots.clone(),
+            root: self.root,
+            next_generation: self.next_generation,
+            free: self.free.clone(),
+            history: None,
+            recent: self.recent.clone(),
+            hidden: self
+                .hidden
+                .iter()
+                .map(|hidden| HiddenTab {
+                    tab: f(&hidden.tab),
+                    leaf: hidden.leaf,
+                    index: hidden.index,
+                })
+                .collect(),
+            zen: self.zen.as_ref().map(|zen| {
+                Box::new(Zen {
+                    mode: zen.mode,
+                    layout: Snapshot {
+                        tree: zen
+                            .layout
+                            .tree
+                            .iter()
+                            .map(|node| node.map_tabs(&mut f))
+                            .collect(),
+                        slots: zen.layout.slots.clone(),
+                        root: zen.layout.root,
+                        free: zen.layout.free.clone(),
+                        focused_node: zen.layout.focused_node,
+                    },
+                    leaf: zen.leaf,
+                })
+            }),
+            links: self.links.clone(),
+            mdi: self.mdi.clone(),
+            resize_policies: self.resize_policies.clone(),
+            min_sizes: self.min_sizes.clone(),
+            single_tab_bars: self.single_tab_bars.clone(),
+            tab_bar_positions: self.tab_bar_positions.clone(),
+            collapsed: self.collapsed.clone(),
+            node_styles: self.node_styles.clone(),
+            tab_insert_policy: self.tab_insert_policy,
+            geometry: self.geometry.clone(),
+        }
+    }
+
+    /// Turns the tree into one with the same layout, with every tab turned into the one `f` returns. Tabs `f` returns
+    /// `None` for are dropped, which may leave leaves without tabs.
+    ///
+    /// Useful for loading a tree saved with [`Tree::map_tabs`], like with [`TabRegistry`](crate::TabRegistry). The
+    /// undo history is cleared.
+    pub fn filter_map_tabs<NewTab>(self, mut f: impl FnMut(Tab) -> Option<NewTab>) -> Tree<NewTab> {
+        Tree {
+            tree: self
+                .tree
+                .into_iter()
+                .map(|node| node.filter_map_tabs(&mut f))
+                .collect(),
+            focused_node: self.focused_node,
+            slots: self.slots,
+            root: self.root,
+            next_generation: self.next_generation,
+            free: self.free,
+            history: None,
+            recent: self.recent,
+            hidden: self
+                .hidden
+                .into_iter()
+                .filter_map(|hidden| {
+                    Some(HiddenTab {
+                        tab: f(hidden.tab)?,
+                        leaf: hidden.leaf,
+                        index: hidden.index,
+                    })
+                })
+                .collect(),
+            zen: self.zen.map(|zen| {
+                Box::new(Zen {
+                    mode: zen.mode,
+                    layout: Snapshot {
+                        tree: zen
+                            .layout
+                            .tree
+                            .into_iter()
+                            .map(|node| node.filter_map_tabs(&mut f))
+                            .collect(),
+                        slots: zen.layout.slots,
+                        root: zen.layout.root,
+                        free: zen.layout.free,
+                        focused_node: zen.layout.focused_node,
+                    },
+                    leaf: zen.leaf,
+                })
+            }),
+            links: self.links,
+            mdi: self.mdi,
+            resize_policies: self.resize_policies,
+            min_sizes: self.min_sizes,
+            single_tab_bars: self.single_tab_bars,
+            tab_bar_positions: self.tab_bar_positions,
+            collapsed: self.collapsed,
+            node_styles: self.node_styles,
+            tab_insert_policy: self.tab_insert_policy,
+            geometry: Geometry::default(),
+        }
+    }
+
+    /// Rebuilds a tree saved with [`Tree::map_tabs`], making every tab from what it was saved as with `f`.
+    ///
+    /// Tabs `f` returns `None` for are dropped, like tabs of files which were deleted since, together with the leaves
+    /// they leave empty. Leaves which were already empty when saved are kept. With the `serde` feature, this is how a
+    /// layout of tabs which can't be serialized themselves is persisted:
+    ///
+    /// ```rust
+    /// # use egui_dock::Tree;
+    /// struct Editor {
+    ///     path: String,
+    /// }
+    ///
+    /// let tree = Tree::new(vec![Editor { path: "main.rs".to_owned() }]);
+    ///
+    /// // `saved` can be serialized, like to restore the layout on startup.
+    /// let saved: Tree<String> = tree.map_tabs(|editor| editor.path.clone());
+    ///
+    /// let tree = Tree::restore(saved, |path| Some(Editor { path }));
+    /// # assert_eq!(tree.tabs().count(), 1);
+    /// ```
+    pub fn restore<Saved>(saved: Tree<Saved>, f: impl FnMut(Saved) -> Option<Tab>) -> Self {
+        let had_tabs: Vec<NodeIndex> = saved
+            .node_indices()
+            .filter(|&node_index| saved[node_index].tabs_count() > 0)
+            .collect();
+        let mut tree = saved.filter_map_tabs(f);
+        for node_index in had_tabs {
+            tree.remove_leaf_if_empty(node_index);
+        }
+        tree
+    }
+
+    /// Describes the layout of the tree without its tabs, which are replaced by the key `f` gives each of them, like
+    /// their [`TabViewer::id`](crate::TabViewer::id) or a name of your own.
+    ///
+    /// Layouts keep the splits, their fractions and where every tab goes. With the `serde` feature they can be saved,
+    /// or shipped with the app as presets to rearrange the tabs with [`Tree::apply_layout`].
+    pub fn to_layout<Key>(&self, f: impl FnMut(&Tab) -> Key) -> Tree<Key> {
+        self.map_tabs(f)
+    }
+
+    /// Rearranges the tabs of the tree as described by `layout`, keeping the tabs themselves and their state.
+    ///
+    /// Every tab goes where `layout` has the key `f` gives it. Tabs missing from `layout` are pushed to the focused
+    /// leaf, and keys of `layout` with no tab are skipped, removing the leaves left without tabs. Zen mode is left
+    /// first, hidden tabs stay hidden and the undo history is kept, with the change recorded in it.
+    ///
+    /// ```rust
+    /// # use egui_dock::Tree;
+    /// let mut editing = Tree::new(vec!["Files".to_owned(), "Editor".to_owned()]);
+    /// let [_, outline] = editing.split_right(editing.root().unwrap(), 0.75, vec!["Outline".to_owned()]);
+    ///
+    /// let mut tree = Tree::new(vec!["Editor".to_owned(), "Outline".to_owned(), "Files".to_owned()]);
+    /// tree.apply_layout(&editing, |tab| tab.clone());
+    /// assert_eq!(tree.tabs().count(), 3);
+    /// assert_eq!(tree.find_tab(&"Outline".to_owned()), Some((outline, 0.into())));
+    /// ```
+    pub fn apply_layout<Key: PartialEq>(
+        &mut self,
+        layout: &Tree<Key>,
+        mut f: impl FnMut(&Tab) -> Key,
+    ) {
+        self.record_history();
+        self.apply_layout_with(layout, |tab| f(tab));
+    }
+
+    /// Like [`Tree::apply_layout`], for keys only given by mutable access, like
+    /// [`TabViewer::id`](crate::TabViewer::id). Nothing is recorded in the history, restoring a layout on startup
+    /// isn't something to undo.
+    pub(crate) fn apply_layout_with<Key: PartialEq>(
+        &mut self,
+        layout: &Tree<Key>,
+        mut f: impl FnMut(&mut Tab) -> Key,
+    ) {
+        self.exit_zen_mode();
+
+        let mut tabs = Vec::new();
+        for node in self.tree.iter_mut() {
+            if let Node::Leaf {
+                tabs: leaf_tabs, ..
+            } = node
+            {
+                tabs.extend(leaf_tabs.drain(..).map(|mut tab| (f(&mut tab), Some(tab))));
+            }
+        }
+        let placed = layout.map_tabs(|key| {
+            tabs.iter_mut()
+                .find(|(tab_key, tab)| tab_key == key && tab.is_some())
+                .and_then(|(_, tab)| tab.take())
+        });
+
+        let mut tree = Tree::restore(placed, |tab| tab);
+        tree.history = self.history.take();
+        tree.recent = std::mem::take(&mut self.recent);
+        tree.hidden = std::mem::take(&mut self.hidden);
+        for hidden in &mut tree.hidden {
+            // Leaves of the old layout are gone, hidden tabs come back to the focused leaf.
+            hidden.leaf = None;
+        }
+        tree.tab_insert_policy = self.tab_insert_policy;
+        *self = tree;
+
+        for (_, tab) in tabs {
+            if let Some(tab) = tab {
+                self.push_to_focused_leaf(tab);
+            }
+        }
+    }
+}
+
+fn overlaps(lhs: RangeInclusive<f32>, rhs: RangeInclusive<f32>) -> bool {
+    lhs.start() < rhs.end() && rhs.start() < lhs.end()
+}
+
+impl<Tab> Tree<Tab> {
+    /// Marks the tab whose [`TabViewer::id`] is `id` as the most recently activated one.
+    ///
+    /// [`DockArea`](crate::DockArea) does it for the active tab of the focused leaf every frame.
+    pub fn touch_recent(&mut self, id: Id) {
+        if self.recent.first() != Some(&id) {
+            self.recent.retain(|&recent| recent != id);
+            self.recent.insert(0, id);
+            self.recent.truncate(RECENT_CAPACITY);
+        }
+    }
+
+    /// Forgets the tab whose [`TabViewer::id`] is `id` was ever activated, like when it's closed.
+    pub fn forget_recent(&mut self, id: Id) {
+        self.recent.retain(|&recent| recent != id);
+    }
+
+    /// Returns the tabs in the order they were last activated, most recent first, for things like MRU tab switchers.
+    ///
+    /// Tabs are recognized by their [`TabViewer::id`], so they're found wherever they were moved to. Tabs which
+    /// were never activated aren't included.
+    pub fn recent_tabs(
+        &mut self,
+        tab_viewer: &mut impl TabViewer<Tab = Tab>,
+    ) -> Vec<(NodeIndex, TabIndex)> {
+        let recent = &self.recent;
+        let mut found = Vec::new();
+        for (node_index, node) in self.tree.iter_mut().enumerate() {
+            if let Node::Leaf { tabs, .. } = node {
+                for (tab_index, tab) in tabs.iter_mut().enumerate() {
+                    let id = tab_viewer.id(tab);
+                    if let Some(rank) = recent.iter().position(|&recent| recent == id) {
+                        found.push((rank, NodeIndex(node_index), TabIndex(tab_index)));
+                    }
+                }
+            }
+        }
+        found.sort_by_key(|&(rank, ..)| rank);
+        found
+            .into_iter()
+            .map(|(_, node, tab)| (node, tab))
+            .collect()
+    }
+
+    /// Activates the tab of the leaf at `node_index` which was activated most recently, if any of them was.
+    pub(crate) fn activate_recent(
+        &mut self,
+        node_index: NodeIndex,
+        tab_viewer: &mut impl TabViewer<Tab = Tab>,
+    ) {
+        let recent = &self.recent;
+        if let Some(Node::Leaf { tabs, active, .. }) = self.tree.get_mut(node_index.0) {
+            let most_recent = tabs
+                .iter_mut()
+                .enumerate()
+                .filter_map(|(tab_index, tab)| {
+                    let id = tab_viewer.id(tab);
+                    let rank = recent.iter().position(|&recent| recent == id)?;
+                    Some((rank, tab_index))
+                })
+                .min();
+            if let Some((_, tab_index)) = most_recent {
+                *active = TabIndex(tab_index);
+            }
+        }
+    }
+
+    /// Records the current layout, so the change about to be made can be reverted with [`Tree::undo`].
+    /// Does nothing unless the history is enabled with [`Tree::set_history_capacity`].
+    ///
+    /// [`DockArea`](crate::DockArea) records the layout before every tab it moves, splits off or closes. Call this
+    /// before changing the tree yourself, otherwise undoing the change before yours reverts yours too. A tab moved
+    /// between two surfaces of a [`DockState`](crate::DockState) is recorded in both trees, undoing the move in only
+    /// one of them loses or duplicates it.
+    pub fn record_history(&mut self) {
+        let snapshot = match &self.history {
+            Some(history) => (history.snapshot)(self),
+            None => return,
+        };
+        if let Some(history) = &mut self.history {
+            if history.undo.len() == history.capacity {
+                history.undo.pop_front();
+            }
+            history.undo.push_back(snapshot);
+            history.redo.clear();
+        }
+    }
+
+    /// Reverts the layout to the one recorded last.
+    ///
+    /// Returns `true` if there was a layout to revert to, `false` otherwise.
+    pub fn undo(&mut self) -> bool {
+        let snapshot = match self
+            .history
+            .as_mut()
+            .and_then(|history| history.undo.pop_back())
+        {
+            Some(snapshot) => snapshot,
+            None => return false,
+        };
+        let current = self.restore_snapshot(snapshot);
+        if let Some(history) = &mut self.history {
+            history.redo.push(current);
+        }
+        true
+    }
+
+    /// Brings back the layout reverted by the last [`Tree::undo`], unless the layout was changed since.
+    ///
+    /// Returns `true` if there was a layout to bring back, `false` otherwise.
+    pub fn redo(&mut self) -> bool {
+        let snapshot = match self.history.as_mut().and_then(|history| history.redo.pop()) {
+            Some(snapshot) => snapshot,
+            None => return false,
+        };
+        let current = self.restore_snapshot(snapshot);
+        if let Some(history) = &mut self.history {
+            history.undo.push_back(current);
+        }
+        true
+    }
+
+    /// Forgets every layout recorded for [`Tree::undo`] and [`Tree::redo`].
+    fn clear_history(&mut self) {
+        if let Some(history) = &mut self.history {
+            history.undo.clear();
+            history.redo.clear();
+        }
+    }
+
+    /// Returns `true` if [`Tree::undo`] has a layout to revert to, `false` otherwise.
+    pub fn can_undo(&self) -> bool {
+        self.history
+            .as_ref()
+            .map_or(false, |history| !history.undo.is_empty())
+    }
+
+    /// Returns `true` if [`Tree::redo`] has a layout to bring back, `false` otherwise.
+    pub fn can_redo(&self) -> bool {
+        self.history
+            .as_ref()
+            .map_or(false, |history| !history.redo.is_empty())
+    }
+
+    /// Swaps `snapshot` in, returning the layout it replaced.
+    fn restore_snapshot(&mut self, snapshot: Snapshot<Tab>) -> Snapshot<Tab> {
+        Snapshot {
+            tree: std::mem::replace(&mut self.tree, snapshot.tree),
+            slots: std::mem::replace(&mut self.slots, snapshot.slots),
+            root: std::mem::replace(&mut self.root, snapshot.root),
+            free: std::mem::replace(&mut self.free, snapshot.free),
+            focused_node: std::mem::replace(&mut self.focused_node, snapshot.focused_node),
+        }
+    }
+}
+
+impl<Tab> Tree<Tab>
+where
+    Tab: Clone,
+{
+    /// Keeps up to `capacity` layouts from before moving, splitting off and closing tabs, to revert to with
+    /// [`Tree::undo`]. By `Default` it's `0`, which keeps none.
+    ///
+    /// Layouts are recorded by cloning the whole tree, tabs included. Undoing a change brings back the tabs as they
+    /// were before it, so tabs with state of their own should keep it behind a shared handle, like an
+    /// `Rc<RefCell<_>>`.
+    pub fn set_history_capacity(&mut self, capacity: usize) {
+        if capacity == 0 {
+            self.history = None;
+            return;
+        }
+        let history = self.history.get_or_insert_with(|| {
+            Box::new(History {
+                capacity,
+                undo: VecDeque::new(),
+                redo: Vec::new(),
+                snapshot: Self::snapshot,
+            })
+        });
+        history.capacity = capacity;
+        while history.undo.len() > capacity {
+            history.undo.pop_front();
+        }
+    }
+
+    fn snapshot(&self) -> Snapshot<Tab> {
+        Snapshot {
+            tree: self.tree.clone(),
+            slots: self.slots.clone(),
+            root: self.root,
+            free: self.free.clone(),
+            focused_node: self.focused_node,
+        }
+    }
+}
+
+impl<Tab> Tree<Tab> {
+    /// Links the separators of the parent nodes at `a` and `b`, so dragging either of them moves the other one to
+    /// the same position, like a header and a content pane sharing a column boundary.
+    ///
+    /// Both nodes have to be split in the same direction. Returns `false` if they aren't, `true` otherwise.
+    pub fn link_separators(&mut self, a: NodeIndex, b: NodeIndex) -> bool {
+        let same_direction = match (self.tree.get(a.0), self.tree.get(b.0)) {
+            (Some(Node::Horizontal { .. }), Some(Node::Horizontal { .. }))
+            | (Some(Node::Vertical { .. }), Some(Node::Vertical { .. })) => a != b,
+            _ => false,
+        };
+        if !same_direction {
+            return false;
+        }
+        let (a, b) = match (self.node_id(a), self.node_id(b)) {
+            (Some(a), Some(b)) => (a, b),
+            _ => return false,
+        };
+        self.prune_links();
+        if !self.links.contains(&[a, b]) && !self.links.contains(&[b, a]) {
+            self.links.push([a, b]);
+        }
+        true
+    }
+
+    /// Unlinks the separator of the parent node at `node_index` from every separator it was linked to.
+    pub fn unlink_separators(&mut self, node_index: NodeIndex) {
+        if let Some(id) = self.node_id(node_index) {
+            self.links.retain(|link| !link.contains(&id));
+        }
+    }
+
+    /// Moves the separators linked to the one of `node_index` to where it is now, as of the last layout.
+    ///
+    /// Returns the nodes whose fractions changed together with their new fractions.
+    pub(crate) fn align_linked_separators(
+        &mut self,
+        node_index: NodeIndex,
+    ) -> Vec<(NodeIndex, f32)> {
+        let id = match self.node_id(node_index) {
+            Some(id) => id,
+            None => return Vec::new(),
+        };
+        let is_horizontal = self[node_index].is_horizontal();
+        let position = match &self[node_index] {
+            Node::Horizontal { rect, fraction } => rect.min.x + rect.width() * fraction,
+            Node::Vertical { rect, fraction } => rect.min.y + rect.height() * fraction,
+            _ => return Vec::new(),
+        };
+
+        let linked: Vec<NodeIndex> = self
+            .links
+            .iter()
+            .filter_map(|&[a, b]| match (a == id, b == id) {
+                (true, _) => Some(b),
+                (_, true) => Some(a),
+                _ => None,
+            })
+            .filter_map(|linked| self.node_index(linked))
+            .collect();
+
+        let mut aligned = Vec::new();
+        for linked in linked {
+            let fraction = match &mut self[linked] {
+                Node::Horizontal { rect, fraction } if is_horizontal && rect.width() > 0.0 => {
+                    *fraction = ((position - rect.min.x) / rect.width()).clamp(0.0, 1.0);
+                    *fraction
+                }
+                Node::Vertical { rect, fraction } if !is_horizontal && rect.height() > 0.0 => {
+                    *fraction = ((position - rect.min.y) / rect.height()).clamp(0.0, 1.0);
+                    *fraction
+                }
+                _ => continue,
+            };
+            aligned.push((linked, fraction));
+        }
+        aligned
+    }
+
+    /// Forgets the links of nodes which no longer exist.
+    fn prune_links(&mut self) {
+        let mut links = std::mem::take(&mut self.links);
+        links.retain(|link| link.iter().all(|id| self.node_index(*id).is_some()));
+        self.links = links;
+    }
+
+    /// Shows the tabs of the leaf at `node_index` as child windows which can be moved and resized inside the leaf,
+    /// instead of stacking them behind a tab bar. Setting `mdi` back to `false` brings the tab bar back.
+    ///
+    /// Returns `false` if there's no leaf at `node_index`, `true` otherwise.
+    pub fn set_mdi(&mut self, node_index: NodeIndex, mdi: bool) -> bool {
+        if !self.tree.get(node_index.0).map_or(false, Node::is_leaf) {
+            return false;
+        }
+        let id = match self.node_id(node_index) {
+            Some(id) => id,
+            None => return false,
+        };
+        let mut leaves = std::mem::take(&mut self.mdi);
+        leaves.retain(|leaf| *leaf != id && self.node_index(*leaf).is_some());
+        if mdi {
+            leaves.push(id);
+        }
+        self.mdi = leaves;
+        true
+    }
+
+    /// Whether the leaf at `node_index` shows its tabs as child windows, see [`Tree::set_mdi`].
+    pub fn is_mdi(&self, node_index: NodeIndex) -> bool {
+        self.node_id(node_index)
+            .map_or(false, |id| self.mdi.contains(&id))
+    }
+
+    /// Sets how the parent node at `node_index` shares out a change of its size, like when the window is maximized,
+    /// between its children. By `Default` it's [`ResizePolicy::Proportional`].
+    ///
+    /// Dragging the separator still resizes both children. Returns `false` if there's no parent node at
+    /// `node_index`, `true` otherwise.
+    ///
+    /// ```
+    /// # use egui_dock::{ResizePolicy, Tree};
+    /// let mut tree = Tree::new(vec!["main.rs"]);
+    /// tree.split_below(tree.root().unwrap(), 0.75, vec!["Console"]);
+    /// // The console keeps its height when the window grows, the editor above takes the extra space.
+    /// let split = tree.root().unwrap();
+    /// assert!(tree.set_resize_policy(split, ResizePolicy::KeepSecond));
+    /// assert_eq!(tree.resize_policy(split), ResizePolicy::KeepSecond);
+    /// ```
+    pub fn set_resize_policy(&mut self, node_index: NodeIndex, policy: ResizePolicy) -> bool {
+        if !self.tree.get(node_index.0).map_or(false, Node::is_parent) {
+            return false;
+        }
+        let id = match self.node_id(node_index) {
+            Some(id) => id,
+            None => return false,
+        };
+        let policy = (policy != ResizePolicy::Proportional).then_some(policy);
+        self.set_node_value(|tree| &mut tree.resize_policies, id, policy);
+        true
+    }
+
+    /// Returns how the parent node at `node_index` shares out a change of its size, see [`Tree::set_resize_policy`].
+    pub fn resize_policy(&self, node_index: NodeIndex) -> ResizePolicy {
+        self.node_id(node_index)
+            .and_then(|id| self.resize_policies.get(id).copied())
+            .unwrap_or_default()
+    }
+
+    /// Keeps the node at `node_index` from being made smaller than `min_size` in points, by dragging separators or
+    /// shrinking the dock area, on top of [`Style::min_node_size`] for leaves. The minimums of nested nodes add up, so
+    /// parent nodes can't be made too small for their children either. `Vec2::ZERO` removes the minimum.
+    ///
+    /// Once there isn't enough room for every minimum, the space is shared out in proportion to them.
+    ///
+    /// Returns `false` if there's no node at `node_index`, `true` otherwise.
+    pub fn set_min_size(&mut self, node_index: NodeIndex, min_size: Vec2) -> bool {
+        if self.tree.get(node_index.0).map_or(true, Node::is_empty) {
+            return false;
+        }
+        let id = match self.node_id(node_index) {
+            Some(id) => id,
+            None => return false,
+        };
+        let min_size = (min_size != Vec2::ZERO).then_some(min_size.max(Vec2::ZERO));
+        self.set_node_value(|tree| &mut tree.min_sizes, id, min_size);
+        true
+    }
+
+    /// Returns the minimum size set with [`Tree::set_min_size`] for the node at `node_index`, `Vec2::ZERO` if there's
+    /// none.
+    pub fn min_size(&self, node_index: NodeIndex) -> Vec2 {
+        self.node_id(node_index)
+            .and_then(|id| self.min_sizes.get(id).copied())
+            .unwrap_or(Vec2::ZERO)
+    }
+
+    /// Overrides [`Style::hide_single_tab_bars`] for the leaf at `node_index`: `Some(true)` hides its tab bar while it
+    /// has a single tab, `Some(false)` always shows it and `None` goes back to the style.
+    ///
+    /// Returns `false` if the node at `node_index` isn't a leaf, `true` otherwise.
+    pub fn set_hide_single_tab_bar(&mut self, node_index: NodeIndex, hide: Option<bool>) -> bool {
+        if !self.tree.get(node_index.0).map_or(false, Node::is_leaf) {
+            return false;
+        }
+        let id = match self.node_id(node_index) {
+            Some(id) => id,
+            None => return false,
+        };
+        self.set_node_value(|tree| &mut tree.single_tab_bars, id, hide);
+        true
+    }
+
+    /// Shows the tab bar of the leaf at `node_index` at `position` instead of [`Style::tab_bar_position`], like at the
+    /// bottom of a console. `None` goes back to the style.
+    ///
+    /// Returns `false` if the node at `node_index` isn't a leaf, `true` otherwise.
+    pub fn set_tab_bar_position(
+        &mut self,
+        node_index: NodeIndex,
+        position: Option<TabBarPosition>,
+    ) -> bool {
+        if !self.tree.get(node_index.0).map_or(false, Node::is_leaf) {
+            return false;
+        }
+        let id = match self.node_id(node_index) {
+            Some(id) => id,
+            None => return false,
+        };
+        self.set_node_value(|tree| &mut tree.tab_bar_positions, id, position);
+        true
+    }
+
+    /// Returns the position set with [`Tree::set_tab_bar_position`] for the leaf at `node_index`, if any.
+    pub fn tab_bar_position(&self, node_index: NodeIndex) -> Option<TabBarPosition> {
+        let id = self.node_id(node_index)?;
+        self.tab_bar_positions.get(id).copied()
+    }
+
+    /// Where the tab bar of the leaf at `node_index` is shown with `style`.
+    ///
+    /// Collapsed leaves are a strip along the axis of their parent's split, their tab bar runs along it on the side
+    /// facing away from their sibling.
+    pub(crate) fn shown_tab_bar_position(
+        &self,
+        node_index: NodeIndex,
+        style: &Style,
+    ) -> TabBarPosition {
+        let position = self
+            .tab_bar_position(node_index)
+            .unwrap_or(style.tab_bar_position);
+        let parent = match self.parent(node_index) {
+            Some(parent) if self.is_collapsed(node_index) => parent,
+            _ => return position,
+        };
+        let first = self
+            .shown_children(parent)
+            .map_or(true, |[first, _]| first == node_index);
+        match (&self[parent], position.is_vertical(), first) {
+            (Node::Horizontal { .. }, false, true) => TabBarPosition::Left,
+            (Node::Horizontal { .. }, false, false) => TabBarPosition::Right,
+            (Node::Vertical { .. }, true, true) => TabBarPosition::Top,
+            (Node::Vertical { .. }, true, false) => TabBarPosition::Bottom,
+            _ => position,
+        }
+    }
+
+    /// Shows the node at `node_index` with `style` instead of the style of the [`DockArea`](crate::DockArea), like a
+    /// terminal with a dark tab bar in an otherwise light dock. `None` goes back to the style of the dock area.
+    ///
+    /// Leaves use it for their tab bar, tabs and body, and parent nodes for the separator between their children.
+    /// Sizes which the layout depends on, like the tab bar height and the separator width, still come from the style
+    /// of the dock area.
+    ///
+    /// Returns `false` if there's no node at `node_index`, `true` otherwise.
+    pub fn set_node_style(&mut self, node_index: NodeIndex, style: Option<Style>) -> bool {
+        let id = match self.node_id(node_index) {
+            Some(id) => id,
+            None => return false,
+        };
+        self.set_node_value(|tree| &mut tree.node_styles, id, style.map(Arc::new));
+        true
+    }
+
+    /// Returns the style set with [`Tree::set_node_style`] for the node at `node_index`, if any.
+    pub fn node_style(&self, node_index: NodeIndex) -> Option<&Style> {
+        self.shared_node_style(node_index).map(|style| &**style)
+    }
+
+    /// Like [`Tree::node_style`], but the style can be held on to cheaply while the tree is changed.
+    pub(crate) fn shared_node_style(&self, node_index: NodeIndex) -> Option<&Arc<Style>> {
+        let id = self.node_id(node_index)?;
+        self.node_styles.get(id)
+    }
+
+    /// Returns the override set with [`Tree::set_hide_single_tab_bar`] for the leaf at `node_index`, if any.
+    pub fn hide_single_tab_bar(&self, node_index: NodeIndex) -> Option<bool> {
+        let id = self.node_id(node_index)?;
+        self.single_tab_bars.get(id).copied()
+    }
+
+    /// Sets the value `values` keeps for the node `id`, or removes it if `value` is `None`, and forgets the values of
+    /// nodes removed since. Returns the value the node had.
+    fn set_node_value<T>(
+        &mut self,
+        values: fn(&mut Self) -> &mut NodeMap<T>,
+        id: NodeId,
+        value: Option<T>,
+    ) -> Option<T> {
+        let mut map = std::mem::take(values(self));
+        map.retain(|node| self.node_index(node).is_some());
+        let previous = map.set(id, value);
+        *values(self) = map;
+        previous
+    }
+
+    /// Works out how small every node can be into `sizes`, with leaves at least `min_leaf_size` large. `order` is the
+    /// nodes in [`Tree::top_down`] order.
+    fn min_sizes(
+        &self,
+        min_leaf_size: Vec2,
+        separator_width: f32,
+        order: &[NodeIndex],
+        sizes: &mut Vec<Vec2>,
+    ) {
+        sizes.clear();
+        sizes.resize(self.tree.len(), Vec2::ZERO);
+        if min_leaf_size == Vec2::ZERO && self.min_sizes.is_empty() {
+            return;
+        }
+        // Children come after their parents in `order`, so they're sized first backwards.
+        for &node_index in order.iter().rev() {
+            let index = node_index.0;
+            let child = |child: NodeIndex| sizes.get(child.0).copied().unwrap_or(Vec2::ZERO);
+            let (left, right) = match self.children(node_index) {
+                Some([left, right]) => (child(left), child(right)),
+                None => (Vec2::ZERO, Vec2::ZERO),
+            };
+            let size = match &self.tree[index] {
+                Node::Empty => continue,
+                Node::Leaf { .. } => min_leaf_size,
+                Node::Horizontal { .. } => {
+                    vec2(left.x + right.x + separator_width, left.y.max(right.y))
+                }
+                Node::Vertical { .. } => {
+                    vec2(left.x.max(right.x), left.y + right.y + separator_width)
+                }
+            };
+            sizes[index] = size.max(self.min_size(node_index));
+        }
+    }
+
+    /// Keeps one child of the parent node at `node_index` `size` points long while the node is resized, with the other
+    /// child taking every change, like a tool panel next to the document area. `policy` picks the child that keeps
+    /// its size, [`ResizePolicy::KeepFirst`] for the left-most or top-most one and [`ResizePolicy::KeepSecond`] for
+    /// the other one.
+    ///
+    /// The resize policy of the node is set to `policy`, see [`Tree::set_resize_policy`], and the size is applied the
+    /// next time the tree is laid out. Dragging the separator still changes it.
+    ///
+    /// ```rust
+    /// # use egui_dock::{ResizePolicy, Tree};
+    /// let mut tree = Tree::new(vec!["Documents"]);
+    /// tree.split_left(tree.root().unwrap(), 0.25, vec!["Tools"]);
+    /// tree.set_fixed_size(tree.root().unwrap(), ResizePolicy::KeepFirst, 250.0);
+    /// ```
+    ///
+    /// Returns `false` if there's no parent node at `node_index` or `policy` is [`ResizePolicy::Proportional`],
+    /// `true` otherwise.
+    pub fn set_fixed_size(
+        &mut self,
+        node_index: NodeIndex,
+        policy: ResizePolicy,
+        size: f32,
+    ) -> bool {
+        if policy == ResizePolicy::Proportional || !self.set_resize_policy(node_index, policy) {
+            return false;
+        }
+        if let Some(id) = self.node_id(node_index) {
+            self.geometry.fixed_sizes.set(id, Some(size.max(0.0)));
+        }
+        true
+    }
+
+    /// Changes the fraction of the parent node at `node_index` for the size set with [`Tree::set_fixed_size`], if
+    /// there's one waiting and the node has a rect.
+    ///
+    /// Returns `true` if the fraction was changed, `false` otherwise.
+    fn apply_fixed_size(&mut self, node_index: NodeIndex, separator_width: f32) -> bool {
+        let id = match self.node_id(node_index) {
+            Some(id) => id,
+            None => return false,
+        };
+        if self.geometry.fixed_sizes.get(id).is_none() {
+            return false;
+        }
+        let length = match &self.tree[node_index.0] {
+            Node::Horizontal { rect, .. } => rect.width(),
+            Node::Vertical { rect, .. } => rect.height(),
+            _ => return false,
+        };
+        let fixed = match self.fixed_fraction(node_index, length, separator_width) {
+            Some(fixed) => fixed,
+            None => return false,
+        };
+        self.geometry.fixed_sizes.remove(id);
+        if let Node::Horizontal { fraction, .. } | Node::Vertical { fraction, .. } =
+            &mut self.tree[node_index.0]
+        {
+            *fraction = fixed;
+        }
+        true
+    }
+
+    /// The fraction the parent node at `node_index` gets from the size set with [`Tree::set_fixed_size`] which
+    /// hasn't been applied yet, once it's `length` long.
+    fn fixed_fraction(
+        &self,
+        node_index: NodeIndex,
+        length: f32,
+        separator_width: f32,
+    ) -> Option<f32> {
+        let id = self.node_id(node_index)?;
+        let size = self.geometry.fixed_sizes.get(id)?;
+        if length <= 0.0 {
+            return None;
+        }
+        // The separator is centered on the fraction, so half of it comes out of each child.
+        let first = (size + separator_width * 0.5) / length;
+        let fraction = match self.resize_policy(node_index) {
+            ResizePolicy::KeepSecond => 1.0 - first,
+            _ => first,
+        };
+        Some(fraction.clamp(0.0, 1.0))
+    }
+
+    /// Collapses the leaf at `node_index` to its tab bar, giving the rest of its space to its sibling, like tool
+    /// windows minimized to the edge of IDEs. Clicking the tab bar of a collapsed leaf expands it back to the size it
+    /// had, and so does calling this with `false`. Tabs can also be collapsed with the "Collapse" entry of their
+    /// context menu.
+    ///
+    /// Leaves split above or below their sibling collapse to the height of a tab bar, and leaves beside it to
+    /// [`Style::vertical_tab_bar_width`], with their tab bar along their left or right edge while they're collapsed.
+    ///
+    /// Returns `false` if the node at `node_index` can't be collapsed, `true` otherwise.
+    pub fn set_collapsed(&mut self, node_index: NodeIndex, collapsed: bool) -> bool {
+        if !self.can_collapse(node_index) {
+            return false;
+        }
+        let (id, parent) = match (self.node_id(node_index), self.parent(node_index)) {
+            (Some(id), Some(parent)) => (id, parent),
+            _ => return false,
+        };
+        match (self.collapsed.get(id), collapsed) {
+            (None, true) => {
+                if let Node::Horizontal { fraction, .. } | Node::Vertical { fraction, .. } =
+                    self[parent]
+                {
+                    self.set_node_value(|tree| &mut tree.collapsed, id, Some(fraction));
+                }
+            }
+            (Some(_), false) => {
+                let restored = self.set_node_value(|tree| &mut tree.collapsed, id, None);
+                if let (
+                    Node::Horizontal { fraction, .. } | Node::Vertical { fraction, .. },
+                    Some(restored),
+                ) = (&mut self[parent], restored)
+                {
+                    *fraction = restored;
+                }
+            }
+            _ => {}
+        }
+        true
+    }
+
+    /// Whether the leaf at `node_index` is collapsed to its tab bar, see [`Tree::set_collapsed`].
+    pub fn is_collapsed(&self, node_index: NodeIndex) -> bool {
+        self.can_collapse(node_index)
+            && self
+                .node_id(node_index)
+                .map_or(false, |id| self.collapsed.get(id).is_some())
+    }
+
+    /// Whether the node at `node_index` is a leaf with a sibling.
+    pub(crate) fn can_collapse(&self, node_index: NodeIndex) -> bool {
+        self.parent(node_index).is_some()
+            && self.tree.get(node_index.0).map_or(false, Node::is_leaf)
+    }
+
+    /// The fraction the parent node at `node_index`, laid out in `rect`, has while one of its children is collapsed,
+    /// leaving that child `collapsed_size` wide beside its sibling or high above or below it.
+    fn collapsed_fraction(
+        &self,
+        node_index: NodeIndex,
+        rect: Rect,
+        separator_width: f32,
+        collapsed_size: Vec2,
+    ) -> Option<f32> {
+        let (length, collapsed_size, is_horizontal) = match self.tree.get(node_index.0) {
+            Some(Node::Horizontal { .. }) => (rect.width(), collapsed_size.x, true),
+            Some(Node::Vertical { .. }) => (rect.height(), collapsed_size.y, false),
+            _ => return None,
+        };
+        let [left, right] = self.children(node_index)?;
+        if length <= 0.0 {
+            return None;
+        }
+        let strip = ((collapsed_size + separator_width * 0.5) / length).clamp(0.0, 1.0);
+        // Horizontal nodes give `fraction` of their width to their right child, which is laid out on the left.
+        let (left_fraction, right_fraction) = if is_horizontal {
+            (1.0 - strip, strip)
+        } else {
+            (strip, 1.0 - strip)
+        };
+        let (child, collapsed) =
+            if self.is_collapsed(left) || self.collapse_progress(left).is_some() {
+                (left, left_fraction)
+            } else if self.is_collapsed(right) || self.collapse_progress(right).is_some() {
+                (right, right_fraction)
+            } else {
+                return None;
+            };
+
+        // Leaves being collapsed or expanded are shown part of the way from their expanded fraction.
+        match self.collapse_progress(child) {
+            Some((progress, expanded)) => Some(lerp(expanded..=collapsed, progress)),
+            None => Some(collapsed),
+        }
+    }
+
+    /// How far the leaf at `node_index` is collapsed while it's on its way to or from being collapsed, with the
+    /// fraction its parent has while it's expanded.
+    fn collapse_progress(&self, node_index: NodeIndex) -> Option<(f32, f32)> {
+        let id = self.node_id(node_index)?;
+        self.geometry.collapsing.get(id).copied()
+    }
+
+    /// Records how far the leaf at `node_index` is collapsed, `0.0` being expanded and `1.0` collapsed, while it's
+    /// animated between the two.
+    pub(crate) fn set_collapse_progress(&mut self, node_index: NodeIndex, progress: f32) {
+        let (id, parent) = match (self.node_id(node_index), self.parent(node_index)) {
+            (Some(id), Some(parent)) => (id, parent),
+            _ => return,
+        };
+        // Leaves removed while they were animated don't need their progress anymore.
+        let mut collapsing = std::mem::take(&mut self.geometry.collapsing);
+        collapsing.retain(|node| self.node_index(node).is_some());
+        self.geometry.collapsing = collapsing;
+        let in_flight = progress > 0.0 && progress < 1.0;
+        match self.geometry.collapsing.get_mut(id) {
+            Some((current, _)) if in_flight => *current = progress,
+            Some(_) => {
+                // The parent was shown part of the way, expanded leaves get their fraction back.
+                let (_, expanded) = self.geometry.collapsing.remove(id).unwrap_or_default();
+                if !self.is_collapsed(node_index) {
+                    if let Node::Horizontal { fraction, .. } | Node::Vertical { fraction, .. } =
+                        &mut self[parent]
+                    {
+                        *fraction = expanded;
+                    }
+                }
+            }
+            None if in_flight => {
+                if let Node::Horizontal { fraction, .. } | Node::Vertical { fraction, .. } =
+                    &self[parent]
+                {
+                    let expanded = *fraction;
+                    self.geometry.collapsing.set(id, Some((progress, expanded)));
+                }
+            }
+            None => {}
+        }
+    }
+
+    /// Returns how long the children of the parent node at `node_index` have to be along its split, the one its
+    /// fraction applies to first.
+    fn min_child_sizes(&self, node_index: NodeIndex, min_sizes: &[Vec2]) -> (f32, f32) {
+        let child = |child: NodeIndex| min_sizes.get(child.0).copied().unwrap_or(Vec2::ZERO);
+        let (left, right) = match self.children(node_index) {
+            Some([left, right]) => (child(left), child(right)),
+            None => (Vec2::ZERO, Vec2::ZERO),
+        };
+        match self.tree.get(node_index.0) {
+            Some(Node::Horizontal { .. }) => (right.x, left.x),
+            Some(Node::Vertical { .. }) => (left.y, right.y),
+            _ => (0.0, 0.0),
+        }
+    }
+
+    /// Clamps `fraction` for the parent node at `node_index` like [`Tree::layout_with_min_size`] would, with the
+    /// minimum sizes and the rect of the last time the tree was laid out.
+    pub(crate) fn clamp_to_min_sizes(
+        &self,
+        node_index: NodeIndex,
+        fraction: f32,
+        separator_width: f32,
+    ) -> f32 {
+        let length = match self.tree.get(node_index.0) {
+            Some(Node::Horizontal { rect, .. }) => rect.width(),
+            Some(Node::Vertical { rect, .. }) => rect.height(),
+            _ => return fraction,
+        };
+        let (first, second) = self.min_child_sizes(node_index, &self.geometry.min_sizes);
+        clamp_fraction(fraction, length, first, second, separator_width)
+    }
+
+    /// Changes the fraction of the parent node at `node_index`, which was `previous` large and just got its new rect,
+    /// so the child its resize policy keeps the size of stays the same size.
+    fn apply_resize_policy(&mut self, node_index: NodeIndex, previous: Rect) {
+        let (rect, fraction) = match &self.tree[node_index.0] {
+            Node::Horizontal { rect, fraction } | Node::Vertical { rect, fraction } => {
+                (*rect, *fraction)
+            }
+            _ => return,
+        };
+        let resized = self.resized_fraction(node_index, fraction, previous, rect);
+        if let Node::Horizontal { fraction, .. } | Node::Vertical { fraction, .. } =
+            &mut self.tree[node_index.0]
+        {
+            *fraction = resized;
+        }
+    }
+
+    /// The fraction the parent node at `node_index` gets from its resize policy when it goes from `previous` to
+    /// `rect` with `fraction`.
+    fn resized_fraction(
+        &self,
+        node_index: NodeIndex,
+        fraction: f32,
+        previous: Rect,
+        rect: Rect,
+    ) -> f32 {
+        let policy = self.resize_policy(node_index);
+        let (before, after) = if self.tree[node_index.0].is_horizontal() {
+            (previous.width(), rect.width())
+        } else {
+            (previous.height(), rect.height())
+        };
+        if policy == ResizePolicy::Proportional || after <= 0.0 || before == after {
+            return fraction;
+        }
+        match policy {
+            ResizePolicy::KeepFirst => fraction * before / after,
+            ResizePolicy::KeepSecond => 1.0 - (1.0 - fraction) * before / after,
+            ResizePolicy::Proportional => fraction,
+        }
+        .clamp(0.0, 1.0)
+    }
+
+    /// Puts the layout aside, leaving only the focused leaf, or only its active tab, depending on `mode`. Calling it
+    /// again while in zen mode switches to the other `mode`.
+    ///
+    /// [`Tree::exit_zen_mode`] brings the layout back, with the leaf as it was changed in the meantime. The undo
+    /// history is cleared when entering and leaving zen mode.
+    ///
+    /// Returns `false` if no leaf is focused, `true` otherwise.
+    pub fn enter_zen_mode(&mut self, mode: ZenMode) -> bool {
+        if let Some(zen) = &mut self.zen {
+            zen.mode = mode;
+            return true;
+        }
+        let leaf = match self.focused_node {
+            Some(leaf) if self.tree.get(leaf.0).map_or(false, Node::is_leaf) => leaf,
+            _ => return false,
+        };
+
+        // The leaf keeps its index and id as the root of the tree shown meanwhile, the slots before it are free.
+        let mut tree = Vec::new();
+        tree.resize_with(leaf.0, || Node::Empty);
+        tree.push(std::mem::replace(&mut self[leaf], Node::Empty));
+        let mut slots = vec![Slot::default(); tree.len()];
+        slots[leaf.0].generation = self.slots[leaf.0].generation;
+        let layout = Snapshot {
+            tree: std::mem::replace(&mut self.tree, tree),
+            slots: std::mem::replace(&mut self.slots, slots),
+            root: self.root.replace(leaf),
+            free: std::mem::replace(&mut self.free, (0..leaf.0).map(NodeIndex).collect()),
+            focused_node: self.focused_node,
+        };
+        self.zen = Some(Box::new(Zen { mode, layout, leaf }));
+        self.clear_history();
+        true
+    }
+
+    /// Brings back the layout put aside by [`Tree::enter_zen_mode`].
+    ///
+    /// The tabs shown in zen mode go back into the leaf they came from, even if they were split off into other
+    /// leaves in the meantime. Returns `false` if the tree wasn't in zen mode, `true` otherwise.
+    pub fn exit_zen_mode(&mut self) -> bool {
+        let zen = match self.zen.take() {
+            Some(zen) => zen,
+            None => return false,
+        };
+        let mut order = Vec::new();
+        self.top_down(&mut order);
+        let mut nodes = std::mem::replace(&mut self.tree, zen.layout.tree);
+        let root = std::mem::replace(&mut self.root, zen.layout.root);
+        self.slots = zen.layout.slots;
+        self.free = zen.layout.free;
+        self.focused_node = zen.layout.focused_node;
+
+        let node = if let Some(root) = root.filter(|root| nodes[root.0].is_leaf()) {
+            std::mem::replace(&mut nodes[root.0], Node::Empty)
+        } else {
+            let tabs = order
+                .into_iter()
+                .flat_map(
+                    |node| match std::mem::replace(&mut nodes[node.0], Node::Empty) {
+                        Node::Leaf { tabs, .. } => tabs,
+                        _ => Vec::new(),
+                    },
+                )
+                .collect();
+            Node::leaf_with(tabs)
+        };
+        self[zen.leaf] = node;
+        self.remove_leaf_if_empty(zen.leaf);
+        self.clear_history();
+        true
+    }
+
+    /// Returns the mode set with [`Tree::enter_zen_mode`], or `None` if the tree isn't in zen mode.
+    pub fn zen_mode(&self) -> Option<ZenMode> {
+        self.zen.as_ref().map(|zen| zen.mode)
+    }
+
+    /// Takes the tab at `tab_index` of the leaf at `node_index` out of its tab bar, keeping it and where it was
+    /// until it's shown again with [`Tree::unhide_tab`]. Hidden tabs aren't shown at all, but keep their state.
+    ///
+    /// The leaf is removed if that was its last tab. Returns `false` if there's no such tab, `true` otherwise.
+    pub fn hide_tab(&mut self, node_index: NodeIndex, tab_index: TabIndex) -> bool {
+        let leaf = self.node_id(node_index);
+        let tab = match self.tree.get_mut(node_index.0) {
+            Some(Node::Leaf { tabs, active, .. }) if tab_index.0 < tabs.len() => {
+                if *active >= tab_index {
+                    active.0 = active.0.saturating_sub(1);
+                }
+                tabs.remove(tab_index.0)
+            }
+            _ => return false,
+        };
+        self.hidden.push(HiddenTab {
+            tab,
+            leaf,
+            index: tab_index,
+        });
+        self.remove_leaf_if_empty(node_index);
+        true
+    }
+
+    /// Puts the hidden tab at `index` in [`Tree::hidden_tabs`] back where it was and activates it, or into the
+    /// focused leaf if its leaf no longer exists.
+    ///
+    /// Returns where the tab was put, or `None` if there's no hidden tab at `index`.
+    pub fn unhide_tab(&mut self, index: usize) -> Option<(NodeIndex, TabIndex)> {
+        if index >= self.hidden.len() {
+            return None;
+        }
+        let hidden = self.hidden.remove(index);
+        let leaf = hidden
+            .leaf
+            .and_then(|leaf| self.node_index(leaf))
+            .filter(|&leaf| self[leaf].is_leaf());
+        match leaf {
+            Some(leaf) => {
+                let tab_index = TabIndex(hidden.index.0.min(self[leaf].tabs_count()));
+                self[leaf].insert_tab(tab_index, hidden.tab);
+                self.set_focused_node(leaf);
+                Some((leaf, tab_index))
+            }
+            None => {
+                self.push_to_focused_leaf(hidden.tab);
+                let leaf = self.focused_leaf()?;
+                Some((leaf, TabIndex(self[leaf].tabs_count() - 1)))
+            }
+        }
+    }
+
+    /// Finds the first tab `predicate` returns `true` for, like the console tab to focus when a build fails:
+    ///
+    /// ```rust
+    /// # use egui_dock::Tree;
+    /// let mut tree = Tree::new(vec!["Editor", "Console"]);
+    /// if let Some((node, tab)) = tree.find_tab_by(|tab| tab.starts_with("Con")) {
+    ///     tree.set_focused_tab(node, tab);
+    /// }
+    /// # assert_eq!(tree.find_active().map(|(_, tab)| *tab), Some("Console"));
+    /// ```
+    ///
+    /// Returns which leaf the tab is in and where in that leaf it is. Hidden tabs aren't searched.
+    pub fn find_tab_by(
+        &self,
+        mut predicate: impl FnMut(&Tab) -> bool,
+    ) -> Option<(NodeIndex, TabIndex)> {
+        self.tabs()
+            .find(|(_, _, tab)| predicate(tab))
+            .map(|(node_index, tab_index, _)| (node_index, tab_index))
+    }
+
+    /// Returns an iterator over the tabs hidden with [`Tree::hide_tab`], in the order they were hidden.
+    pub fn hidden_tabs(&self) -> impl Iterator<Item = &Tab> + '_ {
+        self.hidden.iter().map(|hidden| &hidden.tab)
+    }
+
+    /// Returns a mutable iterator over the tabs hidden with [`Tree::hide_tab`], in the order they were hidden.
+    pub fn hidden_tabs_mut(&mut self) -> impl Iterator<Item = &mut Tab> + '_ {
+        self.hidden.iter_mut().map(|hidden| &mut hidden.tab)
+    }
+}
+
+impl<Tab> Tree<Tab>
+where
+    Tab: PartialEq,
+{
+    /// Hides the given tab if it's shown, or shows it again if it's hidden, like a View menu toggling a tool panel.
+    ///
+    /// Returns `true` if the tab is shown afterwards, `false` if it's hidden or wasn't found at all.
+    pub fn toggle_tab_hidden(&mut self, needle_tab: &Tab) -> bool {
+        if let Some((node_index, tab_index)) = self.find_tab(needle_tab) {
+            self.hide_tab(node_index, tab_index);
+            return false;
+        }
+        match self
+            .hidden
+            .iter()
+            .position(|hidden| hidden.tab == *needle_tab)
+        {
+            Some(index) => self.unhide_tab(index).is_some(),
+            None => false,
+        }
+    }
+
+    /// Find the given tab.
+    ///
+    /// Returns which node the tab is in, and where in that node the tab is in.
+    ///
+    /// The returned [`NodeIndex`] will always point to a [`Node::Leaf`].
+    ///
+    /// In case there are several hits, only the first is returned.
+    pub fn find_tab(&self, needle_tab: &Tab) -> Option<(NodeIndex, TabIndex)> {
+        self.find_tab_by(|tab| tab == needle_tab)
+    }
+}
+
+/// Clamps the `fraction` of a parent node `length` long along its split so that its children are at least `first`
+/// and `second` long, the first being the one `fraction` applies to.
+fn clamp_fraction(
+    fraction: f32,
+    length: f32,
+    first: f32,
+    second: f32,
+    separator_width: f32,
+) -> f32 {
+    if (first <= 0.0 && second <= 0.0) || length <= 0.0 {
+        return fraction;
+    }
+    let half_separator = separator_width * 0.5;
+    let min = (first + half_separator) / length;
+    let max = 1.0 - (second + half_separator) / length;
+    if min <= max {
+        fraction.clamp(min, max)
+    } else {
+        (first + half_separator) / (first + second + separator_width)
+    }
+}
+
+/// Divides the `rect` of a parent node according to its `fraction`.
+///
+/// Returns the rects of its left child, the separator and its right child.
+fn split_rect(
+    rect: Rect,
+    fraction: f32,
+    is_horizontal: bool,
+    separator_width: f32,
+    pixels_per_point: f32,
+) -> (Rect, Rect, Rect) {
+    let rect = expand_to_pixel(rect, pixels_per_point);
+    let half_width = separator_width * 0.5;
+
+    if is_horizontal {
+        let midpoint = rect.min.x + rect.width() * fraction;
+        let separator = Rect::from_x_y_ranges(
+            map_to_pixel(midpoint - half_width, pixels_per_point, f32::round)
+                ..=map_to_pixel(midpoint + half_width, pixels_per_point, f32::round),
+            rect.y_range(),
+        );
+        (
+            rect.intersect(Rect::everything_right_of(separator.max.x)),
+            separator,
+            rect.intersect(Rect::everything_left_of(separator.min.x)),
+        )
+    } else {
+        let midpoint = rect.min.y + rect.height() * fraction;
+        let separator = Rect::from_x_y_ranges(
+            rect.x_range(),
+            map_to_pixel(midpoint - half_width, pixels_per_point, f32::round)
+                ..=map_to_pixel(midpoint + half_width, pixels_per_point, f32::round),
+        );
+        (
+            rect.intersect(Rect::everything_above(separator.min.y)),
+            separator,
+            rect.intersect(Rect::everything_below(separator.max.y)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaf_tabs<'a>(tree: &Tree<&'a str>, node: NodeIndex) -> Vec<&'a str> {
+        tree.tabs()
+            .filter(|(leaf, ..)| *leaf == node)
+            .map(|(.., tab)| *tab)
+            .collect()
+    }
+
+    #[test]
+    fn node_indices_stay_valid_across_remove_empty_leaf() {
+        let mut tree = Tree::new(vec!["editor"]);
+        let [editor, console] = tree.split_below(tree.root().unwrap(), 0.5, vec!["console"]);
+        let [console, outline] = tree.split_right(console, 0.5, vec!["outline"]);
+        let split = tree.parent(console).unwrap();
+        let (editor_id, console_id) = (tree.node_id(editor).unwrap(), tree.node_id(console));
+
+        tree[editor].remove_tab(TabIndex(0));
+        tree.remove_empty_leaf();
+        assert_eq!(tree.node_index(editor_id), None);
+
+        // The split holding both leaves took the place of the root, without moving any node.
+        assert_eq!(tree.root(), Some(split));
+        assert_eq!(tree.parent(split), None);
+        assert_eq!(tree.children(split), Some([outline, console]));
+        assert_eq!(leaf_tabs(&tree, console), ["console"]);
+        assert_eq!(leaf_tabs(&tree, outline), ["outline"]);
+        assert_eq!(tree.node_id(console), console_id);
+
+        // Nodes added later reuse the freed slots, but not the ids of the nodes which were there.
+        let [_, terminal] = tree.split_below(console, 0.5, vec!["terminal"]);
+        assert_eq!(tree.len(), 5);
+        assert_eq!(tree.node_index(editor_id), None);
+        assert_eq!(leaf_tabs(&tree, terminal), ["terminal"]);
+        assert_eq!(tree.node_index(console_id.unwrap()), Some(console));
+    }
+
+    #[test]
+    fn remove_tab_keeps_other_empty_leaves() {
+        let mut tree = Tree::new(vec!["editor"]);
+        let [editor, console] = tree.split_below(tree.root().unwrap(), 0.5, vec!["console"]);
+        let [console, outline] = tree.split_right(console, 0.5, Vec::<&str>::new());
+
+        assert_eq!(tree.remove_tab(console, TabIndex(0)), Some("console"));
+        assert_eq!(tree.node_id(console), None);
+        assert_eq!(tree.children(tree.root().unwrap()), Some([editor, outline]));
+        assert_eq!(tree[outline].tabs_count(), 0);
+
+        assert_eq!(tree.remove_leaf(outline), Some(Vec::new()));
+        assert_eq!(tree.root(), Some(editor));
+        assert_eq!(tree.remove_leaf(outline), None);
+    }
+
+    #[test]
+    fn undo_and_redo_a_split() {
+        let mut tree = Tree::new(vec!["editor", "terminal"]);
+        tree.set_history_capacity(10);
+        assert!(!tree.undo());
+
+        tree.record_history();
+        let [editor, console] = tree.split_below(tree.root().unwrap(), 0.5, vec!["console"]);
+        tree.record_history();
+        tree.split_right(console, 0.5, vec!["outline"]);
+        assert_eq!(tree.tabs().count(), 4);
+
+        assert!(tree.undo());
+        assert_eq!(leaf_tabs(&tree, editor), ["editor", "terminal"]);
+        assert_eq!(leaf_tabs(&tree, console), ["console"]);
+        assert!(tree.undo());
+        assert!(tree[tree.root().unwrap()].is_leaf());
+        assert_eq!(
+            leaf_tabs(&tree, tree.root().unwrap()),
+            ["editor", "terminal"]
+        );
+        assert!(!tree.can_undo());
+
+        assert!(tree.redo());
+        assert_eq!(leaf_tabs(&tree, console), ["console"]);
+        assert!(tree.redo());
+        assert_eq!(tree.tabs().count(), 4);
+        assert!(!tree.redo());
+
+        // Changing the layout after an undo drops what could've been redone.
+        assert!(tree.undo());
+        tree.record_history();
+        tree.split_left(console, 0.5, vec!["symbols"]);
+        assert!(!tree.can_redo());
+    }
+
+    #[test]
+    fn unhide_tab_finds_its_leaf_after_others_are_removed() {
+        let mut tree = Tree::new(vec!["editor"]);
+        let [editor, console] = tree.split_below(tree.root().unwrap(), 0.5, vec!["console"]);
+        let [_, outline] = tree.split_right(console, 0.5, vec!["outline", "symbols", "calls"]);
+
+        assert!(tree.hide_tab(outline, TabIndex(1)));
+        assert_eq!(leaf_tabs(&tree, outline), ["outline", "calls"]);
+        assert_eq!(tree.hidden_tabs().collect::<Vec<_>>(), [&"symbols"]);
+
+        tree[editor].remove_tab(TabIndex(0));
+        tree.remove_empty_leaf();
+        assert_eq!(tree.unhide_tab(0), Some((outline, TabIndex(1))));
+        assert_eq!(leaf_tabs(&tree, outline), ["outline", "symbols", "calls"]);
+        assert_eq!(tree.focused_leaf(), Some(outline));
+        assert_eq!(tree.hidden_tabs().count(), 0);
+        assert_eq!(tree.unhide_tab(0), None);
+
+        // Tabs of a leaf which is gone go to the focused leaf instead.
+        assert!(tree.hide_tab(outline, TabIndex(0)));
+        for _ in 0..2 {
+            tree[outline].remove_tab(TabIndex(0));
+        }
+        tree.remove_empty_leaf();
+        assert_eq!(tree.unhide_tab(0), Some((console, TabIndex(1))));
+        assert_eq!(leaf_tabs(&tree, console), ["console", "outline"]);
+    }
+
+    #[test]
+    fn zen_mode_puts_the_layout_back() {
+        let mut tree = Tree::new(vec!["editor"]);
+        let [editor, console] = tree.split_below(tree.root().unwrap(), 0.75, vec!["console"]);
+        let [console, outline] = tree.split_right(console, 0.5, vec!["outline"]);
+
+        tree.set_focused_node(console);
+        assert!(tree.enter_zen_mode(ZenMode::FocusedLeaf));
+        assert_eq!(tree.zen_mode(), Some(ZenMode::FocusedLeaf));
+        assert_eq!(tree.tabs().count(), 1);
+        assert_eq!(tree.len(), 1);
+        assert_eq!(tree.root(), Some(console));
+        assert_eq!(leaf_tabs(&tree, console), ["console"]);
+        assert!(tree.enter_zen_mode(ZenMode::ActiveTab));
+        assert_eq!(tree.zen_mode(), Some(ZenMode::ActiveTab));
+
+        // Tabs opened in zen mode stay in the leaf when the layout comes back, even if they were split off.
+        tree.push_to_focused_leaf("terminal");
+        tree.split_below(console, 0.5, vec!["symbols"]);
+        assert!(tree.exit_zen_mode());
+        assert_eq!(tree.zen_mode(), None);
+        let root = tree.root().unwrap();
+        assert!(matches!(tree[root], Node::Vertical { fraction, .. } if fraction == 0.75));
+        assert_eq!(leaf_tabs(&tree, editor), ["editor"]);
+        assert_eq!(
+            leaf_tabs(&tree, console),
+            ["console", "terminal", "symbols"]
+        );
+        assert_eq!(leaf_tabs(&tree, outline), ["outline"]);
+        assert_eq!(tree.focused_leaf(), Some(console));
+        assert_eq!(tree.len(), 5);
+        assert!(!tree.exit_zen_mode());
+    }
+
+    #[test]
+    fn simplify_merges_nested_splits() {
+        let mut tree = Tree::new(vec!["a"]);
+        let [a, b] = tree.split_right(tree.root().unwrap(), 0.5, vec!["b"]);
+        let [a, c] = tree.split_right(a, 0.5, vec!["c"]);
+        let rect = Rect::from_min_size(Pos2::ZERO, vec2(400.0, 100.0));
+        let style = Style {
+            separator_width: 0.0,
+            ..Style::default()
+        };
+        let before = tree.solve_layout(rect, &style, 1.0);
+        let root = tree.root().unwrap();
+        assert!(tree.nested_split(root).is_some());
+
+        assert!(tree.simplify());
+        assert_eq!(tree.root(), Some(root));
+        assert_eq!(tree.nested_split(root), None);
+        let [first, rest] = tree.shown_children(root).unwrap();
+        assert_eq!(first, a);
+        assert_eq!(tree.shown_children(rest), Some([c, b]));
+        let after = tree.solve_layout(rect, &style, 1.0);
+        for leaf in [a, b, c] {
+            assert!((before[leaf.0].width() - after[leaf.0].width()).abs() <= 1.0);
+        }
+        assert!(!tree.simplify());
+    }
+
+    #[test]
+    fn hit_test_uses_the_tab_bars_shown() {
+        let mut tree = Tree::new(vec!["a", "b"]);
+        let leaf = tree.root().unwrap();
+        let rect = Rect::from_min_size(Pos2::ZERO, vec2(400.0, 400.0));
+        let style = Style::default();
+        let pos = pos2(200.0, 45.0);
+        tree[leaf].set_rect(rect);
+
+        // Below a single row, the pointer is closest to the top of the leaf.
+        tree.begin_geometry(rect);
+        let row = Rect::from_min_size(Pos2::ZERO, vec2(400.0, style.tab_bar_height()));
+        tree.set_tab_bar_rect(leaf, row);
+        let (_, target, _) = tree.hit_test(&style, pos).unwrap();
+        assert_eq!(target, DropTarget::Split(Split::Above));
+
+        // Tabs wrapped onto more rows make the tab bar taller.
+        tree.begin_geometry(rect);
+        let rows = Rect::from_min_size(Pos2::ZERO, vec2(400.0, 60.0));
+        tree.set_tab_bar_rect(leaf, rows);
+        assert_eq!(
+            tree.hit_test(&style, pos),
+            Some((leaf, DropTarget::Tabs(None), rows))
+        );
+    }
+
+    #[test]
+    fn solve_layout_shares_out_the_rect() {
+        let mut tree = Tree::new(vec!["editor"]);
+        let [editor, console] = tree.split_below(tree.root().unwrap(), 0.25, vec!["console"]);
+        let rect = Rect::from_min_size(Pos2::ZERO, vec2(400.0, 400.0));
+        let style = Style {
+            separator_width: 2.0,
+            ..Style::default()
+        };
+
+        let rects = tree.solve_layout(rect, &style, 1.0);
+        assert_eq!(rects[tree.root().unwrap().0], rect);
+        assert_eq!(
+            rects[editor.0],
+            Rect::from_min_max(pos2(0.0, 0.0), pos2(400.0, 99.0))
+        );
+        assert_eq!(
+            rects[console.0],
+            Rect::from_min_max(pos2(0.0, 101.0), pos2(400.0, 400.0))
+        );
+
+        // The tree itself is left alone, only `Tree::layout` assigns the rects.
+        assert_eq!(tree[editor].rect(), Some(Rect::NOTHING));
+    }
+
+    #[test]
+    fn solve_layout_leaves_out_the_padding() {
+        let mut tree = Tree::new(vec!["editor"]);
+        let [editor, console] = tree.split_below(tree.root().unwrap(), 0.5, vec!["console"]);
+        let rect = Rect::from_min_size(Pos2::ZERO, vec2(400.0, 400.0));
+        let style = Style {
+            padding: Some(egui::style::Margin::same(10.0)),
+            ..Style::default()
+        };
+
+        let rects = tree.solve_layout(rect, &style, 1.0);
+        assert_eq!(rects[tree.root().unwrap().0], rect.shrink(10.0));
+        assert!(rects[editor.0].max.y <= rects[console.0].min.y);
+        assert_eq!(rects[console.0].max.y, 390.0);
+    }
+
+    #[test]
+    fn solve_layout_keeps_min_node_sizes() {
+        let mut tree = Tree::new(vec!["editor"]);
+        let [editor, console] = tree.split_below(tree.root().unwrap(), 0.05, vec!["console"]);
+        let [console, outline] = tree.split_right(console, 0.5, vec!["outline"]);
+        let rect = Rect::from_min_size(Pos2::ZERO, vec2(400.0, 400.0));
+        let style = Style {
+            min_node_size: vec2(0.0, 100.0),
+            ..Style::default()
+        };
+
+        let rects = tree.solve_layout(rect, &style, 1.0);
+        assert!(rects[editor.0].height() >= 100.0 - 1.0);
+        assert!(rects[console.0].height() >= 100.0);
+
+        assert!(tree.set_min_size(outline, vec2(300.0, 0.0)));
+        assert_eq!(tree.min_size(outline), vec2(300.0, 0.0));
+        let rects = tree.solve_layout(rect, &style, 1.0);
+        assert!(rects[outline.0].width() >= 300.0 - 1.0);
+        assert!(rects[console.0].width() < 100.0);
+    }
+
+    #[test]
+    fn solve_layout_applies_resize_policies() {
+        let style = Style {
+            separator_width: 2.0,
+            ..Style::default()
+        };
+        let mut tree = Tree::new(vec!["editor"]);
+        let [_, console] = tree.split_below(tree.root().unwrap(), 0.75, vec!["console"]);
+        assert!(tree.set_resize_policy(tree.root().unwrap(), ResizePolicy::KeepSecond));
+        tree.layout(
+            Rect::from_min_size(Pos2::ZERO, vec2(800.0, 600.0)),
+            2.0,
+            1.0,
+        );
+        let height = tree[console].rect().unwrap().height();
+
+        // The dock lays the tree out the same way, keeping the console as high as the dock area grows.
+        let taller = Rect::from_min_size(Pos2::ZERO, vec2(800.0, 900.0));
+        let rects = tree.solve_layout(taller, &style, 1.0);
+        assert!((rects[console.0].height() - height).abs() < 1.0);
+        tree.layout(taller, 2.0, 1.0);
+        assert_eq!(tree[console].rect(), Some(rects[console.0]));
+    }
+
+    #[test]
+    fn solve_layout_applies_fixed_sizes() {
+        let style = Style {
+            separator_width: 2.0,
+            ..Style::default()
+        };
+        let rect = Rect::from_min_size(Pos2::ZERO, vec2(800.0, 600.0));
+        let mut tree = Tree::new(vec!["documents"]);
+        let [_, tools] = tree.split_left(tree.root().unwrap(), 0.25, vec!["tools"]);
+        tree.layout(rect, 2.0, 1.0);
+
+        assert!(tree.set_fixed_size(tree.root().unwrap(), ResizePolicy::KeepFirst, 250.0));
+        let rects = tree.solve_layout(rect, &style, 1.0);
+        assert!((rects[tools.0].width() - 250.0).abs() < 2.0);
+
+        // The dock lays the tree out the same way, and keeps the size as the dock area grows.
+        tree.layout(rect, 2.0, 1.0);
+        assert_eq!(tree[tools].rect(), Some(rects[tools.0]));
+        let wider = Rect::from_min_size(Pos2::ZERO, vec2(1200.0, 600.0));
+        let rects = tree.solve_layout(wider, &style, 1.0);
+        assert!((rects[tools.0].width() - 250.0).abs() < 2.0);
+        tree.layout(wider, 2.0, 1.0);
+        assert_eq!(tree[tools].rect(), Some(rects[tools.0]));
+    }
+
+    #[test]
+    fn collapsed_leaves_keep_their_tab_bar() {
+        let style = Style::default();
+        let rect = Rect::from_min_size(Pos2::ZERO, vec2(800.0, 600.0));
+
+        let mut tree = Tree::new(vec!["editor"]);
+        let [_, console] = tree.split_below(tree.root().unwrap(), 0.5, vec!["console"]);
+        assert!(tree.set_collapsed(console, true));
+        let rects = tree.solve_layout(rect, &style, 1.0);
+        assert!((rects[console.0].height() - style.tab_bar_height()).abs() < 2.0);
+
+        // Leaves beside their sibling collapse to the width of a vertical tab bar, and come back as they were.
+        let mut tree = Tree::new(vec!["editor"]);
+        let [_, outline] = tree.split_right(tree.root().unwrap(), 0.75, vec!["outline"]);
+        assert!(tree.set_collapsed(outline, true));
+        assert!(tree.is_collapsed(outline));
+        let rects = tree.solve_layout(rect, &style, 1.0);
+        assert!((rects[outline.0].width() - style.vertical_tab_bar_width).abs() < 2.0);
+        assert_eq!(rects[outline.0].height(), 600.0);
+        // Their tab bar runs along the strip, on the edge of the dock area.
+        assert_eq!(
+            tree.shown_tab_bar_position(outline, &style),
+            TabBarPosition::Right
+        );
+
+        assert!(tree.set_collapsed(outline, false));
+        let rects = tree.solve_layout(rect, &style, 1.0);
+        assert!((rects[outline.0].width() - 200.0).abs() < 2.0);
+        assert_eq!(
+            tree.shown_tab_bar_position(outline, &style),
+            TabBarPosition::Top
+        );
+    }
+
+    /// The layout stored by `DockArea::persist_layout` goes through egui's memory and comes back the same.
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn persisted_layout_round_trips() {
+        let mut tree = Tree::new(vec![Id::new("files"), Id::new("editor")]);
+        let [files, outline] =
+            tree.split_right(tree.root().unwrap(), 0.75, vec![Id::new("outline")]);
+        let outline_id = tree.node_id(outline);
+
+        let ctx = Context::default();
+        ctx.data().insert_persisted(Id::new("layout"), tree);
+        let saved = ron::to_string(&*ctx.memory()).unwrap();
+        *ctx.memory() = ron::from_str(&saved).unwrap();
+
+        let restored = ctx
+            .data()
+            .get_persisted::<Tree<Id>>(Id::new("layout"))
+            .unwrap();
+        assert_eq!(restored.tabs().count(), 3);
+        assert_eq!(
+            restored.find_tab(&Id::new("editor")),
+            Some((files, TabIndex(1)))
+        );
+        assert_eq!(
+            restored.find_tab(&Id::new("outline")),
+            Some((outline, TabIndex(0)))
+        );
+        assert_eq!(restored.node_id(outline), outline_id);
+    }
+
+    #[test]
+    fn split_root_moves_the_tree_under_the_old_root() {
+        let mut tree = Tree::new(vec!["editor"]);
+        let [editor, outline] = tree.split_right(tree.root().unwrap(), 0.75, vec!["outline"]);
+        let old_root = tree.root().unwrap();
+
+        let [old, new] = tree.split_root(Split::Below, 0.8, Node::leaf("console"));
+        let root = tree.root().unwrap();
+        assert_eq!(old, old_root);
+        assert_eq!(tree.children(root), Some([old, new]));
+        assert!(matches!(tree[root], Node::Vertical { fraction, .. } if fraction == 0.8));
+        assert!(matches!(tree[old], Node::Horizontal { .. }));
+        assert_eq!(leaf_tabs(&tree, new), ["console"]);
+
+        // The old leaves moved a level down, keeping their indices.
+        assert_eq!(tree.parent(editor), Some(old));
+        assert_eq!(tree.parent(outline), Some(old));
+        assert_eq!(leaf_tabs(&tree, editor), ["editor"]);
+        assert_eq!(leaf_tabs(&tree, outline), ["outline"]);
+    }
+
+    #[test]
+    fn drop_tab_reorders_within_a_leaf() {
+        let mut tree = Tree::new(vec!["a", "b", "c", "d"]);
+        let root = tree.root().unwrap();
+
+        // Targets are indices from before the tab was taken out.
+        let moved = tree.drop_tab(
+            (root, TabIndex(0)),
+            root,
+            DropTarget::Tabs(Some(TabIndex(3))),
+        );
+        assert_eq!(moved, Some((root, None)));
+        assert_eq!(leaf_tabs(&tree, root), ["b", "c", "a", "d"]);
+
+        tree.drop_tab((root, TabIndex(0)), root, DropTarget::Tabs(None));
+        assert_eq!(leaf_tabs(&tree, root), ["c", "a", "d", "b"]);
+
+        tree.drop_tab(
+            (root, TabIndex(3)),
+            root,
+            DropTarget::Tabs(Some(TabIndex(1))),
+        );
+        assert_eq!(leaf_tabs(&tree, root), ["c", "b", "a", "d"]);
+        assert_eq!(tree.find_tab(&"b"), Some((root, TabIndex(1))));
+    }
+
+    #[test]
+    fn drop_tab_removes_the_leaf_it_empties() {
+        let mut tree = Tree::new(vec!["editor"]);
+        let [editor, console] = tree.split_below(tree.root().unwrap(), 0.5, vec!["console"]);
+
+        let (dst, removed) = tree
+            .drop_tab((console, TabIndex(0)), editor, DropTarget::Tabs(None))
+            .unwrap();
+        assert_eq!(removed, Some(console));
+        assert_eq!(dst, editor);
+        assert_eq!(tree.root(), Some(editor));
+        assert_eq!(leaf_tabs(&tree, dst), ["editor", "console"]);
+        assert_eq!(
+            tree.drop_tab((console, TabIndex(0)), dst, DropTarget::Tabs(None)),
+            None
+        );
     }
 }
@@ -18,3 +18,37 @@ pub fn map_to_pixel_pos(mut pos: Pos2, ppi: f32, map: fn(f32) -> f32) -> egui::P
 pub fn map_to_pixel(point: f32, ppi: f32, map: fn(f32) -> f32) -> f32 {
     map(point * ppi) / ppi
 }
+
+#[inline(always)]
+pub fn round_to_pixel(mut rect: Rect, ppi: f32) -> egui::Rect {
+    rect.min = map_to_pixel_pos(rect.min, ppi, f32::round);
+    rect.max = map_to_pixel_pos(rect.max, ppi, f32::round);
+    rect
+}
+
+/// Rounds `width` to whole physical pixels, at least one, so lines don't get blurred across pixels.
+#[inline(always)]
+pub fn stroke_width_to_pixel(width: f32, ppi: f32) -> f32 {
+    (width * ppi).round().max(1.0) / ppi
+}
+
+/// Snaps `rect` so a stroke `width` points wide painted along its edges covers whole physical pixels, returning the
+/// snapped rect and the width rounded with [`stroke_width_to_pixel`].
+///
+/// Strokes are centered on the edges, which have to be in the middle of pixels for strokes an odd number of pixels
+/// wide.
+pub fn stroke_rect_to_pixel(rect: Rect, width: f32, ppi: f32) -> (egui::Rect, f32) {
+    let width = stroke_width_to_pixel(width, ppi);
+    let rect = round_to_pixel(rect, ppi);
+    if (width * ppi).round() as u32 % 2 == 1 {
+        (rect.shrink(0.5 / ppi), width)
+    } else {
+        (rect, width)
+    }
+}
+
+/// Snaps `pos` to the middle of a physical pixel, where lines an odd number of pixels wide are crisp.
+#[inline(always)]
+pub fn pixel_center(pos: Pos2, ppi: f32) -> egui::Pos2 {
+    map_to_pixel_pos(pos, ppi, f32::floor) + Vec2::splat(0.5 / ppi)
+}